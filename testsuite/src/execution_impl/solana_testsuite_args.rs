@@ -1,7 +1,89 @@
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
 
 #[derive(Deserialize, Serialize)]
 pub struct SolanaTestsuiteArgs {
+    // The image that every node runs, unless overridden by the more specific images below
     #[serde(rename = "normalImage")]
     pub normal_image: String,
-}
\ No newline at end of file
+
+    #[serde(rename = "faucetImage", default)]
+    pub faucet_image: Option<String>,
+
+    #[serde(rename = "validatorImage", default)]
+    pub validator_image: Option<String>,
+
+    // Images for individual bootstrappers, keyed by bootstrapper index (e.g. {"0": "solanalabs/solana:v1.5.11"}), to run
+    // a cluster with mixed versions; the other bootstrappers run the validator image
+    #[serde(rename = "bootstrapperImages", default)]
+    pub bootstrapper_images: HashMap<usize, String>,
+
+    // The image that the rolling-upgrade test moves the validators onto, one at a time; that test only runs if this is set
+    #[serde(rename = "upgradeImage", default)]
+    pub upgrade_image: Option<String>,
+
+    // How many of the genesis bootstrappers to start; defaults to all of them
+    #[serde(rename = "numBootstrappers", default)]
+    pub num_bootstrappers: Option<usize>,
+
+    // How many non-staked, non-voting RPC nodes to run in front of the bootstrappers
+    #[serde(rename = "numRpcNodes", default)]
+    pub num_rpc_nodes: usize,
+
+    // Path (inside the testsuite container) to the genesis config JSON file written by the 'genesis' subcommand
+    #[serde(rename = "genesisConfigFilepath", default = "get_default_genesis_config_filepath")]
+    pub genesis_config_filepath: String,
+
+    // URL of the gzipped genesis ledger (the 'test-ledger.tgz' written by the 'genesis' subcommand) that the bootstrappers
+    // start from
+    #[serde(rename = "ledgerArtifactUrl", default)]
+    pub ledger_artifact_url: Option<String>,
+
+    // Path (inside the testsuite container) to a gzipped genesis ledger to serve to the nodes from the testsuite itself,
+    // for running without internet access; mutually exclusive with ledgerArtifactUrl
+    #[serde(rename = "ledgerArtifactFilepath", default)]
+    pub ledger_artifact_filepath: Option<PathBuf>,
+
+    // How many accounts the throughput test funds from the faucet and sends transfers between
+    #[serde(rename = "throughputNumAccounts", default = "get_default_throughput_num_accounts")]
+    pub throughput_num_accounts: usize,
+
+    // The rate at which the throughput test sends transfers, spread across the bootstrappers
+    #[serde(rename = "throughputTargetTps", default = "get_default_throughput_target_tps")]
+    pub throughput_target_tps: u64,
+
+    // The throughput test fails if transfers get confirmed at a lower rate than this
+    #[serde(rename = "throughputMinTps", default = "get_default_throughput_min_tps")]
+    pub throughput_min_tps: u64,
+
+    // How long the throughput test sends transfers for
+    #[serde(rename = "throughputDurationSeconds", default = "get_default_throughput_duration_seconds")]
+    pub throughput_duration_seconds: u64,
+}
+
+// The genesis config that gets baked into the testsuite image (see the Dockerfile)
+fn get_default_genesis_config_filepath() -> String {
+    return String::from("/genesis-config.json");
+}
+
+fn get_default_throughput_num_accounts() -> usize {
+    return 16;
+}
+
+fn get_default_throughput_target_tps() -> u64 {
+    return 100;
+}
+
+// Leaves plenty of headroom below the target, since the nodes all share the one Docker host
+fn get_default_throughput_min_tps() -> u64 {
+    return 50;
+}
+
+fn get_default_throughput_duration_seconds() -> u64 {
+    return 60;
+}
+
+// The ledger that goes with the genesis config that's checked into the repo
+pub fn get_default_ledger_artifact_url() -> String {
+    return String::from("https://kurtosis-public-access.s3.us-east-1.amazonaws.com/client-artifacts/solana/test-ledger.tgz");
+}
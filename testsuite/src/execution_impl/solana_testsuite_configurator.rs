@@ -1,13 +1,14 @@
-use std::str::FromStr;
+use std::{rc::Rc, str::FromStr, time::Duration};
 
-use anyhow::Context;
+use anyhow::{Context, anyhow};
 use kurtosis_rust_lib::execution::test_suite_configurator::TestSuiteConfigurator;
 use log::LevelFilter;
 use simplelog::{ConfigBuilder, TermLogger};
 
-use crate::testsuite_impl::solana_testsuite::SolanaTestsuite;
+use crate::networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, load_generator::LoadGeneratorConfig, solana_network_config::SolanaNetworkConfig};
+use crate::testsuite_impl::solana_testsuite::{NETWORK_WIDTH_BITS, SolanaTestsuite};
 
-use super::solana_testsuite_args::SolanaTestsuiteArgs;
+use super::solana_testsuite_args::{SolanaTestsuiteArgs, get_default_ledger_artifact_url};
 
 pub struct SolanaTestsuiteConfigurator {}
 
@@ -32,7 +33,135 @@ impl TestSuiteConfigurator for SolanaTestsuiteConfigurator {
     fn parse_params_and_create_suite(&self, params_json_str: &str) -> anyhow::Result<Box<dyn kurtosis_rust_lib::testsuite::testsuite::TestSuite>> {
         let args: SolanaTestsuiteArgs = serde_json::from_str(params_json_str)
             .context("Could not deserialize params JSON string to testsuite args")?;
-        let suite = SolanaTestsuite::new(args.normal_image);
+        // Loading the genesis config verifies it, so that a broken config fails before any containers get started
+        let genesis_config = GenesisConfig::load(&args.genesis_config_filepath)
+            .context(format!("An error occurred loading the genesis config from '{}'", args.genesis_config_filepath))?;
+
+        let network_config = SolanaNetworkConfig::new(
+            args.num_bootstrappers.unwrap_or(genesis_config.get_bootstrappers().len()),
+            args.num_rpc_nodes,
+            args.faucet_image.unwrap_or(args.normal_image.clone()),
+            args.validator_image.unwrap_or(args.normal_image),
+            args.bootstrapper_images,
+            args.upgrade_image,
+        );
+        network_config.validate(&genesis_config, NETWORK_WIDTH_BITS)
+            .context(format!("The requested network doesn't fit the genesis config loaded from '{}'", args.genesis_config_filepath))?;
+
+        // The ledger only gets checked against the genesis config when a test starts the network, so that building the suite
+        // doesn't need to fetch it
+        let ledger_artifact = match (args.ledger_artifact_url, args.ledger_artifact_filepath) {
+            (Some(_), Some(_)) => return Err(anyhow!(
+                "Only one of ledgerArtifactUrl and ledgerArtifactFilepath can be set",
+            )),
+            (_, Some(filepath)) => LedgerArtifact::serve_local_file(&filepath)
+                .context(format!("An error occurred serving the ledger archive at '{}'", filepath.display()))?,
+            (url, None) => LedgerArtifact::from_url(url.unwrap_or_else(get_default_ledger_artifact_url)),
+        };
+
+        let load_config = LoadGeneratorConfig{
+            num_accounts: args.throughput_num_accounts,
+            target_tps: args.throughput_target_tps,
+            duration: Duration::from_secs(args.throughput_duration_seconds),
+        };
+        load_config.validate()
+            .context("The requested throughput test load is invalid")?;
+        if args.throughput_min_tps > load_config.target_tps {
+            return Err(anyhow!(
+                "The throughput test's minimum TPS, {}, can't be met at its target TPS of {}",
+                args.throughput_min_tps,
+                load_config.target_tps,
+            ));
+        }
+
+        let suite = SolanaTestsuite::new(Rc::new(genesis_config), Rc::new(ledger_artifact), network_config, load_config, args.throughput_min_tps);
         return Ok(Box::new(suite));
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use kurtosis_rust_lib::execution::test_suite_configurator::TestSuiteConfigurator;
+    use serde_json::json;
+
+    use super::SolanaTestsuiteConfigurator;
+
+    const CHECKED_IN_GENESIS_CONFIG_FILEPATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/genesis-config.json");
+
+    // Returns: the number of tests in the suite
+    fn parse_params(params: serde_json::Value) -> anyhow::Result<usize> {
+        let suite = SolanaTestsuiteConfigurator::new().parse_params_and_create_suite(&params.to_string())?;
+        return Ok(suite.get_tests().len());
+    }
+
+    #[test]
+    fn test_parse_params_with_cluster_shape() {
+        let num_tests = parse_params(json!({
+            "normalImage": "solanalabs/solana:v1.5.10",
+            "genesisConfigFilepath": CHECKED_IN_GENESIS_CONFIG_FILEPATH,
+        })).unwrap();
+        assert_eq!(6, num_tests);
+        let num_tests = parse_params(json!({
+            "normalImage": "solanalabs/solana:v1.5.10",
+            "validatorImage": "solanalabs/solana:v1.5.11",
+            "numBootstrappers": 7,
+            "numRpcNodes": 2,
+            "genesisConfigFilepath": CHECKED_IN_GENESIS_CONFIG_FILEPATH,
+        })).unwrap();
+        assert_eq!(6, num_tests);
+    }
+
+    #[test]
+    fn test_parse_params_with_upgrade_image_adds_rolling_upgrade_test() {
+        let num_tests = parse_params(json!({
+            "normalImage": "solanalabs/solana:v1.5.10",
+            "upgradeImage": "solanalabs/solana:v1.5.11",
+            "genesisConfigFilepath": CHECKED_IN_GENESIS_CONFIG_FILEPATH,
+        })).unwrap();
+        assert_eq!(7, num_tests);
+    }
+
+    #[test]
+    fn test_parse_params_with_bootstrapper_images_adds_mixed_version_test() {
+        let num_tests = parse_params(json!({
+            "normalImage": "solanalabs/solana:v1.5.10",
+            "bootstrapperImages": {
+                "0": "solanalabs/solana:v1.5.11",
+                "3": "solanalabs/solana:v1.5.11",
+            },
+            "genesisConfigFilepath": CHECKED_IN_GENESIS_CONFIG_FILEPATH,
+        })).unwrap();
+        assert_eq!(7, num_tests);
+    }
+
+    #[test]
+    fn test_parse_params_rejects_unreachable_throughput_floor() {
+        let err = parse_params(json!({
+            "normalImage": "solanalabs/solana:v1.5.10",
+            "throughputTargetTps": 100,
+            "throughputMinTps": 101,
+            "genesisConfigFilepath": CHECKED_IN_GENESIS_CONFIG_FILEPATH,
+        })).unwrap_err();
+        assert!(format!("{:#}", err).contains("can't be met"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_parse_params_rejects_both_ledger_artifact_sources() {
+        let err = parse_params(json!({
+            "normalImage": "solanalabs/solana:v1.5.10",
+            "ledgerArtifactUrl": "http://files.internal/test-ledger.tgz",
+            "ledgerArtifactFilepath": "/test-ledger.tgz",
+            "genesisConfigFilepath": CHECKED_IN_GENESIS_CONFIG_FILEPATH,
+        })).unwrap_err();
+        assert!(format!("{:#}", err).contains("Only one of"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_parse_params_rejects_more_bootstrappers_than_genesis_has() {
+        let err = parse_params(json!({
+            "normalImage": "solanalabs/solana:v1.5.10",
+            "numBootstrappers": 11,
+            "genesisConfigFilepath": CHECKED_IN_GENESIS_CONFIG_FILEPATH,
+        })).unwrap_err();
+        assert!(format!("{:#}", err).contains("only has keypairs for 10"), "{:#}", err);
+    }
+}
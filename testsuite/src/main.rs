@@ -1,22 +1,33 @@
 mod execution_impl;
 mod networks_impl;
+mod sdk_impl;
 mod services_impl;
 mod testsuite_impl;
 
 #[macro_use] extern crate log;
 
+use std::path::Path;
+
 use anyhow::{Context, Result};
 
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, ArgMatches};
 use execution_impl::solana_testsuite_configurator::SolanaTestsuiteConfigurator;
 use kurtosis_rust_lib::execution::test_suite_executor::TestSuiteExecutor;
+use networks_impl::{genesis_config::DEFAULT_BOOTSTRAPPER_STAKE_LAMPORTS, genesis_generator::{GENESIS_CONFIG_FILENAME, LEDGER_TGZ_FILENAME, generate_genesis}};
 
 const CUSTOM_PARAMS_JSON_FLAG: &str = "custom-params-json";
 const KURTOSIS_API_SOCKET_FLAG: &str  = "kurtosis-api-socket";
 const LOG_LEVEL_FLAG: &str = "log-level";
 
+const GENESIS_SUBCOMMAND: &str = "genesis";
+const SOLANA_CLIS_DIRPATH_FLAG: &str = "solana-clis-dir";
+const NUM_BOOTSTRAPPERS_FLAG: &str = "num-bootstrappers";
+const BOOTSTRAPPER_STAKES_FLAG: &str = "bootstrapper-stakes";
+const OUTPUT_DIRPATH_FLAG: &str = "output-dir";
+
 fn main() -> Result<()> {
     let matches = App::new("My Super Program")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(Arg::new(CUSTOM_PARAMS_JSON_FLAG)
             .long(CUSTOM_PARAMS_JSON_FLAG)
             .about("JSON string containing custom data that the testsuite will deserialize to modify runtime behaviour")
@@ -35,8 +46,38 @@ fn main() -> Result<()> {
             .required(true)
             .takes_value(true)
             .value_name("LEVEL"))
+        .subcommand(App::new(GENESIS_SUBCOMMAND)
+            .about("Generates a new genesis ledger, and the genesis config file describing it for the testsuite to load")
+            .arg(Arg::new(SOLANA_CLIS_DIRPATH_FLAG)
+                .long(SOLANA_CLIS_DIRPATH_FLAG)
+                .about("Directory containing the 'solana-genesis' and 'solana-ledger-tool' CLIs")
+                .required(true)
+                .takes_value(true)
+                .value_name("DIR"))
+            .arg(Arg::new(NUM_BOOTSTRAPPERS_FLAG)
+                .long(NUM_BOOTSTRAPPERS_FLAG)
+                .about("Number of equally-staked bootstrappers to put in the genesis ledger")
+                .required_unless_present(BOOTSTRAPPER_STAKES_FLAG)
+                .conflicts_with(BOOTSTRAPPER_STAKES_FLAG)
+                .takes_value(true)
+                .value_name("N"))
+            .arg(Arg::new(BOOTSTRAPPER_STAKES_FLAG)
+                .long(BOOTSTRAPPER_STAKES_FLAG)
+                .about("Comma-separated stake, in lamports, of each bootstrapper to put in the genesis ledger (e.g. for a 40/30/20/10 split)")
+                .takes_value(true)
+                .value_name("LAMPORTS,..."))
+            .arg(Arg::new(OUTPUT_DIRPATH_FLAG)
+                .long(OUTPUT_DIRPATH_FLAG)
+                .about("Directory to write the ledger tarball and genesis config file to")
+                .required(true)
+                .takes_value(true)
+                .value_name("DIR")))
         .get_matches();
 
+    if let Some(genesis_matches) = matches.subcommand_matches(GENESIS_SUBCOMMAND) {
+        return run_genesis_subcommand(genesis_matches);
+    }
+
     let custom_params_json = matches.value_of(CUSTOM_PARAMS_JSON_FLAG)
         .context(format!("No '{}' arg provided", CUSTOM_PARAMS_JSON_FLAG))?;
     let kurtosis_api_socket = matches.value_of(KURTOSIS_API_SOCKET_FLAG)
@@ -61,4 +102,39 @@ fn main() -> Result<()> {
         testsuite_result.context("An error occurred running the test suite executor")?;
     }
     return Ok(());
-}
\ No newline at end of file
+}
+
+fn run_genesis_subcommand(matches: &ArgMatches) -> Result<()> {
+    let solana_clis_dirpath = matches.value_of(SOLANA_CLIS_DIRPATH_FLAG)
+        .context(format!("No '{}' arg provided", SOLANA_CLIS_DIRPATH_FLAG))?;
+    let bootstrapper_stakes = parse_bootstrapper_stakes(matches)?;
+    let output_dirpath = matches.value_of(OUTPUT_DIRPATH_FLAG)
+        .context(format!("No '{}' arg provided", OUTPUT_DIRPATH_FLAG))?;
+
+    let genesis_config = generate_genesis(Path::new(solana_clis_dirpath), &bootstrapper_stakes, Path::new(output_dirpath))
+        .context("An error occurred generating the genesis ledger")?;
+
+    // The logger is only set up by the testsuite executor, so we print directly
+    println!("Genesis ledger generated successfully, with genesis hash '{}'", genesis_config.get_genesis_hash());
+    println!("ACTION NEEDED: Copy '{}/{}' to 'testsuite/{}'", output_dirpath, GENESIS_CONFIG_FILENAME, GENESIS_CONFIG_FILENAME);
+    println!("ACTION NEEDED: Upload '{}/{}' to your file host for Kurtosis to use", output_dirpath, LEDGER_TGZ_FILENAME);
+    return Ok(());
+}
+
+// Gets the stake of each genesis bootstrapper, either given one by one or as a number of bootstrappers with the default stake
+fn parse_bootstrapper_stakes(matches: &ArgMatches) -> Result<Vec<u64>> {
+    if let Some(bootstrapper_stakes_str) = matches.value_of(BOOTSTRAPPER_STAKES_FLAG) {
+        let mut result = Vec::new();
+        for stake_str in bootstrapper_stakes_str.split(',') {
+            let stake: u64 = stake_str.trim().parse()
+                .context(format!("Could not parse stake '{}' in '{}' arg '{}' as a number", stake_str, BOOTSTRAPPER_STAKES_FLAG, bootstrapper_stakes_str))?;
+            result.push(stake);
+        }
+        return Ok(result);
+    }
+    let num_bootstrappers_str = matches.value_of(NUM_BOOTSTRAPPERS_FLAG)
+        .context(format!("Neither '{}' nor '{}' arg provided", NUM_BOOTSTRAPPERS_FLAG, BOOTSTRAPPER_STAKES_FLAG))?;
+    let num_bootstrappers: usize = num_bootstrappers_str.parse()
+        .context(format!("Could not parse '{}' arg '{}' as a number", NUM_BOOTSTRAPPERS_FLAG, num_bootstrappers_str))?;
+    return Ok(vec![DEFAULT_BOOTSTRAPPER_STAKE_LAMPORTS; num_bootstrappers]);
+}
@@ -0,0 +1,253 @@
+use anyhow::{Context, Result, anyhow};
+use serde_json::json;
+use std::{collections::HashMap, fs, path::Path, process::Command};
+
+use crate::sdk_impl::{keypair::Keypair, stake_state::{STAKE_PROGRAM_ID, STAKE_RENT_EXEMPT_RESERVE, serialize_bootstrap_stake}};
+
+use super::{genesis_config::{GenesisBootstrapperKeypairs, GenesisConfig, GenesisKeypair}, ledger_artifact::{LEDGER_HASHES_FILENAME, LedgerHashes}};
+
+const SOLANA_GENESIS_BINARY: &str = "solana-genesis";
+const SOLANA_LEDGER_TOOL_BINARY: &str = "solana-ledger-tool";
+
+// solana-genesis writes out this archive, but Kurtosis can only expand gzipped tarballs so we remove it and re-tar the ledger
+// TODO Once Kurtosis supports unzipping bz2 artifacts, we won't need to remove the bz2 and re-tar it - just use the generated one!
+const GENESIS_BZ2_FILENAME: &str = "genesis.tar.bz2";
+
+// Holds the extra stake accounts for bootstrappers staked above the minimum; solana-genesis reads it as YAML, which JSON is
+// a subset of
+const EXTRA_STAKE_ACCOUNTS_FILENAME: &str = "extra-stake-accounts.yml";
+
+pub const LEDGER_DIRNAME: &str = "ledger";
+pub const LEDGER_TGZ_FILENAME: &str = "test-ledger.tgz";
+pub const GENESIS_CONFIG_FILENAME: &str = "genesis-config.json";
+
+const FAUCET_LAMPORTS: u64 = 5_000_000_000_000;
+
+// Generates fresh identity, vote account, and stake account keypairs for a bootstrapper with each of the given stakes,
+// plus a keypair for the faucet
+pub fn generate_keypairs(bootstrapper_stakes: &[u64]) -> (Vec<GenesisBootstrapperKeypairs>, GenesisKeypair) {
+    let bootstrappers = bootstrapper_stakes.iter().map(|stake_lamports| GenesisBootstrapperKeypairs{
+        identity: GenesisKeypair::new(Keypair::new()),
+        vote_account: GenesisKeypair::new(Keypair::new()),
+        stake_account: GenesisKeypair::new(Keypair::new()),
+        stake_lamports: *stake_lamports,
+    }).collect();
+    let faucet = GenesisKeypair::new(Keypair::new());
+    return (bootstrappers, faucet);
+}
+
+// Creates a genesis ledger with a bootstrapper for each of the given stakes using the Solana CLIs in the given directory,
+// and writes the gzipped ledger & the genesis config that describes it into the output directory
+// Returns: the genesis config, which has also been written to the output directory
+pub fn generate_genesis(solana_clis_dirpath: &Path, bootstrapper_stakes: &[u64], output_dirpath: &Path) -> Result<GenesisConfig> {
+    if bootstrapper_stakes.is_empty() {
+        return Err(anyhow!(
+            "Number of genesis bootstrappers must be > 0",
+        ));
+    }
+    if bootstrapper_stakes.contains(&0) {
+        return Err(anyhow!(
+            "Every genesis bootstrapper must have stake, but got stakes {:?}",
+            bootstrapper_stakes,
+        ));
+    }
+    if !solana_clis_dirpath.is_dir() {
+        return Err(anyhow!(
+            "No Solana CLIs directory '{}'",
+            solana_clis_dirpath.display(),
+        ));
+    }
+    let ledger_dirpath = output_dirpath.join(LEDGER_DIRNAME);
+    if ledger_dirpath.exists() {
+        return Err(anyhow!(
+            "Ledger directory '{}' already exists; refusing to overwrite it",
+            ledger_dirpath.display(),
+        ));
+    }
+    fs::create_dir_all(output_dirpath)
+        .context(format!("An error occurred creating output directory '{}'", output_dirpath.display()))?;
+
+    let (bootstrappers, faucet) = generate_keypairs(bootstrapper_stakes);
+
+    // solana-genesis stakes every bootstrapper the same, so they all get the smallest stake and the rest of each bootstrapper's
+    // stake goes in an extra stake account delegated to it
+    let min_stake = *bootstrapper_stakes.iter().min()
+        .context("Couldn't find the smallest bootstrapper stake")?;
+    let extra_stake_accounts_filepath = output_dirpath.join(EXTRA_STAKE_ACCOUNTS_FILENAME);
+    let num_extra_stake_accounts = write_extra_stake_accounts(&bootstrappers, min_stake, &extra_stake_accounts_filepath)
+        .context("An error occurred writing the extra stake accounts")?;
+
+    // WARNING: Do NOT use --enable-warmup-epochs here!! If it's used, spurious failures will be thrown while under network partition
+    let mut genesis_cmd = Command::new(solana_clis_dirpath.join(SOLANA_GENESIS_BINARY));
+    genesis_cmd
+        .arg("--cluster-type").arg("testnet")
+        // Tells the validators to sleep, rather than hash, in order to form the logical clock
+        .arg("--hashes-per-tick").arg("sleep")
+        .arg("--faucet-pubkey").arg(faucet.get_pubkey().to_string())
+        .arg("--faucet-lamports").arg(FAUCET_LAMPORTS.to_string())
+        // The stake account's rent-exempt reserve doesn't get delegated, so it goes on top of the stake
+        .arg("--bootstrap-validator-stake-lamports").arg((min_stake + STAKE_RENT_EXEMPT_RESERVE).to_string())
+        .arg("--ledger").arg(&ledger_dirpath);
+    for bootstrapper_keypairs in &bootstrappers {
+        genesis_cmd
+            .arg("--bootstrap-validator")
+            .arg(bootstrapper_keypairs.identity.get_pubkey().to_string())
+            .arg(bootstrapper_keypairs.vote_account.get_pubkey().to_string())
+            .arg(bootstrapper_keypairs.stake_account.get_pubkey().to_string());
+    }
+    if num_extra_stake_accounts > 0 {
+        genesis_cmd.arg("--primordial-accounts-file").arg(&extra_stake_accounts_filepath);
+    }
+    run_command(&mut genesis_cmd)
+        .context("An error occurred creating the genesis ledger")?;
+    fs::remove_file(&extra_stake_accounts_filepath)
+        .context(format!("An error occurred removing the extra stake accounts file '{}'", extra_stake_accounts_filepath.display()))?;
+
+    let genesis_hash = get_ledger_tool_output(solana_clis_dirpath, "genesis-hash", &ledger_dirpath)?;
+    let bank_hash = get_ledger_tool_output(solana_clis_dirpath, "bank-hash", &ledger_dirpath)?;
+    let shred_version_str = get_ledger_tool_output(solana_clis_dirpath, "shred-version", &ledger_dirpath)?;
+    let shred_version: u64 = shred_version_str.parse()
+        .context(format!("Could not parse shred version '{}' as a number", shred_version_str))?;
+
+    fs::remove_file(ledger_dirpath.join(GENESIS_BZ2_FILENAME))
+        .context(format!("An error occurred removing the '{}' archive from the ledger", GENESIS_BZ2_FILENAME))?;
+    // Recorded in the ledger so that the testsuite can check the ledger against the genesis config without replaying it
+    let ledger_hashes = LedgerHashes{
+        genesis_hash: genesis_hash.clone(),
+        bank_hash: bank_hash.clone(),
+        shred_version,
+    };
+    let ledger_hashes_json = serde_json::to_string_pretty(&ledger_hashes)
+        .context("An error occurred serializing the ledger hashes")?;
+    fs::write(ledger_dirpath.join(LEDGER_HASHES_FILENAME), ledger_hashes_json)
+        .context(format!("An error occurred writing the '{}' file to the ledger", LEDGER_HASHES_FILENAME))?;
+    let ledger_tgz_filepath = output_dirpath.join(LEDGER_TGZ_FILENAME);
+    run_command(
+        Command::new("tar")
+            .arg("-czf").arg(&ledger_tgz_filepath)
+            .arg("-C").arg(&ledger_dirpath)
+            .arg(".")
+    ).context(format!("An error occurred archiving the ledger to '{}'", ledger_tgz_filepath.display()))?;
+
+    let genesis_config = GenesisConfig::new(bootstrappers, faucet, genesis_hash, bank_hash, shred_version);
+    genesis_config.verify()
+        .context("The generated genesis config is invalid")?;
+    let genesis_config_filepath = output_dirpath.join(GENESIS_CONFIG_FILENAME);
+    let genesis_config_filepath_str = genesis_config_filepath.to_str()
+        .context("Couldn't convert genesis config filepath to string")?;
+    genesis_config.save(genesis_config_filepath_str)
+        .context("An error occurred writing the genesis config")?;
+
+    return Ok(genesis_config);
+}
+
+// Writes out a primordial accounts file with a stake account for each bootstrapper staked above the base stake, holding
+// the difference; the stake is bootstrap stake, so it's fully active from genesis just like the base stake
+// Returns: the number of extra stake accounts written
+fn write_extra_stake_accounts(bootstrappers: &[GenesisBootstrapperKeypairs], base_stake: u64, filepath: &Path) -> Result<usize> {
+    let mut accounts: HashMap<String, serde_json::Value> = HashMap::new();
+    for bootstrapper_keypairs in bootstrappers {
+        let extra_stake = bootstrapper_keypairs.stake_lamports - base_stake;
+        if extra_stake == 0 {
+            continue;
+        }
+        // Nothing ever needs to sign for the account, so its keypair gets thrown away
+        let stake_account_pubkey = Keypair::new().pubkey();
+        let data = serialize_bootstrap_stake(
+            bootstrapper_keypairs.identity.get_pubkey(),
+            bootstrapper_keypairs.vote_account.get_pubkey(),
+            extra_stake,
+        );
+        accounts.insert(stake_account_pubkey.to_string(), json!({
+            "balance": extra_stake + STAKE_RENT_EXEMPT_RESERVE,
+            "owner": STAKE_PROGRAM_ID.to_string(),
+            "data": base64::encode(&data),
+            "executable": false,
+        }));
+    }
+    let accounts_json = serde_json::to_string_pretty(&accounts)
+        .context("An error occurred serializing the extra stake accounts")?;
+    fs::write(filepath, accounts_json)
+        .context(format!("An error occurred writing the extra stake accounts to file '{}'", filepath.display()))?;
+    return Ok(accounts.len());
+}
+
+// Runs the given solana-ledger-tool subcommand against the ledger, returning its trimmed stdout
+fn get_ledger_tool_output(solana_clis_dirpath: &Path, subcommand: &str, ledger_dirpath: &Path) -> Result<String> {
+    let output = run_command(
+        Command::new(solana_clis_dirpath.join(SOLANA_LEDGER_TOOL_BINARY))
+            .env("RUST_LOG", "none")
+            .arg(subcommand)
+            .arg("--ledger").arg(ledger_dirpath)
+    ).context(format!("An error occurred getting the ledger's {}", subcommand))?;
+    return Ok(output.trim().to_owned());
+}
+
+// Runs the command to completion, returning its stdout if it succeeded
+fn run_command(cmd: &mut Command) -> Result<String> {
+    let output = cmd.output()
+        .context(format!("An error occurred running command {:?}", cmd))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Command {:?} exited with {}; stderr:\n{}",
+            cmd,
+            output.status,
+            String::from_utf8_lossy(&output.stderr),
+        ));
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .context(format!("The output of command {:?} wasn't valid UTF-8", cmd))?;
+    return Ok(stdout);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, path::Path};
+
+    use crate::sdk_impl::stake_state::{STAKE_PROGRAM_ID, STAKE_RENT_EXEMPT_RESERVE, STAKE_STATE_SIZE};
+
+    use super::{generate_genesis, generate_keypairs, write_extra_stake_accounts};
+
+    #[test]
+    fn test_generate_keypairs() {
+        let (bootstrappers, faucet) = generate_keypairs(&[40, 30, 30]);
+        assert_eq!(3, bootstrappers.len());
+        assert_eq!(40, bootstrappers[0].stake_lamports);
+        let mut pubkeys = HashSet::new();
+        pubkeys.insert(*faucet.get_pubkey());
+        for bootstrapper_keypairs in &bootstrappers {
+            for genesis_keypair in &[&bootstrapper_keypairs.identity, &bootstrapper_keypairs.vote_account, &bootstrapper_keypairs.stake_account] {
+                genesis_keypair.verify().unwrap();
+                pubkeys.insert(*genesis_keypair.get_pubkey());
+            }
+        }
+        assert_eq!(10, pubkeys.len());
+    }
+
+    #[test]
+    fn test_generate_genesis_validates_args() {
+        let output_dirpath = std::env::temp_dir();
+        assert!(generate_genesis(Path::new("/"), &[], &output_dirpath).is_err());
+        assert!(generate_genesis(Path::new("/"), &[10, 0], &output_dirpath).is_err());
+        assert!(generate_genesis(Path::new("/nonexistent-solana-clis-dir"), &[10], &output_dirpath).is_err());
+    }
+
+    #[test]
+    fn test_write_extra_stake_accounts_only_covers_stake_above_base() {
+        let (bootstrappers, _) = generate_keypairs(&[40, 30, 20, 10]);
+        let filepath = std::env::temp_dir().join(format!("extra-stake-accounts-{}.yml", std::process::id()));
+        let num_accounts = write_extra_stake_accounts(&bootstrappers, 10, &filepath).unwrap();
+        let accounts_json = std::fs::read_to_string(&filepath).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert_eq!(3, num_accounts);
+
+        let accounts: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&accounts_json).unwrap();
+        let mut balances: Vec<u64> = accounts.values().map(|account| account["balance"].as_u64().unwrap()).collect();
+        balances.sort();
+        assert_eq!(vec![10 + STAKE_RENT_EXEMPT_RESERVE, 20 + STAKE_RENT_EXEMPT_RESERVE, 30 + STAKE_RENT_EXEMPT_RESERVE], balances);
+        for account in accounts.values() {
+            assert_eq!(STAKE_PROGRAM_ID.to_string(), account["owner"].as_str().unwrap());
+            assert_eq!(STAKE_STATE_SIZE, base64::decode(account["data"].as_str().unwrap()).unwrap().len());
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, io::{BufRead, BufReader, Write}, net::{IpAddr, TcpListener, TcpStream, ToSocketAddrs}, path::{Path, PathBuf}, process::{self, Command}, thread};
+
+use crate::sdk_impl::{hash::{self, Hash}, shred_version::compute_shred_version};
+
+use super::genesis_config::GenesisConfig;
+
+// The files artifact that the bootstrappers mount the genesis ledger from
+const LEDGER_DIR_ARTIFACT_KEY: &str = "ledger-dir";
+
+// The serialized genesis config in the ledger, which the genesis hash is the hash of
+const GENESIS_BIN_FILENAME: &str = "genesis.bin";
+
+// Written into the ledger by the 'genesis' subcommand, since the bank hash can only be worked out by replaying the ledger
+pub const LEDGER_HASHES_FILENAME: &str = "ledger-hashes.json";
+
+// Where the testsuite container serves a local ledger archive from, for Kurtosis to download it as the files artifact
+const LEDGER_ARTIFACT_SERVER_PORT: u16 = 9080;
+
+const HOSTNAME_FILEPATH: &str = "/etc/hostname";
+
+// The hashes that a node checks the ledger against when it starts
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerHashes {
+    pub genesis_hash: String,
+    pub bank_hash: String,
+    pub shred_version: u64,
+}
+
+// The gzipped ledger tarball that the bootstrappers start from, which Kurtosis downloads from a URL
+pub struct LedgerArtifact {
+    url: String,
+    // The archive on the testsuite container's filesystem, if it's being served from there rather than fetched from the URL
+    local_filepath: Option<PathBuf>,
+}
+
+impl LedgerArtifact {
+    pub fn from_url(url: String) -> LedgerArtifact {
+        return LedgerArtifact{
+            url,
+            local_filepath: None,
+        };
+    }
+
+    // Serves the local archive over HTTP from the testsuite container for as long as the testsuite runs, so that the
+    // cluster can be started without internet access
+    pub fn serve_local_file(filepath: &Path) -> Result<LedgerArtifact> {
+        let filename = filepath.file_name()
+            .and_then(|filename| filename.to_str())
+            .context(format!("Couldn't get the filename of ledger archive '{}'", filepath.display()))?
+            .to_owned();
+        if !filepath.is_file() {
+            return Err(anyhow!(
+                "Ledger archive '{}' doesn't exist",
+                filepath.display(),
+            ));
+        }
+        let ip_addr = get_own_ip_address()
+            .context("An error occurred getting the testsuite container's IP address to serve the ledger archive on")?;
+        let listener = TcpListener::bind(("0.0.0.0", LEDGER_ARTIFACT_SERVER_PORT))
+            .context(format!("An error occurred binding the ledger archive server to port {}", LEDGER_ARTIFACT_SERVER_PORT))?;
+        let served_filepath = filepath.to_owned();
+        let served_path = format!("/{}", filename);
+        thread::spawn(move || {
+            for stream_or_err in listener.incoming() {
+                let result = stream_or_err
+                    .context("An error occurred accepting a connection")
+                    .and_then(|stream| serve_file(stream, &served_path, &served_filepath));
+                if let Err(err) = result {
+                    warn!("An error occurred serving the ledger archive: {:#}", err);
+                }
+            }
+        });
+
+        let url = format!("http://{}:{}/{}", ip_addr, LEDGER_ARTIFACT_SERVER_PORT, filename);
+        info!("Serving ledger archive '{}' at '{}'", filepath.display(), url);
+        return Ok(LedgerArtifact{
+            url,
+            local_filepath: Some(filepath.to_owned()),
+        });
+    }
+
+    pub fn get_key(&self) -> &str {
+        return LEDGER_DIR_ARTIFACT_KEY;
+    }
+
+    pub fn get_url(&self) -> &str {
+        return &self.url;
+    }
+
+    // Returns: the files artifacts that a test using this ledger needs, for its test configuration
+    pub fn get_files_artifact_urls(&self) -> HashMap<String, String> {
+        let mut result = HashMap::new();
+        result.insert(self.get_key().to_owned(), self.get_url().to_owned());
+        return result;
+    }
+
+    // Checks that the ledger in the archive is the one the genesis config describes, so that a mismatch fails fast
+    // instead of leaving every node waiting for a supermajority that never comes
+    pub fn verify(&self, genesis_config: &GenesisConfig) -> Result<()> {
+        let archive_filepath = match &self.local_filepath {
+            Some(local_filepath) => local_filepath.clone(),
+            None => self.download()?,
+        };
+        let genesis_bin = read_archive_file(&archive_filepath, GENESIS_BIN_FILENAME)?
+            .context(format!("Ledger archive from '{}' doesn't contain '{}'", self.url, GENESIS_BIN_FILENAME))?;
+        let ledger_hashes_json = read_archive_file(&archive_filepath, LEDGER_HASHES_FILENAME)?;
+        if self.local_filepath.is_none() {
+            fs::remove_file(&archive_filepath)
+                .context(format!("An error occurred removing downloaded ledger archive '{}'", archive_filepath.display()))?;
+        }
+
+        let ledger_hashes = match ledger_hashes_json {
+            Some(json) => {
+                let ledger_hashes: LedgerHashes = serde_json::from_slice(&json)
+                    .context(format!("An error occurred parsing the '{}' file in the ledger archive", LEDGER_HASHES_FILENAME))?;
+                Some(ledger_hashes)
+            },
+            None => {
+                warn!(
+                    "Ledger archive from '{}' doesn't record its bank hash in a '{}' file, so only the validators can check it",
+                    self.url,
+                    LEDGER_HASHES_FILENAME,
+                );
+                None
+            },
+        };
+        verify_ledger(&genesis_bin, ledger_hashes.as_ref(), genesis_config)
+            .context(format!("The ledger archive from '{}' doesn't match the genesis config", self.url))?;
+        return Ok(());
+    }
+
+    // Returns: the path of the downloaded archive, which the caller should remove
+    fn download(&self) -> Result<PathBuf> {
+        let response = reqwest::blocking::get(&self.url)
+            .and_then(|response| response.error_for_status())
+            .context(format!("An error occurred downloading the ledger archive from '{}'", self.url))?;
+        let bytes = response.bytes()
+            .context(format!("An error occurred reading the ledger archive downloaded from '{}'", self.url))?;
+        let filepath = std::env::temp_dir().join(format!("ledger-artifact-{}.tgz", process::id()));
+        fs::write(&filepath, &bytes)
+            .context(format!("An error occurred writing the downloaded ledger archive to '{}'", filepath.display()))?;
+        return Ok(filepath);
+    }
+}
+
+// Checks the genesis hash & shred version (which are derived from the serialized genesis config), and the bank hash too if
+// the ledger records it
+fn verify_ledger(genesis_bin: &[u8], ledger_hashes: Option<&LedgerHashes>, genesis_config: &GenesisConfig) -> Result<()> {
+    let genesis_hash = hash::hash(genesis_bin);
+    let expected_genesis_hash = Hash::from_base58(genesis_config.get_genesis_hash())
+        .context("An error occurred parsing the genesis config's genesis hash")?;
+    if genesis_hash != expected_genesis_hash {
+        return Err(anyhow!(
+            "The ledger's genesis hash is '{}', but the genesis config expects '{}'",
+            genesis_hash,
+            expected_genesis_hash,
+        ));
+    }
+    let shred_version = compute_shred_version(&genesis_hash) as u64;
+    if shred_version != genesis_config.get_shred_version() {
+        return Err(anyhow!(
+            "The ledger's shred version is {}, but the genesis config expects {}",
+            shred_version,
+            genesis_config.get_shred_version(),
+        ));
+    }
+    if let Some(ledger_hashes) = ledger_hashes {
+        let expected_ledger_hashes = LedgerHashes{
+            genesis_hash: genesis_config.get_genesis_hash().to_owned(),
+            bank_hash: genesis_config.get_bank_hash().to_owned(),
+            shred_version: genesis_config.get_shred_version(),
+        };
+        if *ledger_hashes != expected_ledger_hashes {
+            return Err(anyhow!(
+                "The ledger records its hashes as {:?}, but the genesis config expects {:?}",
+                ledger_hashes,
+                expected_ledger_hashes,
+            ));
+        }
+    }
+    return Ok(());
+}
+
+// Returns: the contents of the file at the top level of the gzipped tarball, or None if it doesn't have that file
+fn read_archive_file(archive_filepath: &Path, filename: &str) -> Result<Option<Vec<u8>>> {
+    let list_output = Command::new("tar")
+        .arg("-tzf").arg(archive_filepath)
+        .output()
+        .context(format!("An error occurred listing the contents of archive '{}'", archive_filepath.display()))?;
+    if !list_output.status.success() {
+        return Err(anyhow!(
+            "Listing the contents of archive '{}' exited with {}; stderr:\n{}",
+            archive_filepath.display(),
+            list_output.status,
+            String::from_utf8_lossy(&list_output.stderr),
+        ));
+    }
+    // The 'genesis' subcommand archives the ledger directory as '.', so its entries are prefixed with './'
+    let entry = String::from_utf8_lossy(&list_output.stdout).lines()
+        .find(|entry| entry.trim_start_matches("./") == filename)
+        .map(|entry| entry.to_owned());
+    let entry = match entry {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+
+    let extract_output = Command::new("tar")
+        .arg("-xzOf").arg(archive_filepath)
+        .arg(&entry)
+        .output()
+        .context(format!("An error occurred extracting '{}' from archive '{}'", entry, archive_filepath.display()))?;
+    if !extract_output.status.success() {
+        return Err(anyhow!(
+            "Extracting '{}' from archive '{}' exited with {}; stderr:\n{}",
+            entry,
+            archive_filepath.display(),
+            extract_output.status,
+            String::from_utf8_lossy(&extract_output.stderr),
+        ));
+    }
+    return Ok(Some(extract_output.stdout));
+}
+
+// Docker maps the container's hostname to its IP address on the testnet
+fn get_own_ip_address() -> Result<IpAddr> {
+    let hostname = fs::read_to_string(HOSTNAME_FILEPATH)
+        .context(format!("An error occurred reading the hostname from '{}'", HOSTNAME_FILEPATH))?;
+    let hostname = hostname.trim();
+    let socket_addr = (hostname, 0).to_socket_addrs()
+        .context(format!("An error occurred resolving hostname '{}'", hostname))?
+        .find(|socket_addr| socket_addr.is_ipv4())
+        .context(format!("Hostname '{}' didn't resolve to an IPv4 address", hostname))?;
+    return Ok(socket_addr.ip());
+}
+
+// Answers a single HTTP request: the file for a GET of the served path, and a 404 for anything else
+fn serve_file(mut stream: TcpStream, served_path: &str, filepath: &Path) -> Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)
+        .context("An error occurred reading the request line")?;
+    debug!("Ledger archive server got request '{}'", request_line.trim());
+    let mut request_parts = request_line.split_whitespace();
+    let is_served_path = request_parts.next() == Some("GET") && request_parts.next() == Some(served_path);
+    if !is_served_path {
+        stream.write_all(b"HTTP/1.0 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+            .context("An error occurred writing the 404 response")?;
+        return Ok(());
+    }
+    let contents = fs::read(filepath)
+        .context(format!("An error occurred reading ledger archive '{}'", filepath.display()))?;
+    let headers = format!("HTTP/1.0 200 OK\r\nContent-Type: application/gzip\r\nContent-Length: {}\r\n\r\n", contents.len());
+    stream.write_all(headers.as_bytes())
+        .and_then(|_| stream.write_all(&contents))
+        .context("An error occurred writing the ledger archive response")?;
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{networks_impl::genesis_config::GenesisConfig, sdk_impl::{hash, shred_version::compute_shred_version}};
+
+    use super::{LedgerHashes, verify_ledger};
+
+    const CHECKED_IN_GENESIS_CONFIG_FILEPATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/genesis-config.json");
+
+    #[test]
+    fn test_verify_ledger_rejects_different_genesis() {
+        let genesis_config = GenesisConfig::load(CHECKED_IN_GENESIS_CONFIG_FILEPATH).unwrap();
+        let err = verify_ledger(b"not the checked-in genesis", None, &genesis_config).unwrap_err();
+        assert!(format!("{:#}", err).contains("genesis hash"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_verify_ledger_checks_recorded_hashes() {
+        let genesis_bin = b"some genesis";
+        let genesis_hash = hash::hash(genesis_bin);
+        let shred_version = compute_shred_version(&genesis_hash) as u64;
+        let config_json = std::fs::read_to_string(CHECKED_IN_GENESIS_CONFIG_FILEPATH).unwrap();
+        let mut config_value: serde_json::Value = serde_json::from_str(&config_json).unwrap();
+        config_value["genesisHash"] = serde_json::json!(genesis_hash.to_string());
+        config_value["shredVersion"] = serde_json::json!(shred_version);
+        let genesis_config: GenesisConfig = serde_json::from_value(config_value).unwrap();
+        verify_ledger(genesis_bin, None, &genesis_config).unwrap();
+
+        let mut ledger_hashes = LedgerHashes{
+            genesis_hash: genesis_hash.to_string(),
+            bank_hash: genesis_config.get_bank_hash().to_owned(),
+            shred_version,
+        };
+        verify_ledger(genesis_bin, Some(&ledger_hashes), &genesis_config).unwrap();
+        ledger_hashes.bank_hash = "11111111111111111111111111111111".to_owned();
+        let err = verify_ledger(genesis_bin, Some(&ledger_hashes), &genesis_config).unwrap_err();
+        assert!(format!("{:#}", err).contains("records its hashes"), "{:#}", err);
+    }
+}
@@ -0,0 +1,296 @@
+use anyhow::{Context, Result, anyhow};
+use std::{fmt, thread::sleep, time::{Duration, Instant}};
+
+use crate::sdk_impl::{hash::Hash, keypair::Keypair, system_instruction, transaction::Transaction};
+use crate::services_impl::{faucet::faucet_service::FaucetService, validator::{confirmation_tracker::{ConfirmationStage, ConfirmationTracker}, retry_policy::{NO_RETRY_POLICY, RetryPolicy}, validator_service::ValidatorService}};
+
+// Plenty for every fee & transfer that an account sends over a run
+const ACCOUNT_FUNDING_LAMPORTS: u64 = 1_000_000_000;
+const FUNDING_TIMEOUT: Duration = Duration::from_secs(90);
+
+// Well inside the ~2 minutes that a blockhash stays valid for
+const TIME_BETWEEN_BLOCKHASH_REFRESHES: Duration = Duration::from_secs(10);
+// Statuses get polled while sending, so that latencies are measured from the status cache while it still has them
+const TIME_BETWEEN_STATUS_POLLS: Duration = Duration::from_secs(1);
+// Once sending stops, how long the transactions still in flight get to land
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+// The shape of the load: how many accounts send transfers to each other, how fast, and for how long
+#[derive(Clone, Debug)]
+pub struct LoadGeneratorConfig {
+    pub num_accounts: usize,
+    pub target_tps: u64,
+    pub duration: Duration,
+}
+
+impl LoadGeneratorConfig {
+    pub fn validate(&self) -> Result<()> {
+        // Each account sends to the next one around the ring, so there have to be at least two
+        if self.num_accounts < 2 {
+            return Err(anyhow!(
+                "The load generator needs at least 2 accounts to transfer between, but was configured with {}",
+                self.num_accounts,
+            ));
+        }
+        if self.target_tps == 0 {
+            return Err(anyhow!(
+                "The load generator's target TPS must be greater than 0",
+            ));
+        }
+        if self.duration.as_secs() == 0 {
+            return Err(anyhow!(
+                "The load generator must run for at least a second, but was configured to run for {:?}",
+                self.duration,
+            ));
+        }
+        return Ok(());
+    }
+
+    pub fn get_num_transactions(&self) -> u64 {
+        return self.target_tps * self.duration.as_secs();
+    }
+}
+
+// What happened to the transactions of a load-generation run
+pub struct LoadReport {
+    // Transactions that a node accepted
+    pub num_sent: usize,
+    // Transactions that a node rejected outright
+    pub num_send_errors: usize,
+    pub num_confirmed: usize,
+    // Transactions that landed with an error
+    pub num_failed: usize,
+    // Transactions that never got confirmed, whether a node rejected them, they got lost after being accepted, or their
+    // statuses couldn't be polled
+    pub num_dropped: usize,
+    pub send_duration: Duration,
+    // From the first transaction being sent to the last one getting confirmed, or None if none got confirmed
+    pub confirm_duration: Option<Duration>,
+    pub tracker: ConfirmationTracker,
+}
+
+impl LoadReport {
+    // The rate at which nodes accepted transactions
+    pub fn get_sent_tps(&self) -> f64 {
+        return self.num_sent as f64 / self.send_duration.as_secs_f64();
+    }
+
+    // The rate at which transactions got confirmed, from the first send until the last confirmation, so that the
+    // transactions confirmed while draining don't count as if they'd been confirmed while the load was being sent
+    pub fn get_confirmed_tps(&self) -> f64 {
+        return match self.confirm_duration {
+            Some(confirm_duration) if !confirm_duration.is_zero() => self.num_confirmed as f64 / confirm_duration.as_secs_f64(),
+            _ => 0.0,
+        };
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "{} sent ({:.1} TPS over {:?}), {} rejected, {} confirmed ({:.1} TPS over {:?}), {} failed, {} dropped; {}",
+            self.num_sent,
+            self.get_sent_tps(),
+            self.send_duration,
+            self.num_send_errors,
+            self.num_confirmed,
+            self.get_confirmed_tps(),
+            self.confirm_duration,
+            self.num_failed,
+            self.num_dropped,
+            self.tracker,
+        );
+    }
+}
+
+// Port of the idea behind https://docs.solana.com/cluster/bench-tps : a ring of funded accounts that send each other
+// transfers at a fixed rate, spread over the nodes' RPC endpoints
+pub struct LoadGenerator {
+    config: LoadGeneratorConfig,
+    accounts: Vec<Keypair>,
+}
+
+impl LoadGenerator {
+    pub fn new(config: LoadGeneratorConfig) -> LoadGenerator {
+        let accounts = (0..config.num_accounts).map(|_| Keypair::new()).collect();
+        return LoadGenerator{
+            config,
+            accounts,
+        };
+    }
+
+    // Gets an airdrop transaction for every account from the faucet, and sends them all through the node at once
+    pub fn fund_accounts(&self, faucet: &FaucetService, node: &ValidatorService) -> Result<()> {
+        let blockhash = node.get_recent_blockhash()
+            .context("An error occurred getting a recent blockhash for the funding airdrops")?;
+        let mut tracker = ConfirmationTracker::new();
+        for account in &self.accounts {
+            let transaction = faucet.request_airdrop(&account.pubkey(), ACCOUNT_FUNDING_LAMPORTS, &blockhash)
+                .context(format!("An error occurred getting a funding airdrop for account '{}' from the faucet", account.pubkey()))?;
+            tracker.send(node, &transaction)
+                .context(format!("An error occurred sending the funding airdrop for account '{}'", account.pubkey()))?;
+        }
+        tracker.wait_for_all(node, FUNDING_TIMEOUT);
+        if tracker.get_num_finalized() != self.accounts.len() {
+            return Err(anyhow!(
+                "Only {} of the {} load-generation accounts got funded: {}",
+                tracker.get_num_finalized(),
+                self.accounts.len(),
+                tracker,
+            ));
+        }
+        info!("Funded {} load-generation accounts with {} lamports each", self.accounts.len(), ACCOUNT_FUNDING_LAMPORTS);
+        return Ok(());
+    }
+
+    // Sends transfers at the target rate for the configured duration, round-robining over the nodes, then waits for
+    // the transactions still in flight; statuses are all read from the first node
+    pub fn run(&self, nodes: &[&ValidatorService]) -> Result<LoadReport> {
+        let status_node = nodes.first()
+            .context("The load generator needs at least one node to send to")?;
+        let mut tracker = ConfirmationTracker::new();
+
+        // Retrying a rejected transfer would hold up every transfer after it, so while the load is going the nodes'
+        // failures get counted rather than retried
+        let retry_policies: Vec<RetryPolicy> = nodes.iter().map(|node| node.get_retry_policy()).collect();
+        for node in nodes {
+            node.set_retry_policy(NO_RETRY_POLICY);
+        }
+        let send_result = self.send_load(nodes, &mut tracker);
+        for (node, retry_policy) in nodes.iter().zip(retry_policies) {
+            node.set_retry_policy(retry_policy);
+        }
+        let (num_send_errors, send_duration) = send_result?;
+        info!("Sent {} transfers in {:?}; waiting for them to land...", self.config.get_num_transactions(), send_duration);
+
+        tracker.wait_for_all(status_node, DRAIN_TIMEOUT);
+        let num_confirmed = tracker.get_latencies(ConfirmationStage::Confirmed).get_count();
+        let num_failed = tracker.get_num_failed();
+        let num_sent = tracker.get_num_sent();
+        return Ok(LoadReport{
+            num_sent,
+            num_send_errors,
+            num_confirmed,
+            num_failed,
+            num_dropped: num_send_errors + num_sent - num_confirmed - num_failed,
+            send_duration,
+            confirm_duration: tracker.get_time_to_last(ConfirmationStage::Confirmed),
+            tracker,
+        });
+    }
+
+    // Returns: the number of transfers that the nodes rejected, and how long sending took
+    fn send_load(&self, nodes: &[&ValidatorService], tracker: &mut ConfirmationTracker) -> Result<(usize, Duration)> {
+        let status_node = nodes[0];
+        let num_transactions = self.config.get_num_transactions();
+        let mut num_send_errors = 0;
+        let mut blockhash = status_node.get_recent_blockhash()
+            .context("An error occurred getting a recent blockhash for the load")?;
+        let mut last_blockhash_refresh_time = Instant::now();
+        let mut last_status_poll_time = Instant::now();
+
+        info!("Sending {} transfers at {} TPS across {} nodes...", num_transactions, self.config.target_tps, nodes.len());
+        let start_time = Instant::now();
+        for n in 0..num_transactions {
+            let send_time = start_time + get_send_offset(n, self.config.target_tps);
+            let now = Instant::now();
+            if send_time > now {
+                sleep(send_time - now);
+            }
+            // A node that's struggling under the load shouldn't end the run, so failed refreshes & polls just get tried
+            // again later; the old blockhash stays valid well past the next refresh, and transactions whose statuses
+            // never get polled end up counted as dropped
+            if last_blockhash_refresh_time.elapsed() >= TIME_BETWEEN_BLOCKHASH_REFRESHES {
+                match status_node.get_recent_blockhash() {
+                    Ok(new_blockhash) => blockhash = new_blockhash,
+                    Err(err) => warn!("Couldn't refresh the blockhash for the load, so the old one will be kept: {:#}", err),
+                }
+                last_blockhash_refresh_time = Instant::now();
+            }
+            if last_status_poll_time.elapsed() >= TIME_BETWEEN_STATUS_POLLS {
+                tracker.poll_or_warn(status_node);
+                last_status_poll_time = Instant::now();
+            }
+
+            let transaction = self.build_transfer(n, blockhash)?;
+            let node = nodes[n as usize % nodes.len()];
+            if let Err(err) = tracker.send(node, &transaction) {
+                debug!("Node rejected transfer #{}: {:#}", n, err);
+                num_send_errors += 1;
+            }
+        }
+        return Ok((num_send_errors, start_time.elapsed()));
+    }
+
+    fn build_transfer(&self, n: u64, blockhash: Hash) -> Result<Transaction> {
+        let (from_index, to_index, lamports) = get_transfer(n, self.accounts.len());
+        let from = &self.accounts[from_index];
+        let instruction = system_instruction::transfer(&from.pubkey(), &self.accounts[to_index].pubkey(), lamports);
+        let transaction = Transaction::new_signed_with_payer(&[instruction], Some(from), &[], blockhash)
+            .context(format!("An error occurred building transfer #{}", n))?;
+        return Ok(transaction);
+    }
+}
+
+// When the nth transaction is due, relative to the start of the run
+fn get_send_offset(n: u64, target_tps: u64) -> Duration {
+    return Duration::from_nanos((n as u128 * 1_000_000_000 / target_tps as u128) as u64);
+}
+
+// The nth transfer goes from one account to the next around the ring
+// Returns: the indices of the sending & receiving accounts, and the lamports to transfer, which differ every time the
+// same pair comes around again so that no two transfers under the same blockhash have the same signature
+fn get_transfer(n: u64, num_accounts: usize) -> (usize, usize, u64) {
+    let from_index = n as usize % num_accounts;
+    let to_index = (from_index + 1) % num_accounts;
+    let lamports = 1 + n / num_accounts as u64;
+    return (from_index, to_index, lamports);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Duration};
+
+    use super::{LoadGeneratorConfig, get_send_offset, get_transfer};
+
+    #[test]
+    fn test_validate() {
+        let config = LoadGeneratorConfig{
+            num_accounts: 8,
+            target_tps: 100,
+            duration: Duration::from_secs(60),
+        };
+        config.validate().unwrap();
+        assert_eq!(6000, config.get_num_transactions());
+
+        let mut one_account = config.clone();
+        one_account.num_accounts = 1;
+        assert!(one_account.validate().is_err());
+        let mut zero_tps = config.clone();
+        zero_tps.target_tps = 0;
+        assert!(zero_tps.validate().is_err());
+        let mut too_short = config;
+        too_short.duration = Duration::from_millis(500);
+        assert!(too_short.validate().is_err());
+    }
+
+    #[test]
+    fn test_send_schedule() {
+        assert_eq!(Duration::from_secs(0), get_send_offset(0, 100));
+        assert_eq!(Duration::from_millis(10), get_send_offset(1, 100));
+        assert_eq!(Duration::from_secs(1), get_send_offset(100, 100));
+        assert_eq!(Duration::from_nanos(333_333_333), get_send_offset(1, 3));
+    }
+
+    #[test]
+    fn test_transfers_are_unique() {
+        let num_accounts = 4;
+        let transfers: Vec<(usize, usize, u64)> = (0..100).map(|n| get_transfer(n, num_accounts)).collect();
+        assert_eq!((0, 1, 1), transfers[0]);
+        assert_eq!((3, 0, 1), transfers[3]);
+        assert_eq!((0, 1, 2), transfers[4]);
+        let unique_transfers: HashSet<&(usize, usize, u64)> = transfers.iter().collect();
+        assert_eq!(transfers.len(), unique_transfers.len());
+    }
+}
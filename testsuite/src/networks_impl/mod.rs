@@ -1,2 +1,8 @@
-pub (self) mod genesis_config;
-pub mod solana_network;
\ No newline at end of file
+pub mod genesis_config;
+pub mod genesis_generator;
+pub mod ledger_artifact;
+pub mod load_generator;
+pub mod network_partition;
+pub mod solana_network;
+pub mod solana_network_config;
+pub mod stake_partition;
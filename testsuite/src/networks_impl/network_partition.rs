@@ -0,0 +1,432 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::{HashMap, HashSet};
+
+use kurtosis_rust_lib::{core_api_bindings::api_container_api::PartitionConnectionInfo, networks::network_context::PartitionId, services::service::ServiceId};
+
+const ISOLATED_PARTITION_ID_STR: &str = "isolated";
+const REST_PARTITION_ID_STR: &str = "rest";
+const MINORITY_PARTITION_ID_STR: &str = "minority";
+const MAJORITY_PARTITION_ID_STR: &str = "majority";
+const RING_PARTITION_ID_PREFIX: &str = "ring-";
+const BRIDGE_LEFT_PARTITION_ID_STR: &str = "bridge-left";
+const BRIDGE_PARTITION_ID_STR: &str = "bridge";
+const BRIDGE_RIGHT_PARTITION_ID_STR: &str = "bridge-right";
+// Services that every other group can reach (e.g. the faucet), so that partitioning the nodes doesn't also cut them off
+// from the services they depend on
+const SHARED_PARTITION_ID_STR: &str = "shared";
+
+// The connections between partitions, in the shape that NetworkContext::repartition_network takes
+pub type PartitionConnections = HashMap<PartitionId, HashMap<PartitionId, PartitionConnectionInfo>>;
+
+// A layout of the network: which named group each service is in, and which pairs of groups can & can't reach each other
+// Connections are symmetric; any pair of groups that wasn't given a connection gets the default one
+#[derive(Clone, Debug)]
+pub struct NetworkPartition {
+    groups: HashMap<PartitionId, HashSet<ServiceId>>,
+    // Keyed by the (smaller, larger) partition ID pair, so that each connection is only recorded once
+    connections: HashMap<(PartitionId, PartitionId), bool>,
+    is_default_connection_blocked: bool,
+}
+
+impl NetworkPartition {
+    pub fn builder() -> NetworkPartitionBuilder {
+        return NetworkPartitionBuilder{
+            groups: Vec::new(),
+            connections: Vec::new(),
+            is_default_connection_blocked: false,
+        };
+    }
+
+    // Cuts a single service off from everything else (shared services aside)
+    #[allow(dead_code)]
+    pub fn isolate(isolated: &ServiceId, rest: &[ServiceId], shared: &[ServiceId]) -> Result<NetworkPartition> {
+        let builder = NetworkPartition::builder()
+            .with_group(ISOLATED_PARTITION_ID_STR, vec![isolated.clone()])
+            .with_group(REST_PARTITION_ID_STR, rest.to_vec())
+            .with_blocked_connection(ISOLATED_PARTITION_ID_STR, REST_PARTITION_ID_STR)
+            .with_shared_group(shared);
+        return builder.build()
+            .context(format!("An error occurred building the partition isolating service '{}'", isolated));
+    }
+
+    // Splits the weighted services into a minority side holding at most the given fraction of the total weight and a
+    // majority side holding the rest, with the two sides unable to reach each other
+    #[allow(dead_code)]
+    pub fn split_by_weight(weighted: &[(ServiceId, u64)], minority_fraction: f64, shared: &[ServiceId]) -> Result<NetworkPartition> {
+        if !(minority_fraction > 0.0 && minority_fraction < 1.0) {
+            return Err(anyhow!(
+                "Minority fraction must be strictly between 0 and 1, but was {}",
+                minority_fraction,
+            ));
+        }
+        let total_weight: u64 = weighted.iter().map(|(_, weight)| weight).sum();
+        let max_minority_weight = (total_weight as f64 * minority_fraction) as u64;
+        let (minority, majority, minority_weight) = NetworkPartition::pick_by_weight(weighted, max_minority_weight);
+        debug!(
+            "Split weighted services into minority {:?} with weight {} and majority {:?} with weight {}",
+            minority,
+            minority_weight,
+            majority,
+            total_weight - minority_weight,
+        );
+
+        let builder = NetworkPartition::builder()
+            .with_group(MINORITY_PARTITION_ID_STR, minority)
+            .with_group(MAJORITY_PARTITION_ID_STR, majority)
+            .with_blocked_connection(MINORITY_PARTITION_ID_STR, MAJORITY_PARTITION_ID_STR)
+            .with_shared_group(shared);
+        return builder.build()
+            .context(format!("An error occurred building the partition splitting off at most {} of the weight", minority_fraction));
+    }
+
+    // Greedily picks services from the heaviest down, skipping any that would take the picked weight over the max, so
+    // that the picked weight gets as close to the max as it can without going over
+    // Returns: the picked services, the rest of the services, and the picked weight
+    pub fn pick_by_weight(weighted: &[(ServiceId, u64)], max_picked_weight: u64) -> (Vec<ServiceId>, Vec<ServiceId>, u64) {
+        let mut heaviest_first: Vec<&(ServiceId, u64)> = weighted.iter().collect();
+        // Ties are broken by ID so that the same weights always give the same pick
+        heaviest_first.sort_by(|(id_a, weight_a), (id_b, weight_b)| weight_b.cmp(weight_a).then(id_a.cmp(id_b)));
+        let mut picked: Vec<ServiceId> = Vec::new();
+        let mut rest: Vec<ServiceId> = Vec::new();
+        let mut picked_weight: u64 = 0;
+        for (service_id, weight) in heaviest_first {
+            if picked_weight + weight <= max_picked_weight {
+                picked.push(service_id.clone());
+                picked_weight += weight;
+            } else {
+                rest.push(service_id.clone());
+            }
+        }
+        return (picked, rest, picked_weight);
+    }
+
+    // Arranges the groups in a ring, where each group can only reach the groups on either side of it
+    #[allow(dead_code)]
+    pub fn ring(groups: Vec<Vec<ServiceId>>, shared: &[ServiceId]) -> Result<NetworkPartition> {
+        let num_groups = groups.len();
+        // With fewer than 3 groups, every group is next to every other one and so nothing would get blocked
+        if num_groups < 3 {
+            return Err(anyhow!(
+                "A ring needs at least 3 groups, but only {} were given",
+                num_groups,
+            ));
+        }
+        let mut builder = NetworkPartition::builder()
+            .with_default_connection_blocked(true);
+        for (i, services) in groups.into_iter().enumerate() {
+            let partition_id = NetworkPartition::get_ring_partition_id(i);
+            let next_partition_id = NetworkPartition::get_ring_partition_id((i + 1) % num_groups);
+            builder = builder
+                .with_group(&partition_id, services)
+                .with_unblocked_connection(&partition_id, &next_partition_id);
+        }
+        builder = builder.with_shared_group(shared);
+        return builder.build()
+            .context(format!("An error occurred building the ring partition of {} groups", num_groups));
+    }
+
+    // Blocks the left and right groups from each other, leaving the bridge group as the only path between them
+    #[allow(dead_code)]
+    pub fn bridge(left: Vec<ServiceId>, bridge: Vec<ServiceId>, right: Vec<ServiceId>, shared: &[ServiceId]) -> Result<NetworkPartition> {
+        let builder = NetworkPartition::builder()
+            .with_group(BRIDGE_LEFT_PARTITION_ID_STR, left)
+            .with_group(BRIDGE_PARTITION_ID_STR, bridge)
+            .with_group(BRIDGE_RIGHT_PARTITION_ID_STR, right)
+            .with_blocked_connection(BRIDGE_LEFT_PARTITION_ID_STR, BRIDGE_RIGHT_PARTITION_ID_STR)
+            .with_shared_group(shared);
+        return builder.build()
+            .context("An error occurred building the bridge partition");
+    }
+
+    pub fn get_group(&self, partition_id: &str) -> Option<&HashSet<ServiceId>> {
+        return self.groups.get(partition_id);
+    }
+
+    pub fn get_service_ids(&self) -> HashSet<ServiceId> {
+        return self.groups.values().flatten().cloned().collect();
+    }
+
+    // Returns: whether the two services can reach each other, or None if either of them isn't in the partition
+    #[allow(dead_code)]
+    pub fn can_reach(&self, service_a: &ServiceId, service_b: &ServiceId) -> Option<bool> {
+        let partition_a = self.get_partition_id_of(service_a)?;
+        let partition_b = self.get_partition_id_of(service_b)?;
+        if partition_a == partition_b {
+            return Some(true);
+        }
+        let key = NetworkPartition::get_connection_key(partition_a, partition_b);
+        let is_blocked = self.connections.get(&key).copied().unwrap_or(self.is_default_connection_blocked);
+        return Some(!is_blocked);
+    }
+
+    // Translates the partition into the arguments that NetworkContext::repartition_network takes
+    pub fn to_repartition_args(&self) -> (HashMap<PartitionId, HashSet<ServiceId>>, PartitionConnections, PartitionConnectionInfo) {
+        let partition_services = self.groups.clone();
+        let mut partition_connections: PartitionConnections = HashMap::new();
+        for ((partition_a, partition_b), is_blocked) in &self.connections {
+            partition_connections.entry(partition_a.clone())
+                .or_default()
+                .insert(partition_b.clone(), PartitionConnectionInfo{
+                    is_blocked: *is_blocked,
+                });
+        }
+        let default_connection_info = PartitionConnectionInfo{
+            is_blocked: self.is_default_connection_blocked,
+        };
+        return (partition_services, partition_connections, default_connection_info);
+    }
+
+    fn get_partition_id_of(&self, service_id: &ServiceId) -> Option<&PartitionId> {
+        return self.groups.iter()
+            .find(|(_, services)| services.contains(service_id))
+            .map(|(partition_id, _)| partition_id);
+    }
+
+    fn get_ring_partition_id(i: usize) -> PartitionId {
+        return format!("{}{}", RING_PARTITION_ID_PREFIX, i);
+    }
+
+    fn get_connection_key(partition_a: &str, partition_b: &str) -> (PartitionId, PartitionId) {
+        if partition_a <= partition_b {
+            return (partition_a.to_owned(), partition_b.to_owned());
+        }
+        return (partition_b.to_owned(), partition_a.to_owned());
+    }
+}
+
+// Declares the groups & connections of a NetworkPartition; nothing gets validated until build is called
+pub struct NetworkPartitionBuilder {
+    groups: Vec<(PartitionId, Vec<ServiceId>)>,
+    connections: Vec<(PartitionId, PartitionId, bool)>,
+    is_default_connection_blocked: bool,
+}
+
+impl NetworkPartitionBuilder {
+    pub fn with_group(mut self, partition_id: &str, service_ids: Vec<ServiceId>) -> NetworkPartitionBuilder {
+        self.groups.push((partition_id.to_owned(), service_ids));
+        return self;
+    }
+
+    pub fn with_connection(mut self, partition_a: &str, partition_b: &str, is_blocked: bool) -> NetworkPartitionBuilder {
+        self.connections.push((partition_a.to_owned(), partition_b.to_owned(), is_blocked));
+        return self;
+    }
+
+    pub fn with_blocked_connection(self, partition_a: &str, partition_b: &str) -> NetworkPartitionBuilder {
+        return self.with_connection(partition_a, partition_b, true);
+    }
+
+    pub fn with_unblocked_connection(self, partition_a: &str, partition_b: &str) -> NetworkPartitionBuilder {
+        return self.with_connection(partition_a, partition_b, false);
+    }
+
+    // Sets whether groups without an explicit connection between them can reach each other (they can, by default)
+    #[allow(dead_code)]
+    pub fn with_default_connection_blocked(mut self, is_blocked: bool) -> NetworkPartitionBuilder {
+        self.is_default_connection_blocked = is_blocked;
+        return self;
+    }
+
+    // Adds a group that every group declared so far can reach; does nothing if there are no shared services
+    pub fn with_shared_group(mut self, service_ids: &[ServiceId]) -> NetworkPartitionBuilder {
+        if service_ids.is_empty() {
+            return self;
+        }
+        let other_partition_ids: Vec<PartitionId> = self.groups.iter().map(|(partition_id, _)| partition_id.clone()).collect();
+        self = self.with_group(SHARED_PARTITION_ID_STR, service_ids.to_vec());
+        for partition_id in other_partition_ids {
+            self = self.with_unblocked_connection(SHARED_PARTITION_ID_STR, &partition_id);
+        }
+        return self;
+    }
+
+    pub fn build(self) -> Result<NetworkPartition> {
+        let mut groups: HashMap<PartitionId, HashSet<ServiceId>> = HashMap::new();
+        let mut partition_id_by_service: HashMap<ServiceId, PartitionId> = HashMap::new();
+        for (partition_id, service_ids) in self.groups {
+            if partition_id.is_empty() {
+                return Err(anyhow!(
+                    "Partition IDs must not be empty",
+                ));
+            }
+            if groups.contains_key(&partition_id) {
+                return Err(anyhow!(
+                    "Partition '{}' was declared more than once",
+                    partition_id,
+                ));
+            }
+            // Kurtosis accepts empty partitions, but they're almost always a mistake in the test (e.g. a split that put
+            // everything on one side)
+            if service_ids.is_empty() {
+                return Err(anyhow!(
+                    "Partition '{}' has no services",
+                    partition_id,
+                ));
+            }
+            for service_id in &service_ids {
+                if let Some(other_partition_id) = partition_id_by_service.insert(service_id.clone(), partition_id.clone()) {
+                    return Err(anyhow!(
+                        "Service '{}' is in both partition '{}' and partition '{}'",
+                        service_id,
+                        other_partition_id,
+                        partition_id,
+                    ));
+                }
+            }
+            groups.insert(partition_id, service_ids.into_iter().collect());
+        }
+
+        let mut connections: HashMap<(PartitionId, PartitionId), bool> = HashMap::new();
+        for (partition_a, partition_b, is_blocked) in self.connections {
+            for partition_id in &[&partition_a, &partition_b] {
+                if !groups.contains_key(*partition_id) {
+                    return Err(anyhow!(
+                        "Connection between '{}' and '{}' refers to partition '{}', which wasn't declared",
+                        partition_a,
+                        partition_b,
+                        partition_id,
+                    ));
+                }
+            }
+            if partition_a == partition_b {
+                return Err(anyhow!(
+                    "Partition '{}' can't have a connection to itself",
+                    partition_a,
+                ));
+            }
+            let key = NetworkPartition::get_connection_key(&partition_a, &partition_b);
+            match connections.insert(key, is_blocked) {
+                Some(existing_is_blocked) if existing_is_blocked != is_blocked => {
+                    return Err(anyhow!(
+                        "Connection between '{}' and '{}' was declared as both blocked and unblocked",
+                        partition_a,
+                        partition_b,
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(NetworkPartition{
+            groups,
+            connections,
+            is_default_connection_blocked: self.is_default_connection_blocked,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kurtosis_rust_lib::services::service::ServiceId;
+
+    use super::NetworkPartition;
+
+    fn ids(names: &[&str]) -> Vec<ServiceId> {
+        return names.iter().map(|name| name.to_string()).collect();
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_partitions() {
+        // Service in two groups
+        assert!(NetworkPartition::builder()
+            .with_group("a", ids(&["node-0"]))
+            .with_group("b", ids(&["node-0"]))
+            .build().is_err());
+        // Duplicate group
+        assert!(NetworkPartition::builder()
+            .with_group("a", ids(&["node-0"]))
+            .with_group("a", ids(&["node-1"]))
+            .build().is_err());
+        // Empty group
+        assert!(NetworkPartition::builder()
+            .with_group("a", ids(&[]))
+            .build().is_err());
+        // Connection to an undeclared group
+        assert!(NetworkPartition::builder()
+            .with_group("a", ids(&["node-0"]))
+            .with_blocked_connection("a", "b")
+            .build().is_err());
+        // Conflicting connections, declared in opposite directions
+        assert!(NetworkPartition::builder()
+            .with_group("a", ids(&["node-0"]))
+            .with_group("b", ids(&["node-1"]))
+            .with_blocked_connection("a", "b")
+            .with_unblocked_connection("b", "a")
+            .build().is_err());
+    }
+
+    #[test]
+    fn test_connections_are_symmetric_and_fall_back_to_default() {
+        let partition = NetworkPartition::builder()
+            .with_group("a", ids(&["node-0"]))
+            .with_group("b", ids(&["node-1"]))
+            .with_group("c", ids(&["node-2"]))
+            .with_blocked_connection("b", "a")
+            .build().unwrap();
+        let (node0, node1, node2) = ("node-0".to_owned(), "node-1".to_owned(), "node-2".to_owned());
+        assert_eq!(partition.can_reach(&node0, &node1), Some(false));
+        assert_eq!(partition.can_reach(&node1, &node0), Some(false));
+        assert_eq!(partition.can_reach(&node0, &node2), Some(true));
+        assert_eq!(partition.can_reach(&node0, &"node-3".to_owned()), None);
+
+        let (_, connections, default_connection) = partition.to_repartition_args();
+        assert!(connections["a"]["b"].is_blocked);
+        assert!(!connections.contains_key("b"));
+        assert!(!default_connection.is_blocked);
+    }
+
+    #[test]
+    fn test_isolate_keeps_shared_services_reachable() {
+        let partition = NetworkPartition::isolate(&"node-0".to_owned(), &ids(&["node-1", "node-2"]), &ids(&["faucet"])).unwrap();
+        let (node0, node1, faucet) = ("node-0".to_owned(), "node-1".to_owned(), "faucet".to_owned());
+        assert_eq!(partition.can_reach(&node0, &node1), Some(false));
+        assert_eq!(partition.can_reach(&node0, &faucet), Some(true));
+        assert_eq!(partition.can_reach(&node1, &faucet), Some(true));
+    }
+
+    #[test]
+    fn test_split_by_weight_fills_minority_without_exceeding_fraction() {
+        let weighted = vec![
+            ("node-0".to_owned(), 40),
+            ("node-1".to_owned(), 30),
+            ("node-2".to_owned(), 20),
+            ("node-3".to_owned(), 10),
+        ];
+        let partition = NetworkPartition::split_by_weight(&weighted, 0.3, &[]).unwrap();
+        // node-0 is too heavy on its own, so the minority is filled by node-1 and nothing else fits
+        assert_eq!(partition.get_group("minority").unwrap().len(), 1);
+        assert!(partition.get_group("minority").unwrap().contains("node-1"));
+        assert_eq!(partition.get_group("majority").unwrap().len(), 3);
+
+        let partition = NetworkPartition::split_by_weight(&weighted, 0.5, &[]).unwrap();
+        let minority = partition.get_group("minority").unwrap();
+        assert!(minority.contains("node-0") && minority.contains("node-3"));
+
+        // Nothing is light enough to fit in a 5% minority
+        assert!(NetworkPartition::split_by_weight(&weighted, 0.05, &[]).is_err());
+        assert!(NetworkPartition::split_by_weight(&weighted, 1.0, &[]).is_err());
+    }
+
+    #[test]
+    fn test_ring_only_connects_neighbours() {
+        let groups = vec![ids(&["node-0"]), ids(&["node-1"]), ids(&["node-2"]), ids(&["node-3"])];
+        let partition = NetworkPartition::ring(groups, &ids(&["faucet"])).unwrap();
+        let node = |i: usize| format!("node-{}", i);
+        assert_eq!(partition.can_reach(&node(0), &node(1)), Some(true));
+        assert_eq!(partition.can_reach(&node(3), &node(0)), Some(true));
+        assert_eq!(partition.can_reach(&node(0), &node(2)), Some(false));
+        assert_eq!(partition.can_reach(&node(1), &node(3)), Some(false));
+        assert_eq!(partition.can_reach(&node(2), &"faucet".to_owned()), Some(true));
+
+        assert!(NetworkPartition::ring(vec![ids(&["node-0"]), ids(&["node-1"])], &[]).is_err());
+    }
+
+    #[test]
+    fn test_bridge_only_blocks_the_outer_groups() {
+        let partition = NetworkPartition::bridge(ids(&["node-0"]), ids(&["node-1"]), ids(&["node-2"]), &[]).unwrap();
+        let node = |i: usize| format!("node-{}", i);
+        assert_eq!(partition.can_reach(&node(0), &node(2)), Some(false));
+        assert_eq!(partition.can_reach(&node(0), &node(1)), Some(true));
+        assert_eq!(partition.can_reach(&node(1), &node(2)), Some(true));
+    }
+}
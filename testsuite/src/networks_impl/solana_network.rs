@@ -1,44 +1,115 @@
 use anyhow::{Context, Result, anyhow};
-use std::{collections::{HashMap, HashSet}, rc::Rc, time::Duration};
+use std::{collections::{HashMap, HashSet}, rc::Rc, thread::sleep, time::{Duration, Instant}};
 
-use kurtosis_rust_lib::{core_api_bindings::api_container_api::{PartitionConnectionInfo}, networks::{network::Network, network_context::NetworkContext}, services::{availability_checker::AvailabilityChecker, service::ServiceId}};
+use futures::future::join_all;
+use kurtosis_rust_lib::{networks::{network::Network, network_context::NetworkContext}, services::{availability_checker::AvailabilityChecker, service::ServiceId}};
 
-use crate::services_impl::{faucet::{faucet_container_initializer::{FaucetContainerInitializer}, faucet_service::FaucetService}, validator::{validator_container_initializer::ValidatorContainerInitializer, validator_service::ValidatorService}};
+use tokio::runtime::Runtime;
 
-use super::genesis_config::{FAUCET_KEYPAIR, BANK_HASH, GENESIS_HASH, SHRED_VERSION, GENESIS_BOOTSTRAPPER_KEYPAIRS};
+use crate::sdk_impl::{keypair::Keypair, pubkey::Pubkey, system_instruction, transaction::Transaction, vote_instruction::{self, VOTE_STATE_SIZE, VoteInit}};
+use crate::services_impl::{faucet::{faucet_container_initializer::{FaucetContainerInitializer}, faucet_service::FaucetService}, validator::{commitment_config::CommitmentConfig, pubsub_client::PubsubSubscription, rpc_response::RpcVersionInfo, validator_container_initializer::{BootstrapperLedgerOptions, ValidatorContainerInitializer}, validator_service::ValidatorService}};
+
+use super::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, network_partition::NetworkPartition, solana_network_config::SolanaNetworkConfig, stake_partition::StakePartition};
 
 const FAUCET_SERVICE_ID_STR: &str = "faucet";
 const BOOTSTRAPPER_SERVICE_ID_PREFIX: &str = "bootstrapper-";
+const VALIDATOR_SERVICE_ID_PREFIX: &str = "validator-";
+const RPC_NODE_SERVICE_ID_PREFIX: &str = "rpc-node-";
 
 const FAUCET_PARTITION_ID_STR: &str = "faucet-partition";
 const BOOTSTRAPPERS_PARTITION1_ID_STR: &str = "bootstrappers-partition1";
 const BOOTSTRAPPERS_PARTITION2_ID_STR: &str = "bootstrappers-partition2";
+const HEALED_PARTITION_ID_STR: &str = "healed";
 
 const TIME_BETWEEN_BOOTSTRAPPER_AVAILABILITY_POLLS: Duration = Duration::from_secs(5);
 const NUM_RETRIES_FOR_BOOTSTRAPPER_AVAILBILITY: u32 = 30;
 
+// A validator pays for its votes out of its identity account, so joiners get enough to vote for a long while
+const JOINER_IDENTITY_LAMPORTS: u64 = 10_000_000_000;
+// Same as the 'solana create-vote-account' default
+const JOINER_VOTE_ACCOUNT_COMMISSION: u8 = 100;
+const JOINER_VOTE_ACCOUNT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+// A joiner has to download a snapshot and replay everything since, so it gets longer to catch up than the other timeouts
+const MAX_JOINER_CATCHUP_TIME: Duration = Duration::from_secs(300);
+const TIME_BETWEEN_JOINER_CATCHUP_POLLS: Duration = Duration::from_secs(2);
+
+// How long a node gets to exit after being sent SIGTERM when it's stopped cleanly
+const NODE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+// By the time the container gets removed, either the node has already exited (a clean stop) or it's meant to be SIGKILLed
+// on the spot without getting to flush anything, the same as if the machine lost power (a kill)
+const NODE_CONTAINER_STOP_TIMEOUT_SECONDS: u64 = 0;
+
+// Where to get each node's stake from when planning stake-weighted partitions
+#[derive(Clone, Copy, Debug)]
+pub enum StakeSource {
+    // The stake the bootstrappers were given in genesis; validators that joined later have none
+    Genesis,
+    // The activated stake that the first running bootstrapper reports for each vote account, at the given commitment
+    Live(CommitmentConfig),
+}
+
+// The staked nodes with their stakes, each unstaked node paired with the bootstrapper it entered the cluster through, and
+// the stake of the whole cluster
+type StakePartitionInputs = (Vec<(ServiceId, u64)>, Vec<(ServiceId, ServiceId)>, u64);
+
 pub struct SolanaNetwork {
     network_ctx: NetworkContext,
-    ledger_dir_artifact_key: String,
+    ledger_artifact: Rc<LedgerArtifact>,
+    genesis_config: Rc<GenesisConfig>,
+    config: SolanaNetworkConfig,
     faucet: Option<Rc<FaucetService>>,
     bootstrappers: Vec<Rc<ValidatorService>>,
+    // Validators that weren't in genesis, and so joined once the cluster was already running
+    validators: Vec<Rc<ValidatorService>>,
+    // The identity & vote account keypairs of each validator that joined later, indexed the same as the validators, so
+    // that a validator can be restarted as the same node
+    validator_keypairs: Vec<(Keypair, Keypair)>,
+    // The index of the bootstrapper that each validator that joined later last (re)started through, indexed the same as
+    // the validators
+    validator_entrypoint_idxs: Vec<usize>,
+    // Non-voting nodes that serve the full RPC API, sitting in front of the bootstrappers the way they do for real clients
+    rpc_nodes: Vec<Rc<ValidatorService>>,
+    // Nodes that have been stopped or killed and not restarted yet, whose services are no longer in the network
+    stopped_service_ids: HashSet<ServiceId>,
+    // The image each bootstrapper or validator was last started on, which later restarts keep unless told otherwise;
+    // nodes that aren't in here run the validator image
+    node_images: HashMap<ServiceId, String>,
+    // Used to drive the async queries that get fanned out to all the nodes at once
+    runtime: Runtime,
 }
 
 impl SolanaNetwork {
-    pub fn new(network_ctx: NetworkContext, ledger_dir_artifact_key: String) -> SolanaNetwork {
-        return SolanaNetwork {
+    pub fn new(
+        network_ctx: NetworkContext,
+        ledger_artifact: Rc<LedgerArtifact>,
+        genesis_config: Rc<GenesisConfig>,
+        config: SolanaNetworkConfig,
+    ) -> Result<SolanaNetwork> {
+        let runtime = Runtime::new()
+            .context("An error occurred creating the runtime for the network's async queries")?;
+        return Ok(SolanaNetwork {
             network_ctx,
-            ledger_dir_artifact_key,
+            ledger_artifact,
+            genesis_config,
+            config,
             faucet: None,
             bootstrappers: Vec::new(),
-        }
+            validators: Vec::new(),
+            validator_keypairs: Vec::new(),
+            validator_entrypoint_idxs: Vec::new(),
+            rpc_nodes: Vec::new(),
+            stopped_service_ids: HashSet::new(),
+            node_images: HashMap::new(),
+            runtime,
+        });
     }
 
     pub fn get_num_bootstrappers(&self) -> usize {
-        return GENESIS_BOOTSTRAPPER_KEYPAIRS.len();
+        return self.config.get_num_bootstrappers();
     }
 
-    pub fn start_faucet_and_bootstrappers(&mut self, faucet_docker_image: &str, bootstrapper_docker_image: &str) -> Result<()> {
+    pub fn start_faucet_and_bootstrappers(&mut self) -> Result<()> {
         // Validation
         if self.faucet.is_some() {
             return Err(anyhow!(
@@ -50,11 +121,14 @@ impl SolanaNetwork {
                 "Cannot start bootstrappers because some already exist",
             ))
         }
+        // A ledger that doesn't match the genesis config would leave the bootstrappers unable to start, so catch it first
+        self.ledger_artifact.verify(&self.genesis_config)
+            .context("An error occurred verifying the ledger artifact against the genesis config")?;
 
         // Start the faucet
         let initializer = FaucetContainerInitializer::new(
-            faucet_docker_image.to_owned(),
-            FAUCET_KEYPAIR.keypair_json.to_owned(),
+            self.config.get_faucet_image().to_owned(),
+            self.genesis_config.get_faucet().get_keypair().clone(),
         );
         let (faucet, checker) = self.network_ctx.add_service(&FAUCET_SERVICE_ID_STR.to_owned(), &initializer)
             .context("An error occurred adding the faucet")?;
@@ -64,46 +138,21 @@ impl SolanaNetwork {
 
         // Start bootstrappers
         info!("Starting bootstrappers...");
-        let num_bootstrappers = GENESIS_BOOTSTRAPPER_KEYPAIRS.len();
+        let num_bootstrappers = self.get_num_bootstrappers();
         let mut bootstrapper_checkers: Vec<AvailabilityChecker> = Vec::new();
         for i in 0..num_bootstrappers {
             info!("Starting bootstrapper #{}...", i);
-            let new_bootstrapper_keypairs = GENESIS_BOOTSTRAPPER_KEYPAIRS.get(i)
-                .context(format!("Needed genesis bootstrapper keypair #{}, but genesis config doesn't have that keypair", i))?;
-            let initializer;
-            if i == 0 {
-                initializer = ValidatorContainerInitializer::for_first_bootstrapper(
-                    bootstrapper_docker_image.to_owned(), 
-                    BANK_HASH.to_owned(),
-                    GENESIS_HASH.to_owned(),
-                    SHRED_VERSION,
-                    self.ledger_dir_artifact_key.clone(),
-                    new_bootstrapper_keypairs.identity.keypair_json.to_owned(),
-                    new_bootstrapper_keypairs.vote_account.keypair_json.to_owned(),
-                    faucet.clone(),
-                );
-            } else {
-                let first_boostrapper = self.bootstrappers.get(0)
-                    .context("Trying to start an extra bootstrapper, but no first bootstrapper was found")?;
-                initializer = ValidatorContainerInitializer::for_extra_bootstrapper(
-                    bootstrapper_docker_image.to_owned(), 
-                    BANK_HASH.to_owned(),
-                    GENESIS_HASH.to_owned(),
-                    SHRED_VERSION,
-                    self.ledger_dir_artifact_key.clone(),
-                    new_bootstrapper_keypairs.identity.keypair_json.to_owned(),
-                    new_bootstrapper_keypairs.vote_account.keypair_json.to_owned(),
-                    faucet.clone(),
-                    first_boostrapper.clone(),
-                );
-
-            }
-            let service_id: ServiceId = format!("{}{}", BOOTSTRAPPER_SERVICE_ID_PREFIX, i);
+            // Extra bootstrappers find the rest of the cluster through the first one
+            let entrypoint = self.bootstrappers.first().cloned();
+            let docker_image = self.config.get_bootstrapper_image(i).to_owned();
+            let initializer = self.get_bootstrapper_initializer(i, docker_image.clone(), entrypoint)?;
+            let service_id = SolanaNetwork::get_bootstrapper_service_id(i);
             let (bootstrapper, checker) = self.network_ctx.add_service(&service_id, &initializer)
-                .context(format!("An error occurred adding bootstrapper #{}", i))?;
+                .context(format!("An error occurred adding bootstrapper #{} on image '{}'", i, docker_image))?;
             self.bootstrappers.push(bootstrapper);
             bootstrapper_checkers.push(checker);
-            info!("Bootstrapper #{} started", i);
+            info!("Bootstrapper #{} started on image '{}'", i, docker_image);
+            self.node_images.insert(service_id, docker_image);
         }
         info!("Bootstrappers started");
 
@@ -111,9 +160,9 @@ impl SolanaNetwork {
         // Do availability-checking after starting all the nodes, because the nodes can't ever be up unless all of them
         // are started due to the genesis having all the nodes as bootstrappers
         info!("Waiting for bootstrappers to become available...");
-        for i in 0..num_bootstrappers {
+        for (i, (checker, bootstrapper)) in bootstrapper_checkers.iter().zip(&self.bootstrappers).enumerate() {
             info!("Waiting for bootstrapper #{} to become available...", i);
-            checker.wait_for_startup(&TIME_BETWEEN_BOOTSTRAPPER_AVAILABILITY_POLLS, NUM_RETRIES_FOR_BOOTSTRAPPER_AVAILBILITY)
+            SolanaNetwork::wait_for_node_startup(checker, bootstrapper)
                 .context(format!("An error occurred waiting for validator #{} to become available", i))?;
             info!("Bootstrapper #{} became available", i);
         }
@@ -122,62 +171,699 @@ impl SolanaNetwork {
         return Ok(());
     }
 
+    // Starts a validator that isn't in genesis: it gets a freshly-funded identity & vote account, fetches genesis and a
+    // snapshot from the lowest-numbered running bootstrapper, and then catches up with the cluster
+    // Returns: the new validator's index, for use with get_validator
+    pub fn add_validator(&mut self) -> Result<usize> {
+        let faucet = self.faucet.clone()
+            .context("Cannot add a validator before the faucet has been started")?;
+        let entrypoint_idx = self.get_running_bootstrapper_idx(None)
+            .context("Cannot add a validator when no bootstrappers are running")?;
+        let entrypoint = self.bootstrappers[entrypoint_idx].clone();
+        let i = self.validators.len();
+
+        info!("Creating vote account for validator #{}...", i);
+        let identity_keypair = Keypair::new();
+        let vote_account_keypair = Keypair::new();
+        self.create_vote_account(&entrypoint, &identity_keypair, &vote_account_keypair)
+            .context(format!("An error occurred creating the vote account for validator #{}", i))?;
+        info!("Created vote account '{}' for validator #{} with identity '{}'", vote_account_keypair.pubkey(), i, identity_keypair.pubkey());
+        let expected_identity = identity_keypair.pubkey();
+        self.validator_keypairs.push((identity_keypair, vote_account_keypair));
+
+        info!("Starting validator #{}...", i);
+        let docker_image = self.config.get_validator_image().to_owned();
+        let initializer = self.get_validator_initializer(i, docker_image, faucet, entrypoint.clone())?;
+        let service_id = SolanaNetwork::get_validator_service_id(i);
+        let (validator, checker) = self.network_ctx.add_service(&service_id, &initializer)
+            .context(format!("An error occurred adding validator #{}", i))?;
+        self.validators.push(validator.clone());
+        self.validator_entrypoint_idxs.push(entrypoint_idx);
+        SolanaNetwork::wait_for_node_startup(&checker, &validator)
+            .context(format!("An error occurred waiting for validator #{} to start", i))?;
+        // The vote account was created for this identity, so a node running as anything else would never vote
+        let identity = validator.get_identity()
+            .context(format!("An error occurred getting the identity of validator #{}", i))?;
+        if identity != expected_identity {
+            return Err(anyhow!(
+                "Validator #{} is running as identity '{}', but it was started with identity '{}'",
+                i,
+                identity,
+                expected_identity,
+            ));
+        }
+        info!("Validator #{} started; waiting for it to catch up with the cluster...", i);
+
+        SolanaNetwork::wait_for_catchup(&validator, &entrypoint)
+            .context(format!("An error occurred waiting for validator #{} to catch up with the cluster", i))?;
+        info!("Validator #{} caught up with the cluster", i);
+        return Ok(i);
+    }
+
+    pub fn get_num_validators(&self) -> usize {
+        return self.validators.len();
+    }
+
+    pub fn get_validator(&self, i: usize) -> Result<&ValidatorService> {
+        let validator = self.validators.get(i)
+            .context(format!("Validator #{} doesn't exist", i))?;
+        return Ok(validator);
+    }
+
+    // Shuts the bootstrapper down cleanly and removes its container; its ledger is kept so that it can be restarted
+    pub fn stop_bootstrapper(&mut self, i: usize) -> Result<()> {
+        let bootstrapper = self.bootstrappers.get(i).cloned()
+            .context(format!("Bootstrapper #{} doesn't exist", i))?;
+        self.stop_node(&SolanaNetwork::get_bootstrapper_service_id(i), &bootstrapper, true)
+            .context(format!("An error occurred stopping bootstrapper #{}", i))?;
+        return Ok(());
+    }
+
+    // Removes the bootstrapper's container without giving it a chance to shut down, as if it had crashed
+    pub fn kill_bootstrapper(&mut self, i: usize) -> Result<()> {
+        let bootstrapper = self.bootstrappers.get(i).cloned()
+            .context(format!("Bootstrapper #{} doesn't exist", i))?;
+        self.stop_node(&SolanaNetwork::get_bootstrapper_service_id(i), &bootstrapper, false)
+            .context(format!("An error occurred killing bootstrapper #{}", i))?;
+        return Ok(());
+    }
+
+    // Starts the bootstrapper back up with the same identity, ledger, and image (stopping it first if it's still
+    // running), and waits for it to catch up with another running bootstrapper
+    pub fn restart_bootstrapper(&mut self, i: usize) -> Result<()> {
+        let docker_image = self.get_node_image(&SolanaNetwork::get_bootstrapper_service_id(i)).to_owned();
+        return self.restart_bootstrapper_with_image(i, &docker_image);
+    }
+
+    // Same as restart_bootstrapper, but switches the bootstrapper over to the given image, e.g. to upgrade it
+    pub fn restart_bootstrapper_with_image(&mut self, i: usize, docker_image: &str) -> Result<()> {
+        let service_id = SolanaNetwork::get_bootstrapper_service_id(i);
+        if !self.stopped_service_ids.contains(&service_id) {
+            self.stop_bootstrapper(i)?;
+        }
+        // The other nodes only know the bootstrapper's old address, so it has to reach out to them
+        let entrypoint = self.get_running_bootstrapper(Some(i));
+        let initializer = self.get_bootstrapper_initializer(i, docker_image.to_owned(), entrypoint.clone())?;
+        let bootstrapper = self.restart_node(&service_id, &initializer, entrypoint)
+            .context(format!("An error occurred restarting bootstrapper #{} on image '{}'", i, docker_image))?;
+        self.bootstrappers[i] = bootstrapper;
+        self.node_images.insert(service_id, docker_image.to_owned());
+        return Ok(());
+    }
+
+    // Shuts the validator down cleanly and removes its container; its ledger is kept so that it can be restarted
+    pub fn stop_validator(&mut self, i: usize) -> Result<()> {
+        let validator = self.validators.get(i).cloned()
+            .context(format!("Validator #{} doesn't exist", i))?;
+        self.stop_node(&SolanaNetwork::get_validator_service_id(i), &validator, true)
+            .context(format!("An error occurred stopping validator #{}", i))?;
+        return Ok(());
+    }
+
+    // Removes the validator's container without giving it a chance to shut down, as if it had crashed
+    pub fn kill_validator(&mut self, i: usize) -> Result<()> {
+        let validator = self.validators.get(i).cloned()
+            .context(format!("Validator #{} doesn't exist", i))?;
+        self.stop_node(&SolanaNetwork::get_validator_service_id(i), &validator, false)
+            .context(format!("An error occurred killing validator #{}", i))?;
+        return Ok(());
+    }
+
+    // Starts the validator back up with the same identity, ledger, and image (stopping it first if it's still running),
+    // and waits for it to catch up with the cluster
+    pub fn restart_validator(&mut self, i: usize) -> Result<()> {
+        let docker_image = self.get_node_image(&SolanaNetwork::get_validator_service_id(i)).to_owned();
+        return self.restart_validator_with_image(i, &docker_image);
+    }
+
+    // Same as restart_validator, but switches the validator over to the given image, e.g. to upgrade it
+    pub fn restart_validator_with_image(&mut self, i: usize, docker_image: &str) -> Result<()> {
+        let service_id = SolanaNetwork::get_validator_service_id(i);
+        if !self.stopped_service_ids.contains(&service_id) {
+            self.stop_validator(i)?;
+        }
+        let faucet = self.faucet.clone()
+            .context("Cannot restart a validator without a faucet")?;
+        let entrypoint_idx = self.get_running_bootstrapper_idx(None)
+            .context(format!("Cannot restart validator #{} because no bootstrappers are running", i))?;
+        let entrypoint = self.bootstrappers[entrypoint_idx].clone();
+        let initializer = self.get_validator_initializer(i, docker_image.to_owned(), faucet, entrypoint.clone())?;
+        let validator = self.restart_node(&service_id, &initializer, Some(entrypoint))
+            .context(format!("An error occurred restarting validator #{} on image '{}'", i, docker_image))?;
+        self.validators[i] = validator;
+        self.validator_entrypoint_idxs[i] = entrypoint_idx;
+        self.node_images.insert(service_id, docker_image.to_owned());
+        return Ok(());
+    }
+
+    pub fn is_node_running(&self, service_id: &str) -> bool {
+        return !self.stopped_service_ids.contains(service_id);
+    }
+
+    // Returns: the image the node is currently running (or, if it's stopped, the image it'll be restarted on)
+    pub fn get_node_image(&self, service_id: &str) -> &str {
+        if let Some(docker_image) = self.node_images.get(service_id) {
+            return docker_image;
+        }
+        return self.config.get_validator_image();
+    }
+
+    // Asks each of the given nodes which version of Solana it's running
+    // Returns: the versions, in the same order as the given service IDs
+    pub fn get_node_versions(&self, service_ids: &[ServiceId]) -> Result<Vec<RpcVersionInfo>> {
+        let mut result = Vec::new();
+        for service_id in service_ids {
+            let version = self.get_node(service_id)?.get_version()
+                .context(format!("An error occurred getting the version of node '{}'", service_id))?;
+            result.push(version);
+        }
+        return Ok(result);
+    }
+
+    // Starts the given number of non-voting RPC nodes, spreading their entrypoints across the bootstrappers, and waits
+    // for all of them to catch up with the cluster
+    pub fn attach_rpc_nodes(&mut self, num_rpc_nodes: usize) -> Result<()> {
+        let faucet = self.faucet.clone()
+            .context("Cannot attach RPC nodes before the faucet has been started")?;
+        if self.bootstrappers.is_empty() {
+            return Err(anyhow!(
+                "Cannot attach RPC nodes before the bootstrappers have been started",
+            ));
+        }
+
+        // RPC nodes don't vote so their identities don't need funding, and we can start them all at once
+        let mut new_rpc_nodes: Vec<(usize, Rc<ValidatorService>, Rc<ValidatorService>, AvailabilityChecker)> = Vec::new();
+        for _ in 0..num_rpc_nodes {
+            let i = self.rpc_nodes.len();
+            let entrypoint = self.bootstrappers[i % self.bootstrappers.len()].clone();
+            info!("Starting RPC node #{}...", i);
+            let initializer = ValidatorContainerInitializer::for_rpc_node(
+                self.config.get_validator_image().to_owned(),
+                self.genesis_config.get_genesis_hash().to_owned(),
+                self.genesis_config.get_shred_version(),
+                Keypair::new(),
+                faucet.clone(),
+                entrypoint.clone(),
+            );
+            let service_id = SolanaNetwork::get_rpc_node_service_id(i);
+            let (rpc_node, checker) = self.network_ctx.add_service(&service_id, &initializer)
+                .context(format!("An error occurred adding RPC node #{}", i))?;
+            self.rpc_nodes.push(rpc_node.clone());
+            new_rpc_nodes.push((i, rpc_node, entrypoint, checker));
+        }
+
+        for (i, rpc_node, entrypoint, checker) in new_rpc_nodes {
+            SolanaNetwork::wait_for_node_startup(&checker, &rpc_node)
+                .context(format!("An error occurred waiting for RPC node #{} to start", i))?;
+            SolanaNetwork::wait_for_catchup(&rpc_node, &entrypoint)
+                .context(format!("An error occurred waiting for RPC node #{} to catch up with the cluster", i))?;
+            info!("RPC node #{} started and caught up with the cluster", i);
+        }
+        return Ok(());
+    }
+
+    pub fn get_num_rpc_nodes(&self) -> usize {
+        return self.rpc_nodes.len();
+    }
+
+    pub fn get_rpc_node(&self, i: usize) -> Result<&ValidatorService> {
+        let rpc_node = self.rpc_nodes.get(i)
+            .context(format!("RPC node #{} doesn't exist", i))?;
+        return Ok(rpc_node);
+    }
+
     pub fn get_bootstrapper(&self, i: usize) -> Result<&ValidatorService> {
         let bootstrapper = self.bootstrappers.get(i)
             .context(format!("Bootstrapper #{} doesn't exist", i))?;
         return Ok(bootstrapper);
     }
 
+    pub fn get_faucet(&self) -> Result<&FaucetService> {
+        let faucet = self.faucet.as_deref()
+            .context("The faucet hasn't been started")?;
+        return Ok(faucet);
+    }
+
+    // Looks up any running node (bootstrapper, validator, or RPC node) by its service ID
+    pub fn get_node(&self, service_id: &str) -> Result<&ValidatorService> {
+        if self.stopped_service_ids.contains(service_id) {
+            return Err(anyhow!(
+                "Node with service ID '{}' isn't running",
+                service_id,
+            ));
+        }
+        let bootstrappers = self.bootstrappers.iter().enumerate()
+            .map(|(i, node)| (SolanaNetwork::get_bootstrapper_service_id(i), node));
+        let validators = self.validators.iter().enumerate()
+            .map(|(i, node)| (SolanaNetwork::get_validator_service_id(i), node));
+        let rpc_nodes = self.rpc_nodes.iter().enumerate()
+            .map(|(i, node)| (SolanaNetwork::get_rpc_node_service_id(i), node));
+        for (node_service_id, node) in bootstrappers.chain(validators).chain(rpc_nodes) {
+            if node_service_id == service_id {
+                return Ok(node);
+            }
+        }
+        return Err(anyhow!(
+            "No node with service ID '{}' exists",
+            service_id,
+        ));
+    }
+
+    // Queries the slot of each of the given nodes concurrently, e.g. to compare the two sides of a partition
+    // Returns: the slots, in the same order as the given service IDs
+    pub fn get_node_slots(&self, service_ids: &[ServiceId], commitment: CommitmentConfig) -> Result<Vec<u64>> {
+        let mut nodes = Vec::new();
+        for service_id in service_ids {
+            nodes.push(self.get_node(service_id)?);
+        }
+        let slot_futures = nodes.iter().map(|node| node.get_slot_async(Some(commitment)));
+        let slot_results = self.runtime.block_on(join_all(slot_futures));
+        let mut result = Vec::new();
+        for (service_id, slot_or_err) in service_ids.iter().zip(slot_results) {
+            let slot = slot_or_err
+                .context(format!("An error occurred getting the current {} slot for node '{}'", commitment, service_id))?;
+            result.push(slot);
+        }
+        return Ok(result);
+    }
+
+    // Queries every running bootstrapper's slot concurrently, so that all the slots are sampled at (nearly) the same instant
+    // Returns: the slots, indexed by bootstrapper number, with None for the bootstrappers that are stopped
+    pub fn get_bootstrapper_slots(&self, commitment: CommitmentConfig) -> Result<Vec<Option<u64>>> {
+        let running_idxs = self.get_running_bootstrapper_idxs();
+        let slot_futures = running_idxs.iter().map(|i| self.bootstrappers[*i].get_slot_async(Some(commitment)));
+        let slot_results = self.runtime.block_on(join_all(slot_futures));
+        let mut result = vec![None; self.bootstrappers.len()];
+        for (i, slot_or_err) in running_idxs.into_iter().zip(slot_results) {
+            let slot = slot_or_err
+                .context(format!("An error occurred getting the current {} slot for bootstrapper {}", commitment, i))?;
+            result[i] = Some(slot);
+        }
+        return Ok(result);
+    }
+
+    // Queries every running bootstrapper's slot at each of the given commitment levels concurrently; each
+    // bootstrapper's slots are fetched in a single round trip so they're consistent with each other
+    // Returns: the slots, indexed by bootstrapper number and then by the commitment's position in the given slice, with
+    // None for the bootstrappers that are stopped
+    pub fn get_bootstrapper_slots_by_commitment(&self, commitments: &[CommitmentConfig]) -> Result<Vec<Option<Vec<u64>>>> {
+        let running_idxs = self.get_running_bootstrapper_idxs();
+        let slots_futures = running_idxs.iter().map(|i| self.bootstrappers[*i].get_slots_async(commitments));
+        let slots_results = self.runtime.block_on(join_all(slots_futures));
+        let mut result = vec![None; self.bootstrappers.len()];
+        for (i, slots_or_err) in running_idxs.into_iter().zip(slots_results) {
+            let slots = slots_or_err
+                .context(format!("An error occurred getting the slots for commitments {:?} for bootstrapper {}", commitments, i))?;
+            result[i] = Some(slots);
+        }
+        return Ok(result);
+    }
+
+    // Opens a root subscription to every running bootstrapper, for timing exactly when the cluster starts rooting slots again
+    // Returns: the subscriptions, indexed by bootstrapper number, with None for the bootstrappers that are stopped
+    pub fn subscribe_to_bootstrapper_roots(&self) -> Result<Vec<Option<PubsubSubscription<u64>>>> {
+        let running_idxs = self.get_running_bootstrapper_idxs();
+        let subscription_futures = running_idxs.iter().map(|i| self.bootstrappers[*i].get_pubsub_client().root_subscribe());
+        let subscription_results = self.runtime.block_on(join_all(subscription_futures));
+        let mut result: Vec<Option<PubsubSubscription<u64>>> = (0..self.bootstrappers.len()).map(|_| None).collect();
+        for (i, subscription_or_err) in running_idxs.into_iter().zip(subscription_results) {
+            let subscription = subscription_or_err
+                .context(format!("An error occurred subscribing to the roots of bootstrapper {}", i))?;
+            result[i] = Some(subscription);
+        }
+        return Ok(result);
+    }
+
     /// Splits the network into two halves, with the connection between the halves blocked (or not)
     pub fn partition_in_half_with_connection(&mut self, is_connection_blocked: bool) -> Result<()> {
-        let mut faucet_partition_services: HashSet<String> = HashSet::new();
-        faucet_partition_services.insert(FAUCET_SERVICE_ID_STR.to_owned());
-
-        let num_bootstrappers = self.get_num_bootstrappers();
+        let num_bootstrappers = self.bootstrappers.len();
         let first_id_in_second_partition = num_bootstrappers / 2;
 
-        let mut bootstrappers_partition1_services: HashSet<String> = HashSet::new();
-        for i in 0..first_id_in_second_partition {
-            let service_id: ServiceId = SolanaNetwork::get_bootstrapper_service_id(i);
-            bootstrappers_partition1_services.insert(service_id);
+        // Nodes that joined later go on the same side as the bootstrapper they entered the cluster through
+        let mut bootstrappers_partition1_services: Vec<ServiceId> = Vec::new();
+        let mut bootstrappers_partition2_services: Vec<ServiceId> = Vec::new();
+        for (service_id, entrypoint_idx) in self.get_node_service_ids_with_entrypoints() {
+            if entrypoint_idx < first_id_in_second_partition {
+                bootstrappers_partition1_services.push(service_id);
+            } else {
+                bootstrappers_partition2_services.push(service_id);
+            }
         }
 
-        let mut bootstrappers_partition2_services: HashSet<String> = HashSet::new();
-        for i in first_id_in_second_partition..num_bootstrappers {
-            let service_id = SolanaNetwork::get_bootstrapper_service_id(i);
-            bootstrappers_partition2_services.insert(service_id);
+        let partition = NetworkPartition::builder()
+            .with_group(FAUCET_PARTITION_ID_STR, vec![FAUCET_SERVICE_ID_STR.to_owned()])
+            .with_group(BOOTSTRAPPERS_PARTITION1_ID_STR, bootstrappers_partition1_services)
+            .with_group(BOOTSTRAPPERS_PARTITION2_ID_STR, bootstrappers_partition2_services)
+            .with_connection(BOOTSTRAPPERS_PARTITION1_ID_STR, BOOTSTRAPPERS_PARTITION2_ID_STR, is_connection_blocked)
+            .build()
+            .context("An error occurred building the partition that splits the network in half")?;
+        self.repartition(&partition)
+            .context(format!("An error occurred partitioning the network in half, with blocked connection = {}", is_connection_blocked))?;
+
+        return Ok(());
+    }
+
+    // Puts every service back in the same partition, undoing any previous repartitioning
+    pub fn heal_partition(&mut self) -> Result<()> {
+        let partition = NetworkPartition::builder()
+            .with_group(HEALED_PARTITION_ID_STR, self.get_all_service_ids())
+            .build()
+            .context("An error occurred building the healed partition")?;
+        self.repartition(&partition)
+            .context("An error occurred healing the network's partitions")?;
+        return Ok(());
+    }
+
+    // Returns: the stake of every bootstrapper & validator that's running, keyed by service ID
+    pub fn get_node_stakes(&self, source: StakeSource) -> Result<Vec<(ServiceId, u64)>> {
+        let (node_stakes, _) = self.get_node_and_total_stakes(source)?;
+        return Ok(node_stakes);
+    }
+
+    // Plans a partition where side A holds at most the given fraction of the cluster's stake, with any unstaked nodes
+    // going on the same side as the bootstrapper they entered the cluster through
+    pub fn plan_stake_partition(&self, side_a_fraction: f64, source: StakeSource) -> Result<StakePartition> {
+        let (staked, followers, total_stake) = self.get_stake_partition_inputs(source)?;
+        let plan = StakePartition::with_side_a_fraction(&staked, &followers, &[self.get_faucet_service_id()], total_stake, side_a_fraction)
+            .context(format!("An error occurred planning a partition with {} of the stake on side A", side_a_fraction))?;
+        return Ok(plan);
+    }
+
+    // Plans the largest partition that still falls short of a 2/3 supermajority, so that neither side should be able to root
+    pub fn plan_stake_partition_below_supermajority(&self, source: StakeSource) -> Result<StakePartition> {
+        let (staked, followers, total_stake) = self.get_stake_partition_inputs(source)?;
+        let plan = StakePartition::below_supermajority(&staked, &followers, &[self.get_faucet_service_id()], total_stake)
+            .context("An error occurred planning the largest partition below the supermajority")?;
+        return Ok(plan);
+    }
+
+    // Applies the given partition to the network; every service in the network must be in exactly one of its groups
+    pub fn repartition(&mut self, partition: &NetworkPartition) -> Result<()> {
+        let partition_service_ids = partition.get_service_ids();
+        let network_service_ids: HashSet<ServiceId> = self.get_all_service_ids().into_iter().collect();
+        let missing_service_ids: Vec<&ServiceId> = network_service_ids.difference(&partition_service_ids).collect();
+        if !missing_service_ids.is_empty() {
+            return Err(anyhow!(
+                "The partition doesn't place services {:?}, but every service in the network must be in a partition",
+                missing_service_ids,
+            ));
+        }
+        let unknown_service_ids: Vec<&ServiceId> = partition_service_ids.difference(&network_service_ids).collect();
+        if !unknown_service_ids.is_empty() {
+            return Err(anyhow!(
+                "The partition places services {:?}, which aren't in the network",
+                unknown_service_ids,
+            ));
         }
 
-        let mut partition_services: HashMap<String, HashSet<String>> = HashMap::new();
-        partition_services.insert(FAUCET_PARTITION_ID_STR.to_owned(), faucet_partition_services);
-        partition_services.insert(BOOTSTRAPPERS_PARTITION1_ID_STR.to_owned(), bootstrappers_partition1_services);
-        partition_services.insert(BOOTSTRAPPERS_PARTITION2_ID_STR.to_owned(), bootstrappers_partition2_services);
+        let (partition_services, partition_connections, default_connection_info) = partition.to_repartition_args();
         debug!("Partition services: {:?}", partition_services);
+        debug!("Partition connections: {:?}", partition_connections);
+        self.network_ctx.repartition_network(
+            partition_services,
+            partition_connections,
+            default_connection_info,
+        ).context("An error occurred repartitioning the network")?;
+        return Ok(());
+    }
 
-        let mut bootstrappers_partition1_conns: HashMap<String, PartitionConnectionInfo> = HashMap::new();
-        bootstrappers_partition1_conns.insert(BOOTSTRAPPERS_PARTITION2_ID_STR.to_owned(), PartitionConnectionInfo{
-            is_blocked: is_connection_blocked,
-        });
+    pub fn get_faucet_service_id(&self) -> ServiceId {
+        return FAUCET_SERVICE_ID_STR.to_owned();
+    }
 
-        let mut partition_connections: HashMap<String, HashMap<String, PartitionConnectionInfo>> = HashMap::new();
-        partition_connections.insert(BOOTSTRAPPERS_PARTITION1_ID_STR.to_owned(), bootstrappers_partition1_conns);
-        debug!("Partition connections: {:?}", partition_connections);
+    // Returns: the IDs of the running bootstrappers, validators, and RPC nodes (i.e. everything but the faucet)
+    pub fn get_node_service_ids(&self) -> Vec<ServiceId> {
+        return self.get_node_service_ids_with_entrypoints().into_iter()
+            .map(|(service_id, _)| service_id)
+            .collect();
+    }
+
+    pub fn get_all_service_ids(&self) -> Vec<ServiceId> {
+        let mut result = vec![self.get_faucet_service_id()];
+        result.extend(self.get_node_service_ids());
+        return result;
+    }
+
+    pub fn get_bootstrapper_service_id(i: usize) -> ServiceId {
+        return format!("{}{}", BOOTSTRAPPER_SERVICE_ID_PREFIX, i);
+    }
+
+    pub fn get_validator_service_id(i: usize) -> ServiceId {
+        return format!("{}{}", VALIDATOR_SERVICE_ID_PREFIX, i);
+    }
+
+    pub fn get_rpc_node_service_id(i: usize) -> ServiceId {
+        return format!("{}{}", RPC_NODE_SERVICE_ID_PREFIX, i);
+    }
+
+    // The first bootstrapper only needs an entrypoint when it's being restarted, but the others always do
+    fn get_bootstrapper_initializer(&self, i: usize, docker_image: String, entrypoint: Option<Rc<ValidatorService>>) -> Result<ValidatorContainerInitializer> {
+        let faucet = self.faucet.clone()
+            .context("Cannot start a bootstrapper before the faucet has been started")?;
+        let bootstrapper_keypairs = self.genesis_config.get_bootstrappers().get(i)
+            .context(format!("Needed genesis bootstrapper keypair #{}, but genesis config doesn't have that keypair", i))?;
+        let identity_keypair = bootstrapper_keypairs.identity.get_keypair().clone();
+        let vote_account_keypair = bootstrapper_keypairs.vote_account.get_keypair().clone();
+        let ledger_options = BootstrapperLedgerOptions{
+            expected_bank_hash: self.genesis_config.get_bank_hash().to_owned(),
+            expected_genesis_hash: self.genesis_config.get_genesis_hash().to_owned(),
+            expected_shred_version: self.genesis_config.get_shred_version(),
+            ledger_dir_artifact_key: self.ledger_artifact.get_key().to_owned(),
+        };
+        if i == 0 {
+            return Ok(ValidatorContainerInitializer::for_first_bootstrapper(
+                docker_image,
+                ledger_options,
+                identity_keypair,
+                vote_account_keypair,
+                faucet,
+                entrypoint,
+            ));
+        }
+        let entrypoint = entrypoint
+            .context(format!("Bootstrapper #{} needs another bootstrapper to use as its entrypoint, but none is running", i))?;
+        return Ok(ValidatorContainerInitializer::for_extra_bootstrapper(
+            docker_image,
+            ledger_options,
+            identity_keypair,
+            vote_account_keypair,
+            faucet,
+            entrypoint,
+        ));
+    }
+
+    fn get_validator_initializer(
+        &self,
+        i: usize,
+        docker_image: String,
+        faucet: Rc<FaucetService>,
+        entrypoint: Rc<ValidatorService>,
+    ) -> Result<ValidatorContainerInitializer> {
+        let (identity_keypair, vote_account_keypair) = self.validator_keypairs.get(i)
+            .context(format!("Needed the keypairs for validator #{}, but they weren't found", i))?;
+        return Ok(ValidatorContainerInitializer::for_joiner(
+            docker_image,
+            self.genesis_config.get_genesis_hash().to_owned(),
+            self.genesis_config.get_shred_version(),
+            identity_keypair.clone(),
+            vote_account_keypair.clone(),
+            faucet,
+            entrypoint,
+        ));
+    }
+
+    fn get_running_bootstrapper_idxs(&self) -> Vec<usize> {
+        return (0..self.bootstrappers.len())
+            .filter(|i| self.is_node_running(&SolanaNetwork::get_bootstrapper_service_id(*i)))
+            .collect();
+    }
+
+    // Returns: the lowest-numbered bootstrapper that's running, other than the excluded one
+    fn get_running_bootstrapper(&self, excluded_idx: Option<usize>) -> Option<Rc<ValidatorService>> {
+        return self.get_running_bootstrapper_idx(excluded_idx)
+            .map(|i| self.bootstrappers[i].clone());
+    }
+
+    // Returns: the index of the lowest-numbered bootstrapper that's running, other than the excluded one
+    fn get_running_bootstrapper_idx(&self, excluded_idx: Option<usize>) -> Option<usize> {
+        return self.get_running_bootstrapper_idxs().into_iter()
+            .find(|i| Some(*i) != excluded_idx);
+    }
+
+    // A clean stop waits for the node to exit on its own before its container gets removed; otherwise, the node gets
+    // SIGKILLed along with its container
+    fn stop_node(&mut self, service_id: &ServiceId, node: &ValidatorService, is_clean: bool) -> Result<()> {
+        if self.stopped_service_ids.contains(service_id) {
+            return Err(anyhow!(
+                "Node '{}' is already stopped",
+                service_id,
+            ));
+        }
+        if is_clean {
+            info!("Shutting down node '{}'...", service_id);
+            node.shut_down(NODE_SHUTDOWN_TIMEOUT)
+                .context(format!("An error occurred shutting down node '{}'", service_id))?;
+        }
+        self.network_ctx.remove_service(service_id, NODE_CONTAINER_STOP_TIMEOUT_SECONDS)
+            .context(format!("An error occurred removing node '{}'", service_id))?;
+        self.stopped_service_ids.insert(service_id.clone());
+        info!("Node '{}' {}", service_id, if is_clean { "stopped" } else { "killed" });
+        return Ok(());
+    }
+
+    // Re-adds a stopped node under the same service ID, waits for it to start back up, and then waits for it to catch up
+    // with the reference node (if any)
+    fn restart_node(
+        &mut self,
+        service_id: &ServiceId,
+        initializer: &ValidatorContainerInitializer,
+        reference: Option<Rc<ValidatorService>>,
+    ) -> Result<Rc<ValidatorService>> {
+        info!("Restarting node '{}'...", service_id);
+        let (node, checker) = self.network_ctx.add_service(service_id, initializer)
+            .context(format!("An error occurred re-adding node '{}'", service_id))?;
+        self.stopped_service_ids.remove(service_id);
+        SolanaNetwork::wait_for_node_startup(&checker, &node)
+            .context(format!("An error occurred waiting for node '{}' to start back up", service_id))?;
+        if let Some(reference) = reference {
+            info!("Node '{}' started back up; waiting for it to catch up with the cluster...", service_id);
+            SolanaNetwork::wait_for_catchup(&node, &reference)
+                .context(format!("An error occurred waiting for node '{}' to catch up with the cluster", service_id))?;
+        }
+        info!("Node '{}' restarted", service_id);
+        return Ok(node);
+    }
 
-        let default_connection_info = PartitionConnectionInfo{
-            is_blocked: false,
+    // Funds the identity and creates a vote account authorized to it, all paid for by the genesis faucet keypair (which
+    // holds all the genesis lamports that weren't staked)
+    fn create_vote_account(&self, entrypoint: &ValidatorService, identity_keypair: &Keypair, vote_account_keypair: &Keypair) -> Result<()> {
+        let payer = self.genesis_config.get_faucet().get_keypair();
+        let vote_account_lamports = entrypoint.get_minimum_balance_for_rent_exemption(VOTE_STATE_SIZE as usize)
+            .context("An error occurred getting the rent-exempt balance for a vote account")?;
+        let identity_pubkey = identity_keypair.pubkey();
+        let vote_init = VoteInit{
+            node_pubkey: identity_pubkey,
+            authorized_voter: identity_pubkey,
+            authorized_withdrawer: identity_pubkey,
+            commission: JOINER_VOTE_ACCOUNT_COMMISSION,
         };
+        let mut instructions = vec![
+            system_instruction::transfer(&payer.pubkey(), &identity_pubkey, JOINER_IDENTITY_LAMPORTS),
+        ];
+        instructions.extend(vote_instruction::create_account(&payer.pubkey(), &vote_account_keypair.pubkey(), &vote_init, vote_account_lamports));
 
-        self.network_ctx.repartition_network(
-            partition_services, 
-            partition_connections, 
-            default_connection_info,
-        ).context(format!("An error occurred partitioning the network in half, with blocked connection = {}", is_connection_blocked))?;
+        let recent_blockhash = entrypoint.get_recent_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(&instructions, Some(payer), &[identity_keypair, vote_account_keypair], recent_blockhash)
+            .context("An error occurred signing the vote account creation transaction")?;
+        entrypoint.send_and_confirm_transaction(&transaction, CommitmentConfig::confirmed(), JOINER_VOTE_ACCOUNT_CONFIRMATION_TIMEOUT)?;
+        return Ok(());
+    }
 
+    // Waits for the node to pass all of its readiness stages, reporting the stage it got stuck at (and why) if it doesn't
+    fn wait_for_node_startup(checker: &AvailabilityChecker, node: &ValidatorService) -> Result<()> {
+        if let Err(err) = checker.wait_for_startup(&TIME_BETWEEN_BOOTSTRAPPER_AVAILABILITY_POLLS, NUM_RETRIES_FOR_BOOTSTRAPPER_AVAILBILITY) {
+            let unready_reason = node.get_unready_reason()
+                .unwrap_or_else(|| String::from("The node became ready after the last availability poll"));
+            return Err(err.context(unready_reason));
+        }
         return Ok(());
     }
 
-    fn get_bootstrapper_service_id(i: usize) -> ServiceId {
-        return format!("{}{}", BOOTSTRAPPER_SERVICE_ID_PREFIX, i);
+    // Waits until the validator has processed at least as far as the reference node had (the same check as 'solana catchup')
+    fn wait_for_catchup(validator: &ValidatorService, reference: &ValidatorService) -> Result<()> {
+        let start_time = Instant::now();
+        loop {
+            let reference_slot = reference.get_slot(Some(CommitmentConfig::processed()))
+                .context("An error occurred getting the reference node's slot")?;
+            // The validator's RPC server may not be up yet while it's still loading its snapshot
+            match validator.get_slot(Some(CommitmentConfig::processed())) {
+                Ok(slot) if slot >= reference_slot => return Ok(()),
+                Ok(slot) => debug!("Validator is at slot {}, {} slots behind the reference node", slot, reference_slot - slot),
+                Err(err) => debug!("Couldn't get the validator's slot: {:#}", err),
+            }
+            if start_time.elapsed() >= MAX_JOINER_CATCHUP_TIME {
+                return Err(anyhow!(
+                    "Validator still hadn't caught up to the reference node's slot {} after {:?}",
+                    reference_slot,
+                    MAX_JOINER_CATCHUP_TIME,
+                ));
+            }
+            sleep(TIME_BETWEEN_JOINER_CATCHUP_POLLS);
+        }
+    }
+
+    // Returns: the stake of every running bootstrapper & validator keyed by service ID, and the stake of the whole cluster
+    fn get_node_and_total_stakes(&self, source: StakeSource) -> Result<(Vec<(ServiceId, u64)>, u64)> {
+        // Each node's vote account, alongside the stake it was given in genesis
+        let mut vote_pubkeys: Vec<(ServiceId, Pubkey, u64)> = Vec::new();
+        for i in 0..self.bootstrappers.len() {
+            let service_id = SolanaNetwork::get_bootstrapper_service_id(i);
+            if !self.is_node_running(&service_id) {
+                continue;
+            }
+            let bootstrapper_keypairs = self.genesis_config.get_bootstrappers().get(i)
+                .context(format!("Bootstrapper #{} is running, but the genesis config doesn't have its keypairs", i))?;
+            let genesis_stake = self.genesis_config.get_bootstrapper_stake(i).unwrap_or(0);
+            vote_pubkeys.push((service_id, *bootstrapper_keypairs.vote_account.get_pubkey(), genesis_stake));
+        }
+        for (i, (_, vote_account_keypair)) in self.validator_keypairs.iter().enumerate() {
+            let service_id = SolanaNetwork::get_validator_service_id(i);
+            if self.is_node_running(&service_id) {
+                vote_pubkeys.push((service_id, vote_account_keypair.pubkey(), 0));
+            }
+        }
+
+        let mut node_stakes: Vec<(ServiceId, u64)> = Vec::new();
+        let total_stake = match source {
+            StakeSource::Genesis => {
+                for (service_id, _, genesis_stake) in vote_pubkeys {
+                    node_stakes.push((service_id, genesis_stake));
+                }
+                self.genesis_config.get_total_stake()
+            },
+            StakeSource::Live(commitment) => {
+                let bootstrapper = self.get_running_bootstrapper(None)
+                    .context("Cannot get the live stakes because no bootstrappers are running")?;
+                let stakes_by_vote_pubkey = bootstrapper.get_vote_account_stakes(Some(commitment))
+                    .context("An error occurred getting the live vote account stakes from a bootstrapper")?;
+                for (service_id, vote_pubkey, _) in vote_pubkeys {
+                    node_stakes.push((service_id, stakes_by_vote_pubkey.get(&vote_pubkey).copied().unwrap_or(0)));
+                }
+                stakes_by_vote_pubkey.values().sum()
+            },
+        };
+        debug!("Node stakes from {:?}: {:?}, out of {} total", source, node_stakes, total_stake);
+        return Ok((node_stakes, total_stake));
+    }
+
+    fn get_stake_partition_inputs(&self, source: StakeSource) -> Result<StakePartitionInputs> {
+        let (node_stakes, total_stake) = self.get_node_and_total_stakes(source)?;
+        let staked: Vec<(ServiceId, u64)> = node_stakes.into_iter().filter(|(_, stake)| *stake > 0).collect();
+        let staked_service_ids: HashSet<&ServiceId> = staked.iter().map(|(service_id, _)| service_id).collect();
+        let mut followers: Vec<(ServiceId, ServiceId)> = Vec::new();
+        for (service_id, entrypoint_idx) in self.get_node_service_ids_with_entrypoints() {
+            if !staked_service_ids.contains(&service_id) {
+                followers.push((service_id, SolanaNetwork::get_bootstrapper_service_id(entrypoint_idx)));
+            }
+        }
+        return Ok((staked, followers, total_stake));
+    }
+
+    // Returns: the ID of every running node, alongside the index of the bootstrapper that the node entered the cluster
+    // through (which, for a bootstrapper, is itself)
+    fn get_node_service_ids_with_entrypoints(&self) -> Vec<(ServiceId, usize)> {
+        let mut result = Vec::new();
+        for i in 0..self.bootstrappers.len() {
+            result.push((SolanaNetwork::get_bootstrapper_service_id(i), i));
+        }
+        for (i, entrypoint_idx) in self.validator_entrypoint_idxs.iter().enumerate() {
+            result.push((SolanaNetwork::get_validator_service_id(i), *entrypoint_idx));
+        }
+        for i in 0..self.rpc_nodes.len() {
+            result.push((SolanaNetwork::get_rpc_node_service_id(i), i % self.bootstrappers.len()));
+        }
+        result.retain(|(service_id, _)| self.is_node_running(service_id));
+        return result;
     }
 }
 
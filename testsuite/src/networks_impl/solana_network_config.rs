@@ -0,0 +1,224 @@
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+
+use super::genesis_config::GenesisConfig;
+
+// IPs in the testnet's subnet that Kurtosis uses for itself (network address, gateway, broadcast address, API container,
+// and testsuite container), and so can't be given to nodes
+const NUM_RESERVED_NETWORK_IPS: usize = 5;
+
+// The shape of the cluster that a test wants: how many of each kind of node, and which images they run
+#[derive(Clone, Debug)]
+pub struct SolanaNetworkConfig {
+    num_bootstrappers: usize,
+    num_rpc_nodes: usize,
+    faucet_image: String,
+    validator_image: String,
+    // Images for individual bootstrappers, keyed by bootstrapper index, for running a cluster with mixed versions; the
+    // bootstrappers that aren't in here run the validator image
+    bootstrapper_images: HashMap<usize, String>,
+    // The image that validators get upgraded to in the rolling-upgrade test, which only runs if this is set
+    upgrade_image: Option<String>,
+}
+
+impl SolanaNetworkConfig {
+    pub fn new(
+        num_bootstrappers: usize,
+        num_rpc_nodes: usize,
+        faucet_image: String,
+        validator_image: String,
+        bootstrapper_images: HashMap<usize, String>,
+        upgrade_image: Option<String>,
+    ) -> SolanaNetworkConfig {
+        return SolanaNetworkConfig{
+            num_bootstrappers,
+            num_rpc_nodes,
+            faucet_image,
+            validator_image,
+            bootstrapper_images,
+            upgrade_image,
+        };
+    }
+
+    // Checks that the cluster can actually be built from the given genesis material & fit in a network of the given width
+    pub fn validate(&self, genesis_config: &GenesisConfig, network_width_bits: u32) -> Result<()> {
+        let num_genesis_bootstrappers = genesis_config.get_bootstrappers().len();
+        if self.num_bootstrappers == 0 {
+            return Err(anyhow!(
+                "Number of bootstrappers must be > 0",
+            ));
+        }
+        if self.num_bootstrappers > num_genesis_bootstrappers {
+            return Err(anyhow!(
+                "Requested {} bootstrappers, but the genesis config only has keypairs for {}",
+                self.num_bootstrappers,
+                num_genesis_bootstrappers,
+            ));
+        }
+
+        // Every genesis bootstrapper is staked, so the ones that don't get started still count against the cluster's
+        // supermajority; if too little stake gets started, the cluster will never confirm anything
+        let started_stake: u64 = genesis_config.get_bootstrappers()[..self.num_bootstrappers].iter()
+            .map(|bootstrapper_keypairs| bootstrapper_keypairs.stake_lamports)
+            .sum();
+        let total_stake = genesis_config.get_total_stake();
+        if started_stake as u128 * 3 <= total_stake as u128 * 2 {
+            return Err(anyhow!(
+                "Requested {} bootstrappers, but they only hold {} of the genesis config's {} stake so the cluster can't reach a 2/3 supermajority",
+                self.num_bootstrappers,
+                started_stake,
+                total_stake,
+            ));
+        }
+
+        // +1 for the faucet
+        let num_services = 1 + self.num_bootstrappers + self.num_rpc_nodes;
+        let max_num_services = (1usize << network_width_bits).saturating_sub(NUM_RESERVED_NETWORK_IPS);
+        if num_services > max_num_services {
+            return Err(anyhow!(
+                "Requested {} nodes in total (faucet, {} bootstrappers, and {} RPC nodes), but a network of width {} bits only has room for {}",
+                num_services,
+                self.num_bootstrappers,
+                self.num_rpc_nodes,
+                network_width_bits,
+                max_num_services,
+            ));
+        }
+
+        if self.faucet_image.is_empty() {
+            return Err(anyhow!(
+                "Faucet image must not be empty",
+            ));
+        }
+        if self.validator_image.is_empty() {
+            return Err(anyhow!(
+                "Validator image must not be empty",
+            ));
+        }
+        for (i, bootstrapper_image) in &self.bootstrapper_images {
+            if *i >= self.num_bootstrappers {
+                return Err(anyhow!(
+                    "An image was given for bootstrapper #{}, but only {} bootstrappers will be started",
+                    i,
+                    self.num_bootstrappers,
+                ));
+            }
+            if bootstrapper_image.is_empty() {
+                return Err(anyhow!(
+                    "Image for bootstrapper #{} must not be empty",
+                    i,
+                ));
+            }
+        }
+        if let Some(upgrade_image) = &self.upgrade_image {
+            if upgrade_image.is_empty() {
+                return Err(anyhow!(
+                    "Upgrade image must not be empty",
+                ));
+            }
+            if *upgrade_image == self.validator_image {
+                return Err(anyhow!(
+                    "Upgrade image must differ from the validator image '{}', or else there's nothing to upgrade to",
+                    self.validator_image,
+                ));
+            }
+        }
+        return Ok(());
+    }
+
+    pub fn get_num_bootstrappers(&self) -> usize {
+        return self.num_bootstrappers;
+    }
+
+    pub fn get_num_rpc_nodes(&self) -> usize {
+        return self.num_rpc_nodes;
+    }
+
+    pub fn get_faucet_image(&self) -> &str {
+        return &self.faucet_image;
+    }
+
+    pub fn get_validator_image(&self) -> &str {
+        return &self.validator_image;
+    }
+
+    pub fn get_bootstrapper_image(&self, i: usize) -> &str {
+        return self.bootstrapper_images.get(&i).unwrap_or(&self.validator_image);
+    }
+
+    // Returns: true if the bootstrappers don't all run the same image
+    pub fn has_mixed_bootstrapper_images(&self) -> bool {
+        let validator_image = self.get_validator_image();
+        return (0..self.num_bootstrappers).any(|i| self.get_bootstrapper_image(i) != validator_image);
+    }
+
+    pub fn get_upgrade_image(&self) -> Option<&str> {
+        return self.upgrade_image.as_deref();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::networks_impl::genesis_config::GenesisConfig;
+
+    use super::SolanaNetworkConfig;
+
+    const CHECKED_IN_GENESIS_CONFIG_FILEPATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/genesis-config.json");
+
+    fn validate(num_bootstrappers: usize, num_rpc_nodes: usize, network_width_bits: u32) -> anyhow::Result<()> {
+        // The checked-in genesis config has 10 bootstrappers
+        let genesis_config = GenesisConfig::load(CHECKED_IN_GENESIS_CONFIG_FILEPATH).unwrap();
+        let config = SolanaNetworkConfig::new(num_bootstrappers, num_rpc_nodes, "faucet-image".to_owned(), "validator-image".to_owned(), HashMap::new(), None);
+        return config.validate(&genesis_config, network_width_bits);
+    }
+
+    #[test]
+    fn test_validate_accepts_clusters_that_fit() {
+        validate(10, 0, 8).unwrap();
+        validate(7, 3, 8).unwrap();
+        validate(10, 240, 8).unwrap();
+    }
+
+    #[test]
+    fn test_validate_rejects_clusters_that_dont_fit() {
+        assert!(validate(0, 0, 8).is_err());
+        assert!(validate(11, 0, 8).is_err());
+        // 6 of 10 equally-staked bootstrappers can't reach a 2/3 supermajority
+        assert!(validate(6, 0, 8).is_err());
+        assert!(validate(10, 241, 8).is_err());
+        assert!(validate(10, 0, 3).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_images() {
+        let genesis_config = GenesisConfig::load(CHECKED_IN_GENESIS_CONFIG_FILEPATH).unwrap();
+        let config = SolanaNetworkConfig::new(10, 0, "".to_owned(), "validator-image".to_owned(), HashMap::new(), None);
+        assert!(config.validate(&genesis_config, 8).is_err());
+        let config = SolanaNetworkConfig::new(10, 0, "faucet-image".to_owned(), "validator-image".to_owned(), HashMap::new(), Some("".to_owned()));
+        assert!(config.validate(&genesis_config, 8).is_err());
+        let config = SolanaNetworkConfig::new(10, 0, "faucet-image".to_owned(), "validator-image".to_owned(), HashMap::new(), Some("validator-image".to_owned()));
+        assert!(config.validate(&genesis_config, 8).is_err());
+    }
+
+    #[test]
+    fn test_bootstrapper_images() {
+        let genesis_config = GenesisConfig::load(CHECKED_IN_GENESIS_CONFIG_FILEPATH).unwrap();
+        let mut bootstrapper_images = HashMap::new();
+        bootstrapper_images.insert(1, "old-image".to_owned());
+        let config = SolanaNetworkConfig::new(7, 0, "faucet-image".to_owned(), "validator-image".to_owned(), bootstrapper_images.clone(), None);
+        config.validate(&genesis_config, 8).unwrap();
+        assert_eq!("validator-image", config.get_bootstrapper_image(0));
+        assert_eq!("old-image", config.get_bootstrapper_image(1));
+        assert!(config.has_mixed_bootstrapper_images());
+
+        // Bootstrapper #7 won't be started, so it can't be given an image
+        bootstrapper_images.insert(7, "new-image".to_owned());
+        let config = SolanaNetworkConfig::new(7, 0, "faucet-image".to_owned(), "validator-image".to_owned(), bootstrapper_images, None);
+        assert!(config.validate(&genesis_config, 8).is_err());
+
+        let config = SolanaNetworkConfig::new(7, 0, "faucet-image".to_owned(), "validator-image".to_owned(), HashMap::new(), None);
+        assert!(!config.has_mixed_bootstrapper_images());
+    }
+}
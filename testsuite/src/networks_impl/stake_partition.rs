@@ -0,0 +1,210 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::HashMap;
+
+use kurtosis_rust_lib::services::service::ServiceId;
+
+use super::network_partition::NetworkPartition;
+
+pub const STAKE_SIDE_A_PARTITION_ID_STR: &str = "stake-side-a";
+pub const STAKE_SIDE_B_PARTITION_ID_STR: &str = "stake-side-b";
+
+// A split of the cluster into two sides that can't reach each other, planned by stake rather than by node count since
+// it's stake that decides whether a side can keep confirming & rooting blocks
+#[derive(Clone, Debug)]
+pub struct StakePartition {
+    partition: NetworkPartition,
+    side_a_stake: u64,
+    side_b_stake: u64,
+    // The stake of the whole cluster, which includes any staked nodes that aren't running
+    total_stake: u64,
+}
+
+impl StakePartition {
+    // Gives side A as much stake as it can hold without going over the max, and side B the rest
+    // Unstaked nodes (e.g. RPC nodes) are given as followers, paired with the staked node whose side they should go on
+    pub fn plan(
+        staked: &[(ServiceId, u64)],
+        followers: &[(ServiceId, ServiceId)],
+        shared: &[ServiceId],
+        total_stake: u64,
+        max_side_a_stake: u64,
+    ) -> Result<StakePartition> {
+        let running_stake: u64 = staked.iter().map(|(_, stake)| stake).sum();
+        if running_stake > total_stake {
+            return Err(anyhow!(
+                "The nodes have {} stake between them, which is more than the cluster's total stake of {}",
+                running_stake,
+                total_stake,
+            ));
+        }
+        let (mut side_a, mut side_b, side_a_stake) = NetworkPartition::pick_by_weight(staked, max_side_a_stake);
+        let side_b_stake = running_stake - side_a_stake;
+
+        let side_by_service: HashMap<ServiceId, bool> = side_a.iter().map(|service_id| (service_id.clone(), true))
+            .chain(side_b.iter().map(|service_id| (service_id.clone(), false)))
+            .collect();
+        for (follower, leader) in followers {
+            let is_side_a = side_by_service.get(leader)
+                .context(format!("Node '{}' is supposed to follow node '{}', but that node isn't staked", follower, leader))?;
+            if *is_side_a {
+                side_a.push(follower.clone());
+            } else {
+                side_b.push(follower.clone());
+            }
+        }
+        debug!(
+            "Planned stake partition with side A {:?} holding {} stake and side B {:?} holding {} stake, out of {} total",
+            side_a,
+            side_a_stake,
+            side_b,
+            side_b_stake,
+            total_stake,
+        );
+
+        let partition = NetworkPartition::builder()
+            .with_group(STAKE_SIDE_A_PARTITION_ID_STR, side_a)
+            .with_group(STAKE_SIDE_B_PARTITION_ID_STR, side_b)
+            .with_blocked_connection(STAKE_SIDE_A_PARTITION_ID_STR, STAKE_SIDE_B_PARTITION_ID_STR)
+            .with_shared_group(shared)
+            .build()
+            .context(format!("An error occurred building a partition with at most {} of the {} stake on side A", max_side_a_stake, total_stake))?;
+        return Ok(StakePartition{
+            partition,
+            side_a_stake,
+            side_b_stake,
+            total_stake,
+        });
+    }
+
+    // Gives side A at most the given fraction of the total stake
+    pub fn with_side_a_fraction(
+        staked: &[(ServiceId, u64)],
+        followers: &[(ServiceId, ServiceId)],
+        shared: &[ServiceId],
+        total_stake: u64,
+        side_a_fraction: f64,
+    ) -> Result<StakePartition> {
+        if !(side_a_fraction > 0.0 && side_a_fraction < 1.0) {
+            return Err(anyhow!(
+                "Side A's stake fraction must be strictly between 0 and 1, but was {}",
+                side_a_fraction,
+            ));
+        }
+        let max_side_a_stake = (total_stake as f64 * side_a_fraction) as u64;
+        return StakePartition::plan(staked, followers, shared, total_stake, max_side_a_stake);
+    }
+
+    // Gives side A as much stake as it can hold while still falling short of a 2/3 supermajority, which makes it the
+    // largest partition that can't keep rooting on its own
+    pub fn below_supermajority(
+        staked: &[(ServiceId, u64)],
+        followers: &[(ServiceId, ServiceId)],
+        shared: &[ServiceId],
+        total_stake: u64,
+    ) -> Result<StakePartition> {
+        // Rooting needs strictly more than 2/3 of the stake, so side A can hold exactly 2/3 and still not root
+        let max_side_a_stake = (total_stake as u128 * 2 / 3) as u64;
+        return StakePartition::plan(staked, followers, shared, total_stake, max_side_a_stake);
+    }
+
+    pub fn get_partition(&self) -> &NetworkPartition {
+        return &self.partition;
+    }
+
+    pub fn get_side_a_stake(&self) -> u64 {
+        return self.side_a_stake;
+    }
+
+    pub fn get_side_b_stake(&self) -> u64 {
+        return self.side_b_stake;
+    }
+
+    pub fn get_total_stake(&self) -> u64 {
+        return self.total_stake;
+    }
+
+    // Returns: the services on the given side (STAKE_SIDE_A_PARTITION_ID_STR or STAKE_SIDE_B_PARTITION_ID_STR)
+    pub fn get_side_service_ids(&self, side_partition_id: &str) -> Result<Vec<ServiceId>> {
+        let services = self.partition.get_group(side_partition_id)
+            .context(format!("Stake partition has no side '{}'", side_partition_id))?;
+        let mut result: Vec<ServiceId> = services.iter().cloned().collect();
+        result.sort();
+        return Ok(result);
+    }
+
+    // Returns: the side holding more than 2/3 of the total stake, which is the only side that can keep rooting; None if
+    // neither side does, in which case the whole cluster should stall
+    pub fn get_supermajority_side(&self) -> Option<&'static str> {
+        if StakePartition::is_supermajority(self.side_a_stake, self.total_stake) {
+            return Some(STAKE_SIDE_A_PARTITION_ID_STR);
+        }
+        if StakePartition::is_supermajority(self.side_b_stake, self.total_stake) {
+            return Some(STAKE_SIDE_B_PARTITION_ID_STR);
+        }
+        return None;
+    }
+
+    fn is_supermajority(stake: u64, total_stake: u64) -> bool {
+        return stake as u128 * 3 > total_stake as u128 * 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kurtosis_rust_lib::services::service::ServiceId;
+
+    use super::{STAKE_SIDE_A_PARTITION_ID_STR, STAKE_SIDE_B_PARTITION_ID_STR, StakePartition};
+
+    fn equal_stakes(num_nodes: usize) -> Vec<(ServiceId, u64)> {
+        return (0..num_nodes).map(|i| (format!("bootstrapper-{}", i), 100)).collect();
+    }
+
+    #[test]
+    fn test_side_a_fraction_picks_minority_and_majority_keeps_supermajority() {
+        let staked = equal_stakes(10);
+        let followers = vec![("rpc-node-0".to_owned(), "bootstrapper-9".to_owned())];
+        let plan = StakePartition::with_side_a_fraction(&staked, &followers, &["faucet".to_owned()], 1000, 0.3).unwrap();
+        assert_eq!(300, plan.get_side_a_stake());
+        assert_eq!(700, plan.get_side_b_stake());
+        assert_eq!(Some(STAKE_SIDE_B_PARTITION_ID_STR), plan.get_supermajority_side());
+        assert_eq!(3, plan.get_side_service_ids(STAKE_SIDE_A_PARTITION_ID_STR).unwrap().len());
+        // Ties are broken by ID, so the last bootstrapper ends up on side B along with the RPC node following it
+        assert!(plan.get_side_service_ids(STAKE_SIDE_B_PARTITION_ID_STR).unwrap().contains(&"rpc-node-0".to_owned()));
+        assert_eq!(Some(true), plan.get_partition().can_reach(&"rpc-node-0".to_owned(), &"faucet".to_owned()));
+    }
+
+    #[test]
+    fn test_below_supermajority_halts_both_sides() {
+        let plan = StakePartition::below_supermajority(&equal_stakes(9), &[], &[], 900).unwrap();
+        // Exactly 2/3 isn't a supermajority, so side A can take 6 of the 9 nodes
+        assert_eq!(600, plan.get_side_a_stake());
+        assert_eq!(None, plan.get_supermajority_side());
+
+        let uneven = vec![
+            ("bootstrapper-0".to_owned(), 40),
+            ("bootstrapper-1".to_owned(), 30),
+            ("bootstrapper-2".to_owned(), 20),
+            ("bootstrapper-3".to_owned(), 10),
+        ];
+        let plan = StakePartition::below_supermajority(&uneven, &[], &[], 100).unwrap();
+        // The 30 doesn't fit alongside the 40, so the 20 gets picked instead
+        assert_eq!(60, plan.get_side_a_stake());
+        assert_eq!(None, plan.get_supermajority_side());
+    }
+
+    #[test]
+    fn test_stake_of_nodes_that_arent_running_counts_towards_total() {
+        // 7 of 10 equally-staked nodes are running, so a 30% side A leaves side B with only 40% of the total
+        let plan = StakePartition::with_side_a_fraction(&equal_stakes(7), &[], &[], 1000, 0.3).unwrap();
+        assert_eq!(400, plan.get_side_b_stake());
+        assert_eq!(None, plan.get_supermajority_side());
+    }
+
+    #[test]
+    fn test_invalid_plans_are_rejected() {
+        assert!(StakePartition::with_side_a_fraction(&equal_stakes(10), &[], &[], 500, 0.3).is_err());
+        assert!(StakePartition::with_side_a_fraction(&equal_stakes(10), &[], &[], 1000, 0.0).is_err());
+        let followers = vec![("rpc-node-0".to_owned(), "bootstrapper-10".to_owned())];
+        assert!(StakePartition::with_side_a_fraction(&equal_stakes(10), &followers, &[], 1000, 0.3).is_err());
+    }
+}
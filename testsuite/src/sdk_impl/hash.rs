@@ -0,0 +1,57 @@
+use std::{convert::TryInto, fmt};
+
+use anyhow::{anyhow, Context, Result};
+use sha2::{Digest, Sha256};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/program/src/hash.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+pub const HASH_BYTES: usize = 32;
+
+// A SHA-256 hash, which in practice is almost always a recent blockhash
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct Hash([u8; HASH_BYTES]);
+
+impl Hash {
+    pub fn new(bytes: [u8; HASH_BYTES]) -> Hash {
+        return Hash(bytes);
+    }
+
+    pub fn from_base58(base58_str: &str) -> Result<Hash> {
+        let bytes = bs58::decode(base58_str).into_vec()
+            .context(format!("An error occurred decoding hash '{}' from base58", base58_str))?;
+        let bytes: [u8; HASH_BYTES] = bytes.as_slice().try_into()
+            .map_err(|_| anyhow!(
+                "Expected hash '{}' to decode to {} bytes but got {}",
+                base58_str,
+                HASH_BYTES,
+                bytes.len(),
+            ))?;
+        return Ok(Hash(bytes));
+    }
+
+    pub fn as_bytes(&self) -> &[u8; HASH_BYTES] {
+        return &self.0;
+    }
+}
+
+// Hashes the data with SHA-256, the way Solana hashes everything from blockhashes to the genesis config
+pub fn hash(data: &[u8]) -> Hash {
+    let digest = Sha256::digest(data);
+    let mut bytes = [0u8; HASH_BYTES];
+    bytes.copy_from_slice(&digest);
+    return Hash(bytes);
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", bs58::encode(self.0).into_string());
+    }
+}
@@ -0,0 +1,51 @@
+use super::pubkey::Pubkey;
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/program/src/instruction.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    pub fn new(pubkey: Pubkey, is_signer: bool) -> AccountMeta {
+        return AccountMeta{
+            pubkey,
+            is_signer,
+            is_writable: true,
+        };
+    }
+
+    pub fn new_readonly(pubkey: Pubkey, is_signer: bool) -> AccountMeta {
+        return AccountMeta{
+            pubkey,
+            is_signer,
+            is_writable: false,
+        };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+// An instruction whose program and accounts have been replaced by indexes into the message's account keys
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Context, Result};
+use ed25519_dalek::Signer;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use super::{pubkey::Pubkey, signature::Signature};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/src/signature.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// Length of the secret-key-followed-by-public-key byte array that solana-keygen writes out
+const KEYPAIR_BYTES: usize = ed25519_dalek::KEYPAIR_LENGTH;
+
+pub struct Keypair(ed25519_dalek::Keypair);
+
+impl Keypair {
+    pub fn new() -> Keypair {
+        return Keypair(ed25519_dalek::Keypair::generate(&mut OsRng));
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Keypair> {
+        if bytes.len() != KEYPAIR_BYTES {
+            return Err(anyhow!(
+                "Expected {} keypair bytes but got {}",
+                KEYPAIR_BYTES,
+                bytes.len(),
+            ));
+        }
+        let keypair = ed25519_dalek::Keypair::from_bytes(bytes)
+            .map_err(|err| anyhow!("The keypair bytes aren't a valid ed25519 keypair: {}", err))?;
+        // The public half is stored alongside the secret half rather than derived from it, so a corrupted or
+        // hand-edited keypair could otherwise sign as one key while claiming to be another
+        let derived_public = ed25519_dalek::PublicKey::from(&keypair.secret);
+        if derived_public != keypair.public {
+            return Err(anyhow!(
+                "The keypair's public key '{}' doesn't match the public key '{}' derived from its secret key",
+                bs58::encode(keypair.public.as_bytes()).into_string(),
+                bs58::encode(derived_public.as_bytes()).into_string(),
+            ));
+        }
+        return Ok(Keypair(keypair));
+    }
+
+    #[allow(dead_code)]
+    pub fn from_base58(base58_str: &str) -> Result<Keypair> {
+        let bytes = bs58::decode(base58_str).into_vec()
+            .context("An error occurred decoding the keypair from base58")?;
+        let keypair = Keypair::from_bytes(&bytes)
+            .context("An error occurred creating a keypair from the base58-decoded bytes")?;
+        return Ok(keypair);
+    }
+
+    #[allow(dead_code)]
+    pub fn to_base58(&self) -> String {
+        return bs58::encode(self.0.to_bytes()).into_string();
+    }
+
+    // Parses the JSON array of byte values that solana-keygen writes to keypair files (e.g. '[254,255,155,...]')
+    #[allow(dead_code)]
+    pub fn from_json(keypair_json: &str) -> Result<Keypair> {
+        let bytes: Vec<u8> = serde_json::from_str(keypair_json)
+            .context("An error occurred parsing the keypair JSON as an array of bytes")?;
+        let keypair = Keypair::from_bytes(&bytes)
+            .context("An error occurred creating a keypair from the keypair JSON bytes")?;
+        return Ok(keypair);
+    }
+
+    // The inverse of from_json, in the same format that solana-keygen writes out
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(&self.0.to_bytes().to_vec())
+            .expect("A byte array can always be serialized to JSON");
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        return Pubkey::new(self.0.public.to_bytes());
+    }
+
+    pub fn sign_message(&self, message: &[u8]) -> Signature {
+        return Signature::new(self.0.sign(message).to_bytes());
+    }
+}
+
+impl Clone for Keypair {
+    fn clone(&self) -> Keypair {
+        let keypair = ed25519_dalek::Keypair::from_bytes(&self.0.to_bytes())
+            .expect("A keypair's own bytes are always a valid keypair");
+        return Keypair(keypair);
+    }
+}
+
+// Keypairs are (de)serialized as the same array of byte values that solana-keygen writes to keypair files
+impl Serialize for Keypair {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        return self.0.to_bytes().to_vec().serialize(serializer);
+    }
+}
+
+impl<'de> Deserialize<'de> for Keypair {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Keypair, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        return Keypair::from_bytes(&bytes).map_err(|err| de::Error::custom(format!("{:#}", err)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Keypair;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        return (0..hex.len()).step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+    }
+
+    fn rfc8032_keypair(secret_hex: &str, public_hex: &str) -> Keypair {
+        let mut bytes = from_hex(secret_hex);
+        bytes.extend(from_hex(public_hex));
+        return Keypair::from_bytes(&bytes).unwrap();
+    }
+
+    // Test vectors 1 and 2 from https://tools.ietf.org/html/rfc8032#section-7.1
+    #[test]
+    fn test_sign_message_matches_rfc8032_vectors() {
+        let keypair = rfc8032_keypair(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        );
+        assert_eq!(
+            from_hex("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+            keypair.sign_message(&[]).as_bytes().to_vec(),
+        );
+
+        let keypair = rfc8032_keypair(
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+        );
+        assert_eq!(
+            from_hex("92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+            keypair.sign_message(&[0x72]).as_bytes().to_vec(),
+        );
+    }
+
+    // The keypair of the first genesis bootstrapper's identity, along with the pubkey that solana-keygen reported for it
+    #[test]
+    fn test_from_json_matches_solana_keygen() {
+        let keypair_json = "[254,255,155,184,101,78,168,181,71,142,36,127,63,33,110,200,212,27,204,76,99,94,188,170,153,231,27,207,216,78,112,198,182,163,13,150,208,4,44,178,92,76,174,75,59,137,146,117,185,234,165,234,206,138,185,173,12,124,18,82,13,210,209,121]";
+        let keypair = Keypair::from_json(keypair_json).unwrap();
+        assert_eq!("DHwQYfhEP1jrX7jptcyuZ4AFbwmwoiQ6peiL1BHFhzSk", keypair.pubkey().to_string());
+        assert_eq!(keypair_json, keypair.to_json());
+    }
+
+    #[test]
+    fn test_from_json_rejects_bad_keypairs() {
+        assert!(Keypair::from_json("[1,2,3]").is_err());
+        assert!(Keypair::from_json("not json").is_err());
+        assert!(Keypair::from_json("[256]").is_err());
+
+        // Right length, but the public half belongs to a different secret
+        let mut bytes = Keypair::new().0.to_bytes().to_vec();
+        bytes[32..].copy_from_slice(Keypair::new().pubkey().as_bytes());
+        assert!(Keypair::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_serde_uses_keypair_file_format() {
+        let keypair_json = "[254,255,155,184,101,78,168,181,71,142,36,127,63,33,110,200,212,27,204,76,99,94,188,170,153,231,27,207,216,78,112,198,182,163,13,150,208,4,44,178,92,76,174,75,59,137,146,117,185,234,165,234,206,138,185,173,12,124,18,82,13,210,209,121]";
+        let keypair: Keypair = serde_json::from_str(keypair_json).unwrap();
+        assert_eq!("DHwQYfhEP1jrX7jptcyuZ4AFbwmwoiQ6peiL1BHFhzSk", keypair.pubkey().to_string());
+        assert_eq!(keypair_json, serde_json::to_string(&keypair).unwrap());
+    }
+
+    #[test]
+    fn test_base58_round_trip() {
+        let keypair = Keypair::new();
+        let decoded = Keypair::from_base58(&keypair.to_base58()).unwrap();
+        assert_eq!(keypair.pubkey(), decoded.pubkey());
+        assert_eq!(keypair.to_json(), decoded.to_json());
+    }
+}
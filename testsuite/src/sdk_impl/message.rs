@@ -0,0 +1,178 @@
+use std::convert::TryInto;
+
+use anyhow::{Context, Result};
+
+use super::{hash::{HASH_BYTES, Hash}, instruction::{AccountMeta, CompiledInstruction, Instruction}, pubkey::{PUBKEY_BYTES, Pubkey}, short_vec};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/program/src/message.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// Describes how the message's account keys are laid out: first the keys that must sign (writable ones, then read-only
+// ones), then the keys that don't (again writable, then read-only)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MessageHeader {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub header: MessageHeader,
+    pub account_keys: Vec<Pubkey>,
+    pub recent_blockhash: Hash,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+impl Message {
+    // Compiles the instructions into a message, with the payer (if any) as the first signer so that it pays the fees
+    pub fn new(instructions: &[Instruction], payer: Option<&Pubkey>, recent_blockhash: Hash) -> Message {
+        let account_metas = Message::get_account_metas(instructions, payer);
+        let num_required_signatures = account_metas.iter().filter(|meta| meta.is_signer).count();
+        let num_readonly_signed_accounts = account_metas.iter().filter(|meta| meta.is_signer && !meta.is_writable).count();
+        let num_readonly_unsigned_accounts = account_metas.iter().filter(|meta| !meta.is_signer && !meta.is_writable).count();
+        let account_keys: Vec<Pubkey> = account_metas.iter().map(|meta| meta.pubkey).collect();
+        let compiled_instructions = instructions.iter()
+            .map(|instruction| Message::compile_instruction(instruction, &account_keys))
+            .collect();
+        return Message{
+            header: MessageHeader{
+                num_required_signatures: num_required_signatures as u8,
+                num_readonly_signed_accounts: num_readonly_signed_accounts as u8,
+                num_readonly_unsigned_accounts: num_readonly_unsigned_accounts as u8,
+            },
+            account_keys,
+            recent_blockhash,
+            instructions: compiled_instructions,
+        };
+    }
+
+    // The keys whose signatures the message needs, in the order that the signatures go in the transaction
+    pub fn get_signer_keys(&self) -> &[Pubkey] {
+        return &self.account_keys[..self.header.num_required_signatures as usize];
+    }
+
+    // The wire format, which is also exactly the bytes that get signed
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = vec![
+            self.header.num_required_signatures,
+            self.header.num_readonly_signed_accounts,
+            self.header.num_readonly_unsigned_accounts,
+        ];
+        short_vec::encode_len(self.account_keys.len(), &mut result);
+        for account_key in &self.account_keys {
+            result.extend_from_slice(account_key.as_bytes());
+        }
+        result.extend_from_slice(self.recent_blockhash.as_bytes());
+        short_vec::encode_len(self.instructions.len(), &mut result);
+        for instruction in &self.instructions {
+            result.push(instruction.program_id_index);
+            short_vec::encode_len(instruction.accounts.len(), &mut result);
+            result.extend_from_slice(&instruction.accounts);
+            short_vec::encode_len(instruction.data.len(), &mut result);
+            result.extend_from_slice(&instruction.data);
+        }
+        return result;
+    }
+
+    // Reads a message in the wire format off the front of the input, advancing the input past it
+    pub fn deserialize(input: &mut &[u8]) -> Result<Message> {
+        let header_bytes = short_vec::read_bytes(input, 3)
+            .context("An error occurred reading the message header")?;
+        let header = MessageHeader{
+            num_required_signatures: header_bytes[0],
+            num_readonly_signed_accounts: header_bytes[1],
+            num_readonly_unsigned_accounts: header_bytes[2],
+        };
+        let num_account_keys = short_vec::decode_len(input)
+            .context("An error occurred reading the number of account keys")?;
+        let mut account_keys = Vec::with_capacity(num_account_keys);
+        for _ in 0..num_account_keys {
+            let key_bytes = short_vec::read_bytes(input, PUBKEY_BYTES)
+                .context("An error occurred reading an account key")?;
+            account_keys.push(Pubkey::new(key_bytes.try_into().expect("Read exactly the pubkey length")));
+        }
+        let blockhash_bytes = short_vec::read_bytes(input, HASH_BYTES)
+            .context("An error occurred reading the recent blockhash")?;
+        let recent_blockhash = Hash::new(blockhash_bytes.try_into().expect("Read exactly the hash length"));
+        let num_instructions = short_vec::decode_len(input)
+            .context("An error occurred reading the number of instructions")?;
+        let mut instructions = Vec::with_capacity(num_instructions);
+        for i in 0..num_instructions {
+            let program_id_index = short_vec::read_bytes(input, 1)
+                .context(format!("An error occurred reading the program ID index of instruction #{}", i))?[0];
+            let num_accounts = short_vec::decode_len(input)
+                .context(format!("An error occurred reading the number of accounts of instruction #{}", i))?;
+            let accounts = short_vec::read_bytes(input, num_accounts)
+                .context(format!("An error occurred reading the accounts of instruction #{}", i))?
+                .to_vec();
+            let data_len = short_vec::decode_len(input)
+                .context(format!("An error occurred reading the data length of instruction #{}", i))?;
+            let data = short_vec::read_bytes(input, data_len)
+                .context(format!("An error occurred reading the data of instruction #{}", i))?
+                .to_vec();
+            instructions.push(CompiledInstruction{
+                program_id_index,
+                accounts,
+                data,
+            });
+        }
+        return Ok(Message{
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+        });
+    }
+
+    // Dedupes all the accounts referenced by the instructions (programs included) and sorts them into the order
+    // described by MessageHeader, keeping the order in which they were first referenced within each group
+    fn get_account_metas(instructions: &[Instruction], payer: Option<&Pubkey>) -> Vec<AccountMeta> {
+        let mut all_metas: Vec<AccountMeta> = Vec::new();
+        if let Some(payer) = payer {
+            all_metas.push(AccountMeta::new(*payer, true));
+        }
+        for instruction in instructions {
+            all_metas.extend(instruction.accounts.iter().cloned());
+        }
+        for instruction in instructions {
+            all_metas.push(AccountMeta::new_readonly(instruction.program_id, false));
+        }
+
+        let mut unique_metas: Vec<AccountMeta> = Vec::new();
+        for meta in all_metas {
+            // An account that's a signer or writable anywhere is a signer or writable everywhere
+            match unique_metas.iter_mut().find(|unique_meta| unique_meta.pubkey == meta.pubkey) {
+                Some(unique_meta) => {
+                    unique_meta.is_signer |= meta.is_signer;
+                    unique_meta.is_writable |= meta.is_writable;
+                },
+                None => unique_metas.push(meta),
+            }
+        }
+
+        // The sort is stable, so the payer stays first among the writable signers
+        unique_metas.sort_by_key(|meta| (!meta.is_signer, !meta.is_writable));
+        return unique_metas;
+    }
+
+    fn compile_instruction(instruction: &Instruction, account_keys: &[Pubkey]) -> CompiledInstruction {
+        let get_index = |pubkey: &Pubkey| -> u8 {
+            let index = account_keys.iter().position(|key| key == pubkey)
+                .expect("Every key referenced by an instruction is in the account keys, since that's where the account keys came from");
+            return index as u8;
+        };
+        return CompiledInstruction{
+            program_id_index: get_index(&instruction.program_id),
+            accounts: instruction.accounts.iter().map(|meta| get_index(&meta.pubkey)).collect(),
+            data: instruction.data.clone(),
+        };
+    }
+}
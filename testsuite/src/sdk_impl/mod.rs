@@ -0,0 +1,12 @@
+pub mod hash;
+pub mod instruction;
+pub mod keypair;
+pub mod message;
+pub mod pubkey;
+pub mod short_vec;
+pub mod shred_version;
+pub mod signature;
+pub mod stake_state;
+pub mod system_instruction;
+pub mod transaction;
+pub mod vote_instruction;
@@ -0,0 +1,83 @@
+use std::{convert::TryInto, fmt};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/program/src/pubkey.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+pub const PUBKEY_BYTES: usize = 32;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Pubkey([u8; PUBKEY_BYTES]);
+
+impl Pubkey {
+    pub const fn new(bytes: [u8; PUBKEY_BYTES]) -> Pubkey {
+        return Pubkey(bytes);
+    }
+
+    pub fn from_base58(base58_str: &str) -> Result<Pubkey> {
+        let bytes = bs58::decode(base58_str).into_vec()
+            .context(format!("An error occurred decoding pubkey '{}' from base58", base58_str))?;
+        let bytes: [u8; PUBKEY_BYTES] = bytes.as_slice().try_into()
+            .map_err(|_| anyhow!(
+                "Expected pubkey '{}' to decode to {} bytes but got {}",
+                base58_str,
+                PUBKEY_BYTES,
+                bytes.len(),
+            ))?;
+        return Ok(Pubkey(bytes));
+    }
+
+    pub fn as_bytes(&self) -> &[u8; PUBKEY_BYTES] {
+        return &self.0;
+    }
+}
+
+impl fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", bs58::encode(self.0).into_string());
+    }
+}
+
+// Pubkeys are (de)serialized as base58 strings, the same as they're displayed
+impl Serialize for Pubkey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&self.to_string());
+    }
+}
+
+impl<'de> Deserialize<'de> for Pubkey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Pubkey, D::Error> {
+        let base58_str = String::deserialize(deserializer)?;
+        return Pubkey::from_base58(&base58_str).map_err(|err| de::Error::custom(format!("{:#}", err)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pubkey;
+
+    #[test]
+    fn test_base58_round_trip() {
+        let pubkey_str = "DHwQYfhEP1jrX7jptcyuZ4AFbwmwoiQ6peiL1BHFhzSk";
+        assert_eq!(pubkey_str, Pubkey::from_base58(pubkey_str).unwrap().to_string());
+        assert_eq!("11111111111111111111111111111111", Pubkey::new([0; 32]).to_string());
+        assert_eq!(Pubkey::new([0; 32]), Pubkey::from_base58("11111111111111111111111111111111").unwrap());
+    }
+
+    #[test]
+    fn test_from_base58_rejects_bad_pubkeys() {
+        // Too short
+        assert!(Pubkey::from_base58("DHwQYfhEP1jrX7jpt").is_err());
+        // '0' isn't in the base58 alphabet
+        assert!(Pubkey::from_base58("0HwQYfhEP1jrX7jptcyuZ4AFbwmwoiQ6peiL1BHFhzSk").is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/src/short_vec.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// Appends the "compact-u16" encoding of a length that Solana uses to prefix every array on the wire: 7 bits per byte,
+// least significant group first, with the high bit set on every byte except the last
+pub fn encode_len(len: usize, output: &mut Vec<u8>) {
+    assert!(len <= u16::MAX as usize, "Length {} doesn't fit in a short_vec", len);
+    let mut remaining = len;
+    loop {
+        let mut byte = (remaining & 0x7f) as u8;
+        remaining >>= 7;
+        if remaining == 0 {
+            output.push(byte);
+            return;
+        }
+        byte |= 0x80;
+        output.push(byte);
+    }
+}
+
+// Reads a "compact-u16" length off the front of the input, advancing the input past it
+pub fn decode_len(input: &mut &[u8]) -> Result<usize> {
+    let mut len: usize = 0;
+    for i in 0..3 {
+        let byte = *input.get(i).ok_or_else(|| anyhow!(
+            "Input ended partway through a short_vec length",
+        ))?;
+        len |= ((byte & 0x7f) as usize) << (i * 7);
+        if byte & 0x80 == 0 {
+            if len > u16::MAX as usize {
+                return Err(anyhow!(
+                    "Short_vec length {} doesn't fit in a u16",
+                    len,
+                ));
+            }
+            *input = &input[i + 1..];
+            return Ok(len);
+        }
+    }
+    return Err(anyhow!(
+        "Short_vec length is longer than 3 bytes",
+    ));
+}
+
+// Splits the given number of bytes off the front of the input, advancing the input past them
+pub fn read_bytes<'a>(input: &mut &'a [u8], num_bytes: usize) -> Result<&'a [u8]> {
+    if input.len() < num_bytes {
+        return Err(anyhow!(
+            "Expected {} more bytes but the input only has {}",
+            num_bytes,
+            input.len(),
+        ));
+    }
+    let (result, rest) = input.split_at(num_bytes);
+    *input = rest;
+    return Ok(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_len, encode_len};
+
+    fn encode(len: usize) -> Vec<u8> {
+        let mut result = Vec::new();
+        encode_len(len, &mut result);
+        return result;
+    }
+
+    #[test]
+    fn test_encode_len() {
+        assert_eq!(vec![0x00], encode(0));
+        assert_eq!(vec![0x7f], encode(0x7f));
+        assert_eq!(vec![0x80, 0x01], encode(0x80));
+        assert_eq!(vec![0xff, 0x01], encode(0xff));
+        assert_eq!(vec![0x80, 0x02], encode(0x100));
+        assert_eq!(vec![0xff, 0x7f], encode(0x3fff));
+        assert_eq!(vec![0x80, 0x80, 0x01], encode(0x4000));
+        assert_eq!(vec![0xff, 0xff, 0x03], encode(0xffff));
+    }
+
+    #[test]
+    fn test_decode_len_round_trips() {
+        for len in &[0, 0x7f, 0x80, 0xff, 0x100, 0x3fff, 0x4000, 0xffff] {
+            let mut encoded = encode(*len);
+            encoded.push(42);
+            let mut input = &encoded[..];
+            assert_eq!(*len, decode_len(&mut input).unwrap());
+            assert_eq!(&[42], input);
+        }
+        assert!(decode_len(&mut &[0x80][..]).is_err());
+        assert!(decode_len(&mut &[0xff, 0xff, 0x04][..]).is_err());
+        assert!(decode_len(&mut &[0x80, 0x80, 0x80, 0x01][..]).is_err());
+    }
+}
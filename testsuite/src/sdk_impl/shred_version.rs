@@ -0,0 +1,57 @@
+use super::hash::Hash;
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/src/shred_version.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// Folds the hash down to 16 bits by XORing its byte pairs together
+pub fn version_from_hash(hash: &Hash) -> u16 {
+    let mut accum = [0u8; 2];
+    for seed in hash.as_bytes().chunks(2) {
+        for (accum, seed) in accum.iter_mut().zip(seed) {
+            *accum ^= *seed;
+        }
+    }
+    let version = ((accum[0] as u16) << 8) | accum[1] as u16;
+    // Never zero, so that it can't be mistaken for an uninitialized version
+    return version.saturating_add(1);
+}
+
+// The shred version of a cluster that hasn't had any hard forks, which is all of the clusters the testsuite starts
+pub fn compute_shred_version(genesis_hash: &Hash) -> u16 {
+    return version_from_hash(genesis_hash);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{networks_impl::genesis_config::GenesisConfig, sdk_impl::hash::Hash};
+
+    use super::{compute_shred_version, version_from_hash};
+
+    const CHECKED_IN_GENESIS_CONFIG_FILEPATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/genesis-config.json");
+
+    #[test]
+    fn test_version_from_hash() {
+        assert_eq!(1, version_from_hash(&Hash::default()));
+        let mut bytes = [0u8; 32];
+        bytes[0] = 0x12;
+        bytes[1] = 0x34;
+        bytes[2] = 0x01;
+        assert_eq!(0x1335, version_from_hash(&Hash::new(bytes)));
+        // An even number of identical pairs cancel each other out
+        assert_eq!(1, version_from_hash(&Hash::new([0xff; 32])));
+    }
+
+    #[test]
+    fn test_shred_version_matches_checked_in_genesis() {
+        let genesis_config = GenesisConfig::load(CHECKED_IN_GENESIS_CONFIG_FILEPATH).unwrap();
+        let genesis_hash = Hash::from_base58(genesis_config.get_genesis_hash()).unwrap();
+        assert_eq!(genesis_config.get_shred_version(), compute_shred_version(&genesis_hash) as u64);
+    }
+}
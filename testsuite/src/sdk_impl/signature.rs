@@ -0,0 +1,59 @@
+use std::{convert::TryInto, fmt};
+
+use anyhow::{anyhow, Context, Result};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/src/signature.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+pub const SIGNATURE_BYTES: usize = 64;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Signature([u8; SIGNATURE_BYTES]);
+
+impl Signature {
+    pub fn new(bytes: [u8; SIGNATURE_BYTES]) -> Signature {
+        return Signature(bytes);
+    }
+
+    pub fn from_base58(base58_str: &str) -> Result<Signature> {
+        let bytes = bs58::decode(base58_str).into_vec()
+            .context(format!("An error occurred decoding signature '{}' from base58", base58_str))?;
+        let bytes: [u8; SIGNATURE_BYTES] = bytes.as_slice().try_into()
+            .map_err(|_| anyhow!(
+                "Expected signature '{}' to decode to {} bytes but got {}",
+                base58_str,
+                SIGNATURE_BYTES,
+                bytes.len(),
+            ))?;
+        return Ok(Signature(bytes));
+    }
+
+    pub fn as_bytes(&self) -> &[u8; SIGNATURE_BYTES] {
+        return &self.0;
+    }
+}
+
+impl Default for Signature {
+    fn default() -> Signature {
+        return Signature([0; SIGNATURE_BYTES]);
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", bs58::encode(self.0).into_string());
+    }
+}
+
+impl fmt::Debug for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self);
+    }
+}
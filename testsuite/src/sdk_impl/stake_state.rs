@@ -0,0 +1,88 @@
+use super::pubkey::Pubkey;
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/programs/stake/src/stake_state.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// "Stake11111111111111111111111111111111111111"
+pub const STAKE_PROGRAM_ID: Pubkey = Pubkey::new([
+    6, 161, 216, 23, 145, 55, 84, 42, 152, 52, 55, 189, 254, 42, 122, 178,
+    85, 127, 83, 92, 138, 120, 114, 43, 104, 164, 157, 192, 0, 0, 0, 0,
+]);
+
+// The size of a StakeState, which is how much space a stake account needs (as of Solana v1.5)
+pub const STAKE_STATE_SIZE: usize = 200;
+
+// What a stake account needs to hold to be rent-exempt at STAKE_STATE_SIZE, under the default rent that solana-genesis
+// uses: (128 bytes of account overhead + STAKE_STATE_SIZE) * 3480 lamports per byte-year * 2 years
+pub const STAKE_RENT_EXEMPT_RESERVE: u64 = 2_282_880;
+
+// Index of the StakeState enum variant for an account that's delegated to a vote account
+const STAKE_STATE_STAKE_INDEX: u32 = 2;
+
+// An activation epoch of u64::MAX marks the stake as bootstrap stake, which is fully active from genesis without warming up
+const BOOTSTRAP_ACTIVATION_EPOCH: u64 = u64::MAX;
+const NOT_DEACTIVATED_EPOCH: u64 = u64::MAX;
+const DEFAULT_WARMUP_COOLDOWN_RATE: f64 = 0.25;
+
+// Serializes the data of a stake account that delegates the given stake to the vote account from genesis, the same way
+// solana-genesis sets up its bootstrap validators' stake; the account's balance should be stake + STAKE_RENT_EXEMPT_RESERVE
+pub fn serialize_bootstrap_stake(authority: &Pubkey, vote_pubkey: &Pubkey, stake: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(STAKE_STATE_SIZE);
+    data.extend_from_slice(&STAKE_STATE_STAKE_INDEX.to_le_bytes());
+
+    // Meta: rent-exempt reserve, authorized staker & withdrawer, then an empty lockup
+    data.extend_from_slice(&STAKE_RENT_EXEMPT_RESERVE.to_le_bytes());
+    data.extend_from_slice(authority.as_bytes());
+    data.extend_from_slice(authority.as_bytes());
+    data.extend_from_slice(&0i64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(Pubkey::default().as_bytes());
+
+    // Stake: the delegation, then the vote credits observed (none, since nothing has voted yet)
+    data.extend_from_slice(vote_pubkey.as_bytes());
+    data.extend_from_slice(&stake.to_le_bytes());
+    data.extend_from_slice(&BOOTSTRAP_ACTIVATION_EPOCH.to_le_bytes());
+    data.extend_from_slice(&NOT_DEACTIVATED_EPOCH.to_le_bytes());
+    data.extend_from_slice(&DEFAULT_WARMUP_COOLDOWN_RATE.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+
+    // The account is sized for the largest StakeState variant, with the unused tail left zeroed
+    data.resize(STAKE_STATE_SIZE, 0);
+    return data;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sdk_impl::pubkey::Pubkey;
+
+    use super::{STAKE_PROGRAM_ID, STAKE_STATE_SIZE, serialize_bootstrap_stake};
+
+    #[test]
+    fn test_program_id_matches_solana() {
+        assert_eq!("Stake11111111111111111111111111111111111111", STAKE_PROGRAM_ID.to_string());
+    }
+
+    #[test]
+    fn test_serialize_bootstrap_stake_matches_bincode_layout() {
+        let authority = Pubkey::new([1; 32]);
+        let vote = Pubkey::new([2; 32]);
+        let data = serialize_bootstrap_stake(&authority, &vote, 42);
+        assert_eq!(STAKE_STATE_SIZE, data.len());
+        assert_eq!(&[2, 0, 0, 0], &data[0..4]);
+        assert_eq!(&[1; 32], &data[12..44]);
+        assert_eq!(&[1; 32], &data[44..76]);
+        // The delegation comes after the 120-byte Meta
+        assert_eq!(&[2; 32], &data[124..156]);
+        assert_eq!(&42u64.to_le_bytes(), &data[156..164]);
+        assert_eq!(&u64::MAX.to_le_bytes(), &data[164..172]);
+        assert_eq!(&0.25f64.to_le_bytes(), &data[180..188]);
+        assert!(data[196..].iter().all(|byte| *byte == 0));
+    }
+}
@@ -0,0 +1,48 @@
+use super::{instruction::{AccountMeta, Instruction}, pubkey::Pubkey};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/program/src/system_instruction.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// The system program's ID is all zeroes, which base58-encodes to "11111111111111111111111111111111"
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new([0; 32]);
+
+// Indexes of the SystemInstruction enum variants, which is how the system program tells its instructions apart
+const CREATE_ACCOUNT_INSTRUCTION_INDEX: u32 = 0;
+const TRANSFER_INSTRUCTION_INDEX: u32 = 2;
+
+pub fn create_account(from_pubkey: &Pubkey, to_pubkey: &Pubkey, lamports: u64, space: u64, owner: &Pubkey) -> Instruction {
+    let mut data = Vec::new();
+    data.extend_from_slice(&CREATE_ACCOUNT_INSTRUCTION_INDEX.to_le_bytes());
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data.extend_from_slice(&space.to_le_bytes());
+    data.extend_from_slice(owner.as_bytes());
+    return Instruction{
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*from_pubkey, true),
+            AccountMeta::new(*to_pubkey, true),
+        ],
+        data,
+    };
+}
+
+pub fn transfer(from_pubkey: &Pubkey, to_pubkey: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = Vec::new();
+    data.extend_from_slice(&TRANSFER_INSTRUCTION_INDEX.to_le_bytes());
+    data.extend_from_slice(&lamports.to_le_bytes());
+    return Instruction{
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*from_pubkey, true),
+            AccountMeta::new(*to_pubkey, false),
+        ],
+        data,
+    };
+}
@@ -0,0 +1,197 @@
+use std::convert::TryInto;
+
+use anyhow::{anyhow, Context, Result};
+
+use super::{hash::Hash, instruction::Instruction, keypair::Keypair, message::Message, short_vec, signature::{SIGNATURE_BYTES, Signature}};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/sdk/src/transaction.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transaction {
+    // One per required signer, in the same order as the signers appear in the message's account keys
+    pub signatures: Vec<Signature>,
+    pub message: Message,
+}
+
+impl Transaction {
+    // Builds the message from the instructions and signs it with every keypair it requires
+    // The signers may be given in any order, but every required signer must be present
+    pub fn new_signed_with_payer(
+        instructions: &[Instruction],
+        payer: Option<&Keypair>,
+        signers: &[&Keypair],
+        recent_blockhash: Hash,
+    ) -> Result<Transaction> {
+        let payer_pubkey = payer.map(|payer| payer.pubkey());
+        let message = Message::new(instructions, payer_pubkey.as_ref(), recent_blockhash);
+        let message_bytes = message.serialize();
+        let mut signatures = Vec::new();
+        for signer_key in message.get_signer_keys() {
+            let signer = payer.into_iter()
+                .chain(signers.iter().copied())
+                .find(|signer| signer.pubkey() == *signer_key)
+                .ok_or_else(|| anyhow!(
+                    "The transaction requires a signature from '{}', but no keypair was given for it",
+                    signer_key,
+                ))?;
+            signatures.push(signer.sign_message(&message_bytes));
+        }
+        return Ok(Transaction{
+            signatures,
+            message,
+        });
+    }
+
+    // The first signature, which is what the cluster uses to identify the transaction
+    pub fn get_signature(&self) -> Signature {
+        return self.signatures.first().copied().unwrap_or_default();
+    }
+
+    // The wire format, as accepted by the sendTransaction RPC method
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        short_vec::encode_len(self.signatures.len(), &mut result);
+        for signature in &self.signatures {
+            result.extend_from_slice(signature.as_bytes());
+        }
+        result.extend(self.message.serialize());
+        return result;
+    }
+
+    // Parses the wire format, e.g. a transaction that something else built & signed
+    pub fn deserialize(bytes: &[u8]) -> Result<Transaction> {
+        let mut input = bytes;
+        let num_signatures = short_vec::decode_len(&mut input)
+            .context("An error occurred reading the number of signatures")?;
+        let mut signatures = Vec::with_capacity(num_signatures);
+        for _ in 0..num_signatures {
+            let signature_bytes = short_vec::read_bytes(&mut input, SIGNATURE_BYTES)
+                .context("An error occurred reading a signature")?;
+            signatures.push(Signature::new(signature_bytes.try_into().expect("Read exactly the signature length")));
+        }
+        let message = Message::deserialize(&mut input)
+            .context("An error occurred reading the message")?;
+        if !input.is_empty() {
+            return Err(anyhow!(
+                "Transaction has {} bytes left over after the message",
+                input.len(),
+            ));
+        }
+        return Ok(Transaction{
+            signatures,
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ed25519_dalek::{PublicKey, Verifier};
+
+    use crate::sdk_impl::{hash::Hash, keypair::Keypair, pubkey::Pubkey, system_instruction};
+
+    use super::Transaction;
+
+    const FROM_KEYPAIR_JSON: &str = "[254,255,155,184,101,78,168,181,71,142,36,127,63,33,110,200,212,27,204,76,99,94,188,170,153,231,27,207,216,78,112,198,182,163,13,150,208,4,44,178,92,76,174,75,59,137,146,117,185,234,165,234,206,138,185,173,12,124,18,82,13,210,209,121]";
+    const TO_PUBKEY_BYTES: [u8; 32] = [7; 32];
+    const BLOCKHASH_BYTES: [u8; 32] = [9; 32];
+
+    fn assert_signature_is_valid(transaction: &Transaction, signer_index: usize) {
+        let signer_key = transaction.message.account_keys[signer_index];
+        let public_key = PublicKey::from_bytes(signer_key.as_bytes()).unwrap();
+        let signature = ed25519_dalek::Signature::try_from(&transaction.signatures[signer_index].as_bytes()[..]).unwrap();
+        public_key.verify(&transaction.message.serialize(), &signature).unwrap();
+    }
+
+    #[test]
+    fn test_transfer_serializes_byte_for_byte() {
+        let from = Keypair::from_json(FROM_KEYPAIR_JSON).unwrap();
+        let to = Pubkey::new(TO_PUBKEY_BYTES);
+        let instruction = system_instruction::transfer(&from.pubkey(), &to, 1_000_000_007);
+        let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&from), &[], Hash::new(BLOCKHASH_BYTES)).unwrap();
+
+        let mut expected_message: Vec<u8> = vec![
+            1, 0, 1,  // 1 required signature, 0 read-only signed accounts, 1 read-only unsigned account
+            3,        // 3 account keys...
+        ];
+        expected_message.extend_from_slice(from.pubkey().as_bytes());  // ...the payer...
+        expected_message.extend_from_slice(&TO_PUBKEY_BYTES);          // ...the recipient...
+        expected_message.extend_from_slice(&[0; 32]);                  // ...and the system program
+        expected_message.extend_from_slice(&BLOCKHASH_BYTES);
+        expected_message.extend_from_slice(&[
+            1,                          // 1 instruction...
+            2,                          // ...calling the system program...
+            2, 0, 1,                    // ...on accounts 0 and 1...
+            12,                         // ...with 12 bytes of data:
+            2, 0, 0, 0,                 // the Transfer variant index, as a little-endian u32
+            0x07, 0xca, 0x9a, 0x3b, 0, 0, 0, 0,  // 1_000_000_007 lamports, as a little-endian u64
+        ]);
+        assert_eq!(expected_message, transaction.message.serialize());
+
+        let mut expected_transaction = vec![1];  // 1 signature
+        expected_transaction.extend_from_slice(transaction.signatures[0].as_bytes());
+        expected_transaction.extend(expected_message);
+        assert_eq!(expected_transaction, transaction.serialize());
+        assert_signature_is_valid(&transaction, 0);
+        assert_eq!(transaction.signatures[0], transaction.get_signature());
+    }
+
+    #[test]
+    fn test_create_account_orders_signers() {
+        let from = Keypair::from_json(FROM_KEYPAIR_JSON).unwrap();
+        let new_account = Keypair::new();
+        let owner = Pubkey::new([5; 32]);
+        let instruction = system_instruction::create_account(&from.pubkey(), &new_account.pubkey(), 42, 165, &owner);
+        // Signers are matched up by pubkey, so the order they're given in doesn't matter
+        let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&from), &[&new_account], Hash::new(BLOCKHASH_BYTES)).unwrap();
+
+        assert_eq!(2, transaction.message.header.num_required_signatures);
+        assert_eq!(0, transaction.message.header.num_readonly_signed_accounts);
+        assert_eq!(1, transaction.message.header.num_readonly_unsigned_accounts);
+        assert_eq!(vec![from.pubkey(), new_account.pubkey(), Pubkey::new([0; 32])], transaction.message.account_keys);
+
+        let mut expected_data = vec![0, 0, 0, 0];                      // The CreateAccount variant index
+        expected_data.extend_from_slice(&[42, 0, 0, 0, 0, 0, 0, 0]);   // Lamports
+        expected_data.extend_from_slice(&[165, 0, 0, 0, 0, 0, 0, 0]);  // Space
+        expected_data.extend_from_slice(&[5; 32]);                     // Owner
+        assert_eq!(expected_data, transaction.message.instructions[0].data);
+        assert_eq!(vec![0, 1], transaction.message.instructions[0].accounts);
+
+        assert_eq!(2, transaction.serialize()[0]);
+        assert_signature_is_valid(&transaction, 0);
+        assert_signature_is_valid(&transaction, 1);
+    }
+
+    #[test]
+    fn test_deserialize_round_trips() {
+        let from = Keypair::from_json(FROM_KEYPAIR_JSON).unwrap();
+        let new_account = Keypair::new();
+        let instruction = system_instruction::create_account(&from.pubkey(), &new_account.pubkey(), 42, 165, &Pubkey::new([5; 32]));
+        let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&from), &[&new_account], Hash::new(BLOCKHASH_BYTES)).unwrap();
+        let serialized = transaction.serialize();
+        assert_eq!(transaction, Transaction::deserialize(&serialized).unwrap());
+
+        assert!(Transaction::deserialize(&serialized[..serialized.len() - 1]).is_err());
+        let mut with_trailing_byte = serialized.clone();
+        with_trailing_byte.push(0);
+        assert!(Transaction::deserialize(&with_trailing_byte).is_err());
+    }
+
+    #[test]
+    fn test_missing_signer_is_rejected() {
+        let from = Keypair::from_json(FROM_KEYPAIR_JSON).unwrap();
+        let new_account = Keypair::new();
+        let instruction = system_instruction::create_account(&from.pubkey(), &new_account.pubkey(), 42, 0, &Pubkey::new([5; 32]));
+        assert!(Transaction::new_signed_with_payer(&[instruction], Some(&from), &[], Hash::new(BLOCKHASH_BYTES)).is_err());
+    }
+}
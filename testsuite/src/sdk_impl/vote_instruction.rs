@@ -0,0 +1,110 @@
+use super::{instruction::{AccountMeta, Instruction}, pubkey::Pubkey, system_instruction};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/programs/vote/src/vote_instruction.rs
+because solana-sdk is pulled in alongside solana-client, which provides Ledger support, which means it has a
+dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana SDK here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// "Vote111111111111111111111111111111111111111"
+pub const VOTE_PROGRAM_ID: Pubkey = Pubkey::new([
+    7, 97, 72, 29, 53, 116, 116, 187, 124, 77, 118, 36, 235, 211, 189, 179,
+    216, 53, 94, 115, 209, 16, 67, 252, 13, 163, 83, 128, 0, 0, 0, 0,
+]);
+
+// "SysvarRent111111111111111111111111111111111"
+const RENT_SYSVAR_ID: Pubkey = Pubkey::new([
+    6, 167, 213, 23, 25, 44, 92, 81, 33, 140, 201, 76, 61, 74, 241, 127,
+    88, 218, 238, 8, 155, 161, 253, 68, 227, 219, 217, 138, 0, 0, 0, 0,
+]);
+
+// "SysvarC1ock11111111111111111111111111111111"
+const CLOCK_SYSVAR_ID: Pubkey = Pubkey::new([
+    6, 167, 213, 23, 24, 199, 116, 201, 40, 86, 99, 152, 105, 29, 94, 182,
+    139, 94, 184, 163, 155, 75, 109, 92, 115, 85, 91, 33, 0, 0, 0, 0,
+]);
+
+// The serialized size of a VoteState, which is how much space a vote account needs (as of Solana v1.5)
+pub const VOTE_STATE_SIZE: u64 = 3731;
+
+// Index of the VoteInstruction enum variant, which is how the vote program tells its instructions apart
+const INITIALIZE_ACCOUNT_INSTRUCTION_INDEX: u32 = 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VoteInit {
+    pub node_pubkey: Pubkey,
+    pub authorized_voter: Pubkey,
+    pub authorized_withdrawer: Pubkey,
+    pub commission: u8,
+}
+
+// Creates a vote account owned by the vote program & initializes it; both the vote account and the node must sign
+// The lamports should be enough to make the account rent-exempt at VOTE_STATE_SIZE
+pub fn create_account(from_pubkey: &Pubkey, vote_pubkey: &Pubkey, vote_init: &VoteInit, lamports: u64) -> Vec<Instruction> {
+    let create_ix = system_instruction::create_account(from_pubkey, vote_pubkey, lamports, VOTE_STATE_SIZE, &VOTE_PROGRAM_ID);
+    let init_ix = initialize_account(vote_pubkey, vote_init);
+    return vec![create_ix, init_ix];
+}
+
+fn initialize_account(vote_pubkey: &Pubkey, vote_init: &VoteInit) -> Instruction {
+    let mut data = Vec::new();
+    data.extend_from_slice(&INITIALIZE_ACCOUNT_INSTRUCTION_INDEX.to_le_bytes());
+    data.extend_from_slice(vote_init.node_pubkey.as_bytes());
+    data.extend_from_slice(vote_init.authorized_voter.as_bytes());
+    data.extend_from_slice(vote_init.authorized_withdrawer.as_bytes());
+    data.push(vote_init.commission);
+    return Instruction{
+        program_id: VOTE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*vote_pubkey, false),
+            AccountMeta::new_readonly(RENT_SYSVAR_ID, false),
+            AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+            AccountMeta::new_readonly(vote_init.node_pubkey, true),
+        ],
+        data,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sdk_impl::{pubkey::Pubkey, system_instruction::SYSTEM_PROGRAM_ID};
+
+    use super::{CLOCK_SYSVAR_ID, RENT_SYSVAR_ID, VOTE_PROGRAM_ID, VoteInit, create_account};
+
+    #[test]
+    fn test_program_ids_match_solana() {
+        assert_eq!("Vote111111111111111111111111111111111111111", VOTE_PROGRAM_ID.to_string());
+        assert_eq!("SysvarRent111111111111111111111111111111111", RENT_SYSVAR_ID.to_string());
+        assert_eq!("SysvarC1ock11111111111111111111111111111111", CLOCK_SYSVAR_ID.to_string());
+    }
+
+    #[test]
+    fn test_create_account_matches_bincode_layout() {
+        let from = Pubkey::new([1; 32]);
+        let vote = Pubkey::new([2; 32]);
+        let vote_init = VoteInit{
+            node_pubkey: Pubkey::new([3; 32]),
+            authorized_voter: Pubkey::new([4; 32]),
+            authorized_withdrawer: Pubkey::new([5; 32]),
+            commission: 100,
+        };
+        let instructions = create_account(&from, &vote, &vote_init, 42);
+        assert_eq!(2, instructions.len());
+        assert_eq!(SYSTEM_PROGRAM_ID, instructions[0].program_id);
+
+        let init_ix = &instructions[1];
+        assert_eq!(VOTE_PROGRAM_ID, init_ix.program_id);
+        let mut expected_data = vec![0, 0, 0, 0];
+        expected_data.extend_from_slice(&[3; 32]);
+        expected_data.extend_from_slice(&[4; 32]);
+        expected_data.extend_from_slice(&[5; 32]);
+        expected_data.push(100);
+        assert_eq!(expected_data, init_ix.data);
+        let signers: Vec<Pubkey> = init_ix.accounts.iter().filter(|meta| meta.is_signer).map(|meta| meta.pubkey).collect();
+        assert_eq!(vec![vote_init.node_pubkey], signers);
+    }
+}
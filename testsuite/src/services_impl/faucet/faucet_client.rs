@@ -0,0 +1,138 @@
+use anyhow::{Context, Result, anyhow};
+use std::{io::{ErrorKind, Read, Write}, net::{SocketAddr, TcpStream}, time::Duration};
+
+use crate::sdk_impl::{hash::Hash, pubkey::Pubkey, transaction::Transaction};
+
+// Same timeouts as the official faucet client
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// The faucet's transactions have to fit in a packet, so any length over this means the response is garbage
+const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
+
+// The index of the GetAirdrop variant of the faucet's FaucetRequest enum, which is its only variant
+const GET_AIRDROP_REQUEST_VARIANT: u32 = 0;
+
+// Speaks the faucet's TCP protocol: the request is a bincode-serialized FaucetRequest, and the response is the airdrop
+// transaction (signed by the faucet but not submitted) prefixed by its length as a little-endian u16
+// The faucet refuses a request (e.g. one over its caps) by either closing the connection or sending a zero length
+// See: https://github.com/solana-labs/solana/blob/v1.5.10/faucet/src/faucet.rs
+pub struct FaucetClient {
+    addr: SocketAddr,
+}
+
+impl FaucetClient {
+    pub fn new(addr: SocketAddr) -> FaucetClient {
+        return FaucetClient{
+            addr,
+        };
+    }
+
+    pub fn request_airdrop_transaction(&self, to: &Pubkey, lamports: u64, blockhash: &Hash) -> Result<Transaction> {
+        let mut stream = TcpStream::connect_timeout(&self.addr, CONNECT_TIMEOUT)
+            .context(format!("An error occurred connecting to the faucet at '{}'", self.addr))?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))
+            .context("An error occurred setting the faucet connection's read timeout")?;
+        stream.write_all(&serialize_airdrop_request(to, lamports, blockhash))
+            .context("An error occurred sending the airdrop request to the faucet")?;
+
+        let mut length_bytes = [0u8; 2];
+        if let Err(err) = stream.read_exact(&mut length_bytes) {
+            if err.kind() == ErrorKind::UnexpectedEof {
+                return Err(anyhow!(
+                    "The faucet closed the connection without a transaction, refusing the airdrop of {} lamports to '{}'",
+                    lamports,
+                    to,
+                ));
+            }
+            return Err(err).context("An error occurred reading the airdrop transaction's length from the faucet");
+        }
+        let transaction_length = u16::from_le_bytes(length_bytes) as usize;
+        if transaction_length == 0 {
+            return Err(anyhow!(
+                "The faucet refused the airdrop of {} lamports to '{}'",
+                lamports,
+                to,
+            ));
+        }
+        if transaction_length > PACKET_DATA_SIZE {
+            return Err(anyhow!(
+                "The faucet sent an airdrop transaction length of {} bytes, which is more than fits in a packet",
+                transaction_length,
+            ));
+        }
+        let mut transaction_bytes = vec![0u8; transaction_length];
+        stream.read_exact(&mut transaction_bytes)
+            .context(format!("An error occurred reading the {}-byte airdrop transaction from the faucet", transaction_length))?;
+        let transaction = Transaction::deserialize(&transaction_bytes)
+            .context("An error occurred parsing the airdrop transaction from the faucet")?;
+        return Ok(transaction);
+    }
+}
+
+// The bincode serialization of FaucetRequest::GetAirdrop{lamports, to, blockhash}
+fn serialize_airdrop_request(to: &Pubkey, lamports: u64, blockhash: &Hash) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&GET_AIRDROP_REQUEST_VARIANT.to_le_bytes());
+    result.extend_from_slice(&lamports.to_le_bytes());
+    result.extend_from_slice(to.as_bytes());
+    result.extend_from_slice(blockhash.as_bytes());
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryInto, io::{Read, Write}, net::{SocketAddr, TcpListener}, thread};
+
+    use crate::sdk_impl::{hash::Hash, keypair::Keypair, pubkey::Pubkey, system_instruction, transaction::Transaction};
+
+    use super::FaucetClient;
+
+    const AIRDROP_REQUEST_LEN: usize = 4 + 8 + 32 + 32;
+
+    // Answers a single airdrop request the way the real faucet does, with the given keypair paying for the airdrop
+    // Returns: the address the fake faucet listens on
+    fn start_fake_faucet(keypair: Keypair, lamport_cap: u64) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; AIRDROP_REQUEST_LEN];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!([0, 0, 0, 0], request[..4]);
+            let lamports = u64::from_le_bytes(request[4..12].try_into().unwrap());
+            let to = Pubkey::new(request[12..44].try_into().unwrap());
+            let blockhash = Hash::new(request[44..76].try_into().unwrap());
+            if lamports > lamport_cap {
+                // Dropping the stream closes the connection, just like the real faucet does on an error
+                return;
+            }
+            let instruction = system_instruction::transfer(&keypair.pubkey(), &to, lamports);
+            let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&keypair), &[], blockhash).unwrap();
+            let transaction_bytes = transaction.serialize();
+            stream.write_all(&(transaction_bytes.len() as u16).to_le_bytes()).unwrap();
+            stream.write_all(&transaction_bytes).unwrap();
+        });
+        return addr;
+    }
+
+    #[test]
+    fn test_request_airdrop_transaction() {
+        let faucet_keypair = Keypair::new();
+        let addr = start_fake_faucet(faucet_keypair.clone(), 1_000);
+        let to = Pubkey::new([7; 32]);
+        let blockhash = Hash::new([9; 32]);
+
+        let transaction = FaucetClient::new(addr).request_airdrop_transaction(&to, 1_000, &blockhash).unwrap();
+        let expected_instruction = system_instruction::transfer(&faucet_keypair.pubkey(), &to, 1_000);
+        let expected_transaction = Transaction::new_signed_with_payer(&[expected_instruction], Some(&faucet_keypair), &[], blockhash).unwrap();
+        assert_eq!(expected_transaction, transaction);
+    }
+
+    #[test]
+    fn test_request_airdrop_transaction_refused() {
+        let addr = start_fake_faucet(Keypair::new(), 1_000);
+        let err = FaucetClient::new(addr).request_airdrop_transaction(&Pubkey::new([7; 32]), 1_001, &Hash::default()).unwrap_err();
+        assert!(format!("{:#}", err).contains("refusing the airdrop of 1001 lamports"), "{:#}", err);
+    }
+}
@@ -3,6 +3,8 @@ use std::{collections::{HashMap, HashSet}, fs::File, io::Write, path::PathBuf};
 
 use kurtosis_rust_lib::services::{docker_container_initializer::DockerContainerInitializer, service::Service, service_context::ServiceContext};
 
+use crate::sdk_impl::keypair::Keypair;
+
 use super::faucet_service::{FAUCET_PORT, FaucetService};
 
 const KEYPAIR_FILE_KEY: &str = "keypair";
@@ -10,14 +12,14 @@ const TEST_VOLUME_MOUNTPOINT: &str = "/test-volume";
 
 pub struct FaucetContainerInitializer {
     docker_image: String,
-    keypair_json: String,
+    keypair: Keypair,
 }
 
 impl FaucetContainerInitializer {
-    pub fn new(docker_image: String, keypair_json: String) -> FaucetContainerInitializer {
+    pub fn new(docker_image: String, keypair: Keypair) -> FaucetContainerInitializer {
         return FaucetContainerInitializer{
             docker_image,
-            keypair_json,
+            keypair,
         };
     }
 }
@@ -35,7 +37,7 @@ impl DockerContainerInitializer<FaucetService> for FaucetContainerInitializer {
     }
 
     fn get_service(&self, service_context: ServiceContext) -> Box<FaucetService> {
-        return Box::new(FaucetService::new(service_context, self.keypair_json.clone()));
+        return Box::new(FaucetService::new(service_context, self.keypair.clone()));
     }
 
     fn get_files_to_generate(&self) -> HashSet<String> {
@@ -47,7 +49,7 @@ impl DockerContainerInitializer<FaucetService> for FaucetContainerInitializer {
     fn initialize_generated_files(&self, generated_files: HashMap<String, File>) -> Result<()> {
         for (file_key, mut fp) in generated_files {
             if file_key == KEYPAIR_FILE_KEY {
-                fp.write_all(self.keypair_json.as_bytes())
+                fp.write_all(self.keypair.to_json().as_bytes())
                     .context("An error occurred writing the faucet keypair JSON To file")?;
             } else {
                 return Err(anyhow!(
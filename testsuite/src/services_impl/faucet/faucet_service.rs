@@ -1,17 +1,25 @@
+use anyhow::{Context, Result, anyhow};
 use kurtosis_rust_lib::services::{service::Service, service_context::ServiceContext};
 
+use crate::sdk_impl::{hash::Hash, keypair::Keypair, pubkey::Pubkey, transaction::Transaction};
+
+use super::faucet_client::FaucetClient;
+
 pub (super) const FAUCET_PORT: u32 = 9900;
 
+// The availability check asks for an airdrop of this many lamports, which never gets submitted
+const AVAILABILITY_CHECK_AIRDROP_LAMPORTS: u64 = 1;
+
 pub struct FaucetService {
     service_context: ServiceContext,
-    keypair_json: String,
+    keypair: Keypair,
 }
 
 impl FaucetService {
-    pub fn new(service_context: ServiceContext, keypair_json: String) -> FaucetService {
+    pub fn new(service_context: ServiceContext, keypair: Keypair) -> FaucetService {
         return FaucetService{
             service_context,
-            keypair_json,
+            keypair,
         };
     }
 
@@ -23,14 +31,43 @@ impl FaucetService {
         return FAUCET_PORT;
     }
 
-    pub fn get_keypair_json(&self) -> String {
-        return self.keypair_json.clone();
+    pub fn get_keypair(&self) -> &Keypair {
+        return &self.keypair;
+    }
+
+    // Gets a transaction from the faucet that airdrops the lamports to the pubkey, signed by the faucet but not yet
+    // submitted; the blockhash should be a recent one from the cluster the transaction will be sent to
+    pub fn request_airdrop(&self, pubkey: &Pubkey, lamports: u64, blockhash: &Hash) -> Result<Transaction> {
+        let addr_str = format!("{}:{}", self.get_ip_address(), FAUCET_PORT);
+        let addr = addr_str.parse()
+            .context(format!("An error occurred parsing faucet address '{}'", addr_str))?;
+        let transaction = FaucetClient::new(addr).request_airdrop_transaction(pubkey, lamports, blockhash)
+            .context(format!("An error occurred requesting an airdrop of {} lamports to '{}' from the faucet", lamports, pubkey))?;
+        // A faucet started with the wrong keypair would hand out transactions that the cluster rejects
+        let payer = transaction.message.account_keys.first()
+            .context("The faucet's airdrop transaction doesn't have any accounts")?;
+        if *payer != self.keypair.pubkey() {
+            return Err(anyhow!(
+                "Expected the faucet's airdrop transaction to be paid for by the faucet keypair '{}', but it's paid for by '{}'",
+                self.keypair.pubkey(),
+                payer,
+            ));
+        }
+        return Ok(transaction);
     }
 }
 
 impl Service for FaucetService {
+    // The faucet is available once it answers an airdrop request with a transaction paid for by the faucet keypair
+    // The blockhash doesn't matter because the transaction never gets submitted
     fn is_available(&self) -> bool {
-        // Faucet operates on UDP - no guarantees of availability
-        return true;
+        let own_pubkey = self.keypair.pubkey();
+        match self.request_airdrop(&own_pubkey, AVAILABILITY_CHECK_AIRDROP_LAMPORTS, &Hash::default()) {
+            Ok(_) => return true,
+            Err(err) => {
+                debug!("The faucet isn't available yet: {:#}", err);
+                return false;
+            },
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,2 +1,3 @@
+pub mod faucet_client;
 pub mod faucet_container_initializer;
-pub mod faucet_service;
\ No newline at end of file
+pub mod faucet_service;
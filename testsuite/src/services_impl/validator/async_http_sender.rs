@@ -0,0 +1,85 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Client, header::CONTENT_TYPE};
+use serde_json::Value;
+use std::{sync::atomic::{AtomicU64, Ordering}, time::Duration};
+
+use super::{async_rpc_sender::AsyncRpcSender, http_sender::HttpSender, rpc_error::RpcError, rpc_request::RpcRequest};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Async counterpart to HttpSender, built on the non-blocking reqwest client
+pub struct AsyncHttpSender {
+    client: Client,
+    url: String,
+    next_request_id: AtomicU64,
+}
+
+impl AsyncHttpSender {
+    pub fn new(url: String) -> AsyncHttpSender {
+        return AsyncHttpSender::new_with_timeout(url, REQUEST_TIMEOUT);
+    }
+
+    pub fn new_with_timeout(url: String, timeout: Duration) -> AsyncHttpSender {
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("build async rpc client");
+
+        return AsyncHttpSender{
+            client,
+            url,
+            next_request_id: AtomicU64::new(1),
+        };
+    }
+
+    fn get_next_request_id(&self) -> u64 {
+        return self.next_request_id.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // POSTs the body to the node; retrying is left to the client's retry policy
+    async fn post(&self, body: String) -> Result<Value> {
+        let response = self.client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(RpcError::from_reqwest_error)?;
+        if !response.status().is_success() {
+            return Err(RpcError::HttpStatus(response.status()).into());
+        }
+
+        let resp_body = response.text().await
+            .map_err(RpcError::from_reqwest_error)?;
+        let json: Value = serde_json::from_str(&resp_body)
+            .map_err(|err| RpcError::Decode(format!("Response body '{}' isn't valid JSON: {}", resp_body, err)))?;
+        return Ok(json);
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncRpcSender for AsyncHttpSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> Result<Value> {
+        let request_json = request.build_request_json(self.get_next_request_id(), params);
+        let response = self.post(request_json.to_string()).await?;
+        return HttpSender::get_result(&response);
+    }
+
+    async fn send_batch(&self, requests: Vec<(RpcRequest, Value)>) -> Result<Vec<Result<Value>>> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut request_ids: Vec<u64> = Vec::new();
+        let mut batch_json: Vec<Value> = Vec::new();
+        for (request, params) in requests {
+            let request_id = self.get_next_request_id();
+            request_ids.push(request_id);
+            batch_json.push(request.build_request_json(request_id, params));
+        }
+
+        let response = self.post(Value::Array(batch_json).to_string()).await?;
+        return HttpSender::match_batch_responses(&request_ids, response);
+    }
+}
@@ -0,0 +1,146 @@
+use std::cell::Cell;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{Value, json};
+
+use super::{async_http_sender::AsyncHttpSender, async_rpc_sender::AsyncRpcSender, commitment_config::CommitmentConfig, retry_policy::{DEFAULT_RETRY_POLICY, RetryPolicy}, rpc_client::RpcClient, rpc_error::RpcError, rpc_request::RpcRequest};
+
+// Async counterpart to RpcClient, covering the queries that tests need to fire at many nodes at the same instant
+// Methods that take an optional commitment fall back to the client's default commitment when given None
+// Failed requests are retried according to the client's retry policy
+pub struct AsyncRpcClient {
+    sender: Box<dyn AsyncRpcSender>,
+    commitment_config: Cell<CommitmentConfig>,
+    retry_policy: Cell<RetryPolicy>,
+}
+
+impl AsyncRpcClient {
+    pub fn new(url: String, commitment_config: CommitmentConfig) -> AsyncRpcClient {
+        return AsyncRpcClient::new_sender(AsyncHttpSender::new(url), commitment_config);
+    }
+
+    pub fn new_sender<T: AsyncRpcSender + 'static>(sender: T, commitment_config: CommitmentConfig) -> AsyncRpcClient {
+        return AsyncRpcClient{
+            sender: Box::new(sender),
+            commitment_config: Cell::new(commitment_config),
+            retry_policy: Cell::new(DEFAULT_RETRY_POLICY),
+        };
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        return self.commitment_config.get();
+    }
+
+    pub fn set_commitment(&self, commitment_config: CommitmentConfig) {
+        self.commitment_config.set(commitment_config);
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        return self.retry_policy.get();
+    }
+
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        self.retry_policy.set(retry_policy);
+    }
+
+    pub async fn get_slot(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        return self.send(RpcRequest::GetSlot, json!([self.get_commitment(commitment)])).await
+            .context("An error occurred getting the slot");
+    }
+
+    // Gets the node's slot at each of the given commitment levels, in a single round trip
+    pub async fn get_slots(&self, commitments: &[CommitmentConfig]) -> Result<Vec<u64>> {
+        let requests = commitments.iter()
+            .map(|commitment| (RpcRequest::GetSlot, json!([commitment])))
+            .collect();
+        let mut result = Vec::new();
+        for (commitment, slot_or_err) in commitments.iter().zip(self.send_batch(requests).await?) {
+            let slot = RpcClient::deserialize(slot_or_err)
+                .context(format!("An error occurred getting the {} slot", commitment))?;
+            result.push(slot);
+        }
+        return Ok(result);
+    }
+
+    // Sends all the requests in a single round trip; each request gets its own result, in the same order as the requests
+    pub async fn send_batch(&self, requests: Vec<(RpcRequest, Value)>) -> Result<Vec<Result<Value>>> {
+        for (_, params) in &requests {
+            assert!(params.is_array() || params.is_null());
+        }
+        let num_requests = requests.len();
+        let results = self.retry_policy().run_async(|| self.sender.send_batch(requests.clone())).await
+            .context("An error occurred sending the batch request")?;
+        if results.len() != num_requests {
+            return Err(anyhow!(
+                "Sent a batch of {} requests but got {} results back",
+                num_requests,
+                results.len(),
+            ));
+        }
+        return Ok(results);
+    }
+
+    pub async fn send<T>(&self, request: RpcRequest, params: Value) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        assert!(params.is_array() || params.is_null());
+        let response = self.retry_policy().run_async(|| self.sender.send(request, params.clone())).await
+            .context("An error occurred sending the request")?;
+        let deserialized = serde_json::from_value(response.clone())
+            .map_err(|err| RpcError::Decode(format!("Couldn't deserialize result '{}': {}", response, err)))
+            .context("An error occurred deserializing the response string to a JSON object")?;
+        return Ok(deserialized);
+    }
+
+    fn get_commitment(&self, commitment: Option<CommitmentConfig>) -> CommitmentConfig {
+        return commitment.unwrap_or_else(|| self.commitment());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tokio::runtime::Runtime;
+
+    use crate::services_impl::validator::{commitment_config::CommitmentConfig, mock_rpc_server::MockRpcServer};
+
+    use super::AsyncRpcClient;
+
+    #[test]
+    fn test_concurrent_queries() {
+        let servers: Vec<MockRpcServer> = (0..3).map(|_| MockRpcServer::start(|method, params| {
+            assert_eq!("getSlot", method);
+            assert_eq!("finalized", params[0]["commitment"]);
+            return Ok(json!(1234));
+        })).collect();
+        let clients: Vec<AsyncRpcClient> = servers.iter()
+            .map(|server| AsyncRpcClient::new(server.get_url(), CommitmentConfig::finalized()))
+            .collect();
+
+        let runtime = Runtime::new().unwrap();
+        let slots = runtime.block_on(futures::future::join_all(
+            clients.iter().map(|client| client.get_slot(None))
+        ));
+        for slot in slots {
+            assert_eq!(1234, slot.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_get_slots_batch() {
+        let server = MockRpcServer::start(|_, params| {
+            return match params[0]["commitment"].as_str() {
+                Some("processed") => Ok(json!(12)),
+                Some("confirmed") => Ok(json!(10)),
+                _ => Ok(json!(5)),
+            };
+        });
+        let client = AsyncRpcClient::new(server.get_url(), CommitmentConfig::confirmed());
+        let runtime = Runtime::new().unwrap();
+        let commitments = [CommitmentConfig::processed(), CommitmentConfig::confirmed(), CommitmentConfig::finalized()];
+        let slots = runtime.block_on(client.get_slots(&commitments)).unwrap();
+        assert_eq!(vec![12, 10, 5], slots);
+        assert_eq!(1, server.get_received_requests().len());
+    }
+}
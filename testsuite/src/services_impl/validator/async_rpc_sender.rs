@@ -0,0 +1,13 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::rpc_request::RpcRequest;
+
+// Async counterpart to RpcSender, so that many nodes can be queried at the same instant rather than one after another
+#[async_trait(?Send)]
+pub trait AsyncRpcSender {
+    async fn send(&self, request: RpcRequest, params: serde_json::Value) -> Result<serde_json::Value>;
+
+    // Same semantics as RpcSender::send_batch
+    async fn send_batch(&self, requests: Vec<(RpcRequest, serde_json::Value)>) -> Result<Vec<Result<serde_json::Value>>>;
+}
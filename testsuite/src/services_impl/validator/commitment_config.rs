@@ -0,0 +1,70 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This entire file is copied from https://github.com/solana-labs/solana/blob/master/sdk/src/commitment_config.rs
+because solana-client provides Ledger support, which means it has a dependency
+on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana client here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentConfig {
+    pub commitment: CommitmentLevel,
+}
+
+impl CommitmentConfig {
+    pub fn processed() -> CommitmentConfig {
+        return CommitmentConfig{
+            commitment: CommitmentLevel::Processed,
+        };
+    }
+
+    pub fn confirmed() -> CommitmentConfig {
+        return CommitmentConfig{
+            commitment: CommitmentLevel::Confirmed,
+        };
+    }
+
+    pub fn finalized() -> CommitmentConfig {
+        return CommitmentConfig{
+            commitment: CommitmentLevel::Finalized,
+        };
+    }
+}
+
+impl fmt::Display for CommitmentConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.commitment);
+    }
+}
+
+// Ordered from least to most committed, so that e.g. 'Finalized > Confirmed'
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum CommitmentLevel {
+    // The node has processed the block, but it may still be skipped by the cluster
+    Processed,
+
+    // A supermajority of the cluster has voted on the block
+    Confirmed,
+
+    // The block has been rooted by a supermajority of the cluster
+    Finalized,
+}
+
+impl fmt::Display for CommitmentLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self {
+            CommitmentLevel::Processed => "processed",
+            CommitmentLevel::Confirmed => "confirmed",
+            CommitmentLevel::Finalized => "finalized",
+        };
+        return write!(f, "{}", level);
+    }
+}
@@ -0,0 +1,286 @@
+use std::{collections::HashMap, fmt, thread::sleep, time::{Duration, Instant}};
+
+use anyhow::{Context, Result};
+
+use crate::sdk_impl::{signature::Signature, transaction::Transaction};
+
+use super::{commitment_config::CommitmentConfig, latency_histogram::LatencyHistogram, rpc_response::TransactionStatus, validator_service::ValidatorService};
+
+const TIME_BETWEEN_STATUS_POLLS: Duration = Duration::from_millis(500);
+
+// The commitment levels a transaction passes through after it's sent, in order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfirmationStage {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+const ALL_STAGES: [ConfirmationStage; 3] = [ConfirmationStage::Processed, ConfirmationStage::Confirmed, ConfirmationStage::Finalized];
+
+impl ConfirmationStage {
+    fn get_commitment(&self) -> CommitmentConfig {
+        return match self {
+            ConfirmationStage::Processed => CommitmentConfig::processed(),
+            ConfirmationStage::Confirmed => CommitmentConfig::confirmed(),
+            ConfirmationStage::Finalized => CommitmentConfig::finalized(),
+        };
+    }
+}
+
+impl fmt::Display for ConfirmationStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.get_commitment());
+    }
+}
+
+struct PendingTransaction {
+    sent_time: Instant,
+    // How many of ALL_STAGES the transaction has reached so far
+    num_stages_reached: usize,
+}
+
+// Follows sent transactions through every commitment level, recording how long each took to reach each level after
+// being sent
+// Latencies are only as precise as the polling, since a transaction is seen to reach a level at the first poll after it
+// actually did
+pub struct ConfirmationTracker {
+    pending: HashMap<Signature, PendingTransaction>,
+    latencies: HashMap<ConfirmationStage, LatencyHistogram>,
+    first_sent_time: Option<Instant>,
+    // When the most recent transaction to reach each stage was seen to reach it
+    last_reached_times: HashMap<ConfirmationStage, Instant>,
+    num_sent: usize,
+    num_finalized: usize,
+    // Transactions that landed with an error
+    num_failed: usize,
+    // Transactions that hadn't been finalized when the tracker stopped waiting on them
+    num_expired: usize,
+    // Polls that couldn't get the statuses from the node, e.g. because it was overloaded
+    num_failed_polls: usize,
+}
+
+impl ConfirmationTracker {
+    pub fn new() -> ConfirmationTracker {
+        let latencies = ALL_STAGES.iter()
+            .map(|stage| (*stage, LatencyHistogram::new()))
+            .collect();
+        return ConfirmationTracker{
+            pending: HashMap::new(),
+            latencies,
+            first_sent_time: None,
+            last_reached_times: HashMap::new(),
+            num_sent: 0,
+            num_finalized: 0,
+            num_failed: 0,
+            num_expired: 0,
+            num_failed_polls: 0,
+        };
+    }
+
+    // Sends the transaction through the node and starts tracking it
+    pub fn send(&mut self, node: &ValidatorService, transaction: &Transaction) -> Result<Signature> {
+        let sent_time = Instant::now();
+        let signature = node.send_transaction(transaction)?;
+        self.track(signature, sent_time);
+        return Ok(signature);
+    }
+
+    // Starts tracking a transaction that was sent some other way
+    pub fn track(&mut self, signature: Signature, sent_time: Instant) {
+        self.pending.insert(signature, PendingTransaction{
+            sent_time,
+            num_stages_reached: 0,
+        });
+        if self.first_sent_time.map(|first_sent_time| sent_time < first_sent_time).unwrap_or(true) {
+            self.first_sent_time = Some(sent_time);
+        }
+        self.num_sent += 1;
+    }
+
+    pub fn get_num_sent(&self) -> usize {
+        return self.num_sent;
+    }
+
+    pub fn get_num_finalized(&self) -> usize {
+        return self.num_finalized;
+    }
+
+    pub fn get_num_failed(&self) -> usize {
+        return self.num_failed;
+    }
+
+    pub fn get_num_expired(&self) -> usize {
+        return self.num_expired;
+    }
+
+    // Returns: how long it took from the first transaction being sent to the last one reaching the given stage, or None
+    // if no transaction has reached it
+    pub fn get_time_to_last(&self, stage: ConfirmationStage) -> Option<Duration> {
+        let first_sent_time = self.first_sent_time?;
+        let last_reached_time = self.last_reached_times.get(&stage)?;
+        return Some(last_reached_time.duration_since(first_sent_time));
+    }
+
+    // Returns: the latencies from sending to reaching the given stage, of every transaction that reached it
+    pub fn get_latencies(&self, stage: ConfirmationStage) -> &LatencyHistogram {
+        return self.latencies.get(&stage)
+            .expect("Every stage has a histogram");
+    }
+
+    // Queries the statuses of all the pending transactions from the node, in as few batches as it'll allow
+    pub fn poll(&mut self, node: &ValidatorService) -> Result<()> {
+        let signatures: Vec<Signature> = self.pending.keys().copied().collect();
+        let statuses = node.get_signature_statuses(&signatures)
+            .context(format!("An error occurred getting the statuses of {} pending transactions", signatures.len()))?;
+        let now = Instant::now();
+        for (signature, status) in signatures.iter().zip(statuses) {
+            if let Some(status) = status {
+                self.record_status(signature, &status, now);
+            }
+        }
+        return Ok(());
+    }
+
+    // Like poll, except that a failed poll is counted and logged rather than returned, leaving the transactions pending
+    // for the next poll
+    pub fn poll_or_warn(&mut self, node: &ValidatorService) {
+        if let Err(err) = self.poll(node) {
+            warn!("Couldn't poll the statuses of the pending transactions: {:#}", err);
+            self.num_failed_polls += 1;
+        }
+    }
+
+    // Polls until every pending transaction is finalized or failed, or until the timeout; the transactions that are
+    // still pending after the timeout (including any whose statuses couldn't be polled) are counted as expired and no
+    // longer tracked
+    pub fn wait_for_all(&mut self, node: &ValidatorService, timeout: Duration) {
+        let start_time = Instant::now();
+        loop {
+            self.poll_or_warn(node);
+            if self.pending.is_empty() {
+                return;
+            }
+            if start_time.elapsed() >= timeout {
+                warn!("{} transactions weren't finalized within {:?}", self.pending.len(), timeout);
+                self.num_expired += self.pending.len();
+                self.pending.clear();
+                return;
+            }
+            sleep(TIME_BETWEEN_STATUS_POLLS);
+        }
+    }
+
+    fn record_status(&mut self, signature: &Signature, status: &TransactionStatus, now: Instant) {
+        let pending = match self.pending.get_mut(signature) {
+            Some(pending) => pending,
+            None => return,
+        };
+        if let Some(err) = &status.err {
+            debug!("Transaction '{}' failed in slot {} with error: {}", signature, status.slot, err);
+            self.pending.remove(signature);
+            self.num_failed += 1;
+            return;
+        }
+        // A transaction can skip past stages between polls, in which case it's seen to reach all of them at once
+        let latency = now.duration_since(pending.sent_time);
+        while pending.num_stages_reached < ALL_STAGES.len() {
+            let stage = ALL_STAGES[pending.num_stages_reached];
+            if !status.satisfies_commitment(stage.get_commitment()) {
+                break;
+            }
+            self.latencies.get_mut(&stage)
+                .expect("Every stage has a histogram")
+                .record(latency);
+            self.last_reached_times.insert(stage, now);
+            pending.num_stages_reached += 1;
+        }
+        if pending.num_stages_reached == ALL_STAGES.len() {
+            self.pending.remove(signature);
+            self.num_finalized += 1;
+        }
+    }
+}
+
+impl fmt::Display for ConfirmationTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} sent, {} finalized, {} failed, {} expired, {} pending, {} failed polls",
+            self.num_sent,
+            self.num_finalized,
+            self.num_failed,
+            self.num_expired,
+            self.pending.len(),
+            self.num_failed_polls,
+        )?;
+        for stage in ALL_STAGES.iter() {
+            write!(f, "; {} latency: {}", stage, self.latencies[stage])?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use serde_json::json;
+
+    use crate::{sdk_impl::signature::Signature, services_impl::validator::rpc_response::TransactionStatus};
+
+    use super::{ConfirmationStage, ConfirmationTracker};
+
+    fn parse_status(status: serde_json::Value) -> TransactionStatus {
+        return serde_json::from_value(status).unwrap();
+    }
+
+    #[test]
+    fn test_record_status_follows_stages() {
+        let mut tracker = ConfirmationTracker::new();
+        let sent_time = Instant::now();
+        let signature = Signature::new([1; 64]);
+        tracker.track(signature, sent_time);
+
+        let processed = parse_status(json!({ "slot": 5, "confirmations": 0, "err": null, "confirmationStatus": "processed" }));
+        tracker.record_status(&signature, &processed, sent_time + Duration::from_millis(400));
+        let confirmed = parse_status(json!({ "slot": 5, "confirmations": 2, "err": null, "confirmationStatus": "confirmed" }));
+        tracker.record_status(&signature, &confirmed, sent_time + Duration::from_millis(1200));
+        assert_eq!(1, tracker.pending.len());
+        let finalized = parse_status(json!({ "slot": 5, "confirmations": null, "err": null, "confirmationStatus": "finalized" }));
+        tracker.record_status(&signature, &finalized, sent_time + Duration::from_millis(13000));
+
+        assert_eq!(0, tracker.pending.len());
+        assert_eq!(1, tracker.get_num_finalized());
+        assert_eq!(Some(Duration::from_millis(400)), tracker.get_latencies(ConfirmationStage::Processed).get_p50());
+        assert_eq!(Some(Duration::from_millis(1200)), tracker.get_latencies(ConfirmationStage::Confirmed).get_p50());
+        assert_eq!(Some(Duration::from_millis(13000)), tracker.get_latencies(ConfirmationStage::Finalized).get_p50());
+        assert_eq!(Some(Duration::from_millis(1200)), tracker.get_time_to_last(ConfirmationStage::Confirmed));
+    }
+
+    #[test]
+    fn test_record_status_skipped_stages_and_failures() {
+        let mut tracker = ConfirmationTracker::new();
+        let sent_time = Instant::now();
+        let finalized_signature = Signature::new([1; 64]);
+        let failed_signature = Signature::new([2; 64]);
+        tracker.track(finalized_signature, sent_time);
+        tracker.track(failed_signature, sent_time);
+
+        // First seen already finalized, so it reached every stage at that poll
+        let finalized = parse_status(json!({ "slot": 5, "confirmations": null, "err": null, "confirmationStatus": "finalized" }));
+        tracker.record_status(&finalized_signature, &finalized, sent_time + Duration::from_secs(15));
+        let failed = parse_status(json!({ "slot": 6, "confirmations": 0, "err": { "InstructionError": [0, "Custom"] }, "confirmationStatus": "processed" }));
+        tracker.record_status(&failed_signature, &failed, sent_time + Duration::from_secs(1));
+
+        assert_eq!(2, tracker.get_num_sent());
+        assert_eq!(1, tracker.get_num_finalized());
+        assert_eq!(1, tracker.get_num_failed());
+        assert_eq!(0, tracker.pending.len());
+        for stage in &[ConfirmationStage::Processed, ConfirmationStage::Confirmed, ConfirmationStage::Finalized] {
+            let latencies = tracker.get_latencies(*stage);
+            assert_eq!(1, latencies.get_count());
+            assert_eq!(Some(Duration::from_secs(15)), latencies.get_max());
+        }
+    }
+}
@@ -1,8 +1,7 @@
-use anyhow::{anyhow, Result};
-use log::*;
-use reqwest::{StatusCode, blocking::Client, header::CONTENT_TYPE};
+use anyhow::{Context, Result};
+use reqwest::{blocking::Client, header::CONTENT_TYPE};
 use serde_json::Value;
-use std::{thread::sleep, time::Duration};
+use std::{collections::HashMap, sync::atomic::{AtomicU64, Ordering}, time::Duration};
 
 /*
 NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
@@ -14,13 +13,15 @@ Therefore, we reimplement parts of the Solana client here as a hackaround
 NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
 */
 
-use super::{rpc_request::RpcRequest, rpc_sender::RpcSender};
+use super::{rpc_error::RpcError, rpc_request::RpcRequest, rpc_sender::RpcSender};
 
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct HttpSender {
     client: Client,
     url: String,
+    // Every request gets a unique ID so that the responses to a batch can be matched back up with their requests
+    next_request_id: AtomicU64,
 }
 
 impl HttpSender {
@@ -34,58 +35,99 @@ impl HttpSender {
             .build()
             .expect("build rpc client");
 
-        Self { client, url }
+        Self {
+            client,
+            url,
+            next_request_id: AtomicU64::new(1),
+        }
+    }
+
+    fn get_next_request_id(&self) -> u64 {
+        return self.next_request_id.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // POSTs the body to the node; retrying is left to the client's retry policy
+    fn post(&self, body: String) -> Result<Value> {
+        let response = self.client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .map_err(RpcError::from_reqwest_error)?;
+        if !response.status().is_success() {
+            return Err(RpcError::HttpStatus(response.status()).into());
+        }
+
+        let resp_body = response.text()
+            .map_err(RpcError::from_reqwest_error)?;
+        let json: Value = serde_json::from_str(&resp_body)
+            .map_err(|err| RpcError::Decode(format!("Response body '{}' isn't valid JSON: {}", resp_body, err)))?;
+        return Ok(json);
+    }
+
+    pub (super) fn get_result(response: &Value) -> Result<Value> {
+        if response["error"].is_object() {
+            return Err(RpcError::from_error_object(&response["error"]).into());
+        }
+        return Ok(response["result"].clone());
+    }
+
+    // Pairs up the entries of a batch response with the IDs of the requests in the batch, returning the results in
+    // the same order as the request IDs
+    pub (super) fn match_batch_responses(request_ids: &[u64], response: Value) -> Result<Vec<Result<Value>>> {
+        // The node is free to answer a batch in any order, so we key the responses by their request ID
+        let response_entries = match response {
+            Value::Array(entries) => entries,
+            other => {
+                // A batch that the node couldn't parse at all gets a single error object back, rather than an array
+                HttpSender::get_result(&other)
+                    .context("The node rejected the entire batch")?;
+                return Err(RpcError::Decode(format!(
+                    "Expected a JSON array in response to a batch request but got '{}'",
+                    other,
+                )).into());
+            }
+        };
+        let mut responses_by_id: HashMap<u64, Value> = HashMap::new();
+        for entry in response_entries {
+            let response_id = entry["id"].as_u64()
+                .ok_or_else(|| RpcError::Decode(format!("Batch response entry '{}' doesn't have a numeric ID", entry)))?;
+            responses_by_id.insert(response_id, entry);
+        }
+
+        let mut results: Vec<Result<Value>> = Vec::new();
+        for request_id in request_ids {
+            let result = match responses_by_id.get(request_id) {
+                Some(entry) => HttpSender::get_result(entry),
+                None => Err(RpcError::Decode(format!("The batch response contained no entry for request ID {}", request_id)).into()),
+            };
+            results.push(result);
+        }
+        return Ok(results);
     }
 }
 
 impl RpcSender for HttpSender {
     fn send(&self, request: RpcRequest, params: Value) -> Result<Value> {
-        // Concurrent requests are not supported so reuse the same request id for all requests
-        let request_id = 1;
-
-        let request_json = request.build_request_json(request_id, params);
-
-        let mut too_many_requests_retries = 5;
-        loop {
-            let resp_or_err = self.client
-                .post(&self.url)
-                .header(CONTENT_TYPE, "application/json")
-                .body(request_json.to_string())
-                .send();
-            match resp_or_err
-            {
-                Ok(response) => {
-                    if !response.status().is_success() {
-                        if response.status() == StatusCode::TOO_MANY_REQUESTS
-                            && too_many_requests_retries > 0
-                        {
-                            too_many_requests_retries -= 1;
-                            debug!(
-                                "Server responded with {:?}, {} retries left",
-                                response, too_many_requests_retries
-                            );
-
-                            // Sleep for 500ms to give the server a break
-                            sleep(Duration::from_millis(500));
-                            continue;
-                        }
-                        return Err(response.error_for_status().unwrap_err().into());
-                    }
-
-                    let resp_body = response.text()?;
-                    let json: Value = serde_json::from_str(&resp_body)?;
-                    if json["error"].is_object() {
-                        return Err(anyhow!(
-                            "An error occurred making the JSON RPC request: {}",
-                            json["error"].clone(),
-                        ));
-                    }
-                    return Ok(json["result"].clone());
-                }
-                Err(err) => {
-                    return Err(err.into());
-                }
-            }
+        let request_json = request.build_request_json(self.get_next_request_id(), params);
+        let response = self.post(request_json.to_string())?;
+        return HttpSender::get_result(&response);
+    }
+
+    fn send_batch(&self, requests: Vec<(RpcRequest, Value)>) -> Result<Vec<Result<Value>>> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
         }
+
+        let mut request_ids: Vec<u64> = Vec::new();
+        let mut batch_json: Vec<Value> = Vec::new();
+        for (request, params) in requests {
+            let request_id = self.get_next_request_id();
+            request_ids.push(request_id);
+            batch_json.push(request.build_request_json(request_id, params));
+        }
+
+        let response = self.post(Value::Array(batch_json).to_string())?;
+        return HttpSender::match_batch_responses(&request_ids, response);
     }
 }
@@ -0,0 +1,92 @@
+use std::{fmt, time::Duration};
+
+// Keeps every latency it's given, so that percentiles are exact; the tests never record more than a few thousand
+#[derive(Clone, Debug, Default)]
+pub struct LatencyHistogram {
+    // Kept sorted as they're recorded, so that reading a percentile doesn't need to sort
+    samples: Vec<Duration>,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> LatencyHistogram {
+        return LatencyHistogram::default();
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        let index = self.samples.partition_point(|sample| *sample <= latency);
+        self.samples.insert(index, latency);
+    }
+
+    pub fn get_count(&self) -> usize {
+        return self.samples.len();
+    }
+
+    // The nearest-rank percentile, i.e. the smallest latency that's at least as large as the given percent of the
+    // latencies; None if nothing has been recorded
+    pub fn get_percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.samples.len() as f64).ceil() as usize;
+        return Some(self.samples[rank.max(1) - 1]);
+    }
+
+    pub fn get_p50(&self) -> Option<Duration> {
+        return self.get_percentile(50.0);
+    }
+
+    pub fn get_p90(&self) -> Option<Duration> {
+        return self.get_percentile(90.0);
+    }
+
+    pub fn get_p99(&self) -> Option<Duration> {
+        return self.get_percentile(99.0);
+    }
+
+    pub fn get_max(&self) -> Option<Duration> {
+        return self.samples.last().copied();
+    }
+}
+
+impl fmt::Display for LatencyHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "count={} p50={:?} p90={:?} p99={:?} max={:?}",
+            self.get_count(),
+            self.get_p50(),
+            self.get_p90(),
+            self.get_p99(),
+            self.get_max(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::LatencyHistogram;
+
+    #[test]
+    fn test_percentiles() {
+        let mut histogram = LatencyHistogram::new();
+        assert_eq!(None, histogram.get_p50());
+        assert_eq!(None, histogram.get_max());
+
+        // Recorded out of order, to check that they get sorted
+        for millis in (1..=100).rev() {
+            histogram.record(Duration::from_millis(millis));
+        }
+        assert_eq!(100, histogram.get_count());
+        assert_eq!(Some(Duration::from_millis(50)), histogram.get_p50());
+        assert_eq!(Some(Duration::from_millis(90)), histogram.get_p90());
+        assert_eq!(Some(Duration::from_millis(99)), histogram.get_p99());
+        assert_eq!(Some(Duration::from_millis(100)), histogram.get_max());
+        assert_eq!(Some(Duration::from_millis(1)), histogram.get_percentile(0.0));
+
+        histogram.record(Duration::from_millis(1000));
+        assert_eq!(Some(Duration::from_millis(100)), histogram.get_p99());
+        assert_eq!(Some(Duration::from_millis(1000)), histogram.get_percentile(100.0));
+    }
+}
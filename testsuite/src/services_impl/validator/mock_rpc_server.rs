@@ -0,0 +1,126 @@
+use std::{io::{BufRead, BufReader, Read, Write}, net::{SocketAddr, TcpListener, TcpStream}, sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}}, thread};
+
+use serde_json::{Value, json};
+
+// Minimal single-threaded HTTP server that speaks just enough JSON-RPC to let us unit-test the RPC client layer
+// without a running validator
+pub struct MockRpcServer {
+    url: String,
+    received_requests: Arc<Mutex<Vec<Value>>>,
+}
+
+impl MockRpcServer {
+    // The handler receives the method name and params of each request, and returns either the 'result' or the
+    // 'error' object to send back
+    pub fn start<F>(handler: F) -> MockRpcServer
+    where
+        F: Fn(&str, &Value) -> Result<Value, Value> + Send + 'static,
+    {
+        return MockRpcServer::start_flaky("127.0.0.1:0".parse().unwrap(), 0, handler);
+    }
+
+    // Like start, but listens on the given address and answers the first num_failing_requests requests with an
+    // HTTP 503, to stand in for a node that's flaky or still coming up
+    pub fn start_flaky<F>(addr: SocketAddr, num_failing_requests: usize, handler: F) -> MockRpcServer
+    where
+        F: Fn(&str, &Value) -> Result<Value, Value> + Send + 'static,
+    {
+        let listener = TcpListener::bind(addr).expect("bind mock RPC server");
+        let url = format!("http://{}", listener.local_addr().expect("get mock RPC server address"));
+        let received_requests = Arc::new(Mutex::new(Vec::new()));
+        let thread_received_requests = received_requests.clone();
+        let num_failures_left = AtomicUsize::new(num_failing_requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let should_fail = num_failures_left.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| left.checked_sub(1)).is_ok();
+                        MockRpcServer::handle_connection(stream, should_fail, &handler, &thread_received_requests);
+                    },
+                    Err(_) => return,
+                }
+            }
+        });
+        return MockRpcServer{
+            url,
+            received_requests,
+        };
+    }
+
+    pub fn get_url(&self) -> String {
+        return self.url.clone();
+    }
+
+    pub fn get_received_requests(&self) -> Vec<Value> {
+        return self.received_requests.lock().unwrap().clone();
+    }
+
+    fn handle_connection<F>(stream: TcpStream, should_fail: bool, handler: &F, received_requests: &Arc<Mutex<Vec<Value>>>)
+    where
+        F: Fn(&str, &Value) -> Result<Value, Value>,
+    {
+        let mut reader = BufReader::new(stream.try_clone().expect("clone mock RPC server stream"));
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let lowercase_line = line.to_ascii_lowercase();
+            if let Some(value) = lowercase_line.strip_prefix("content-length:") {
+                content_length = value.trim().parse().expect("parse content length");
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).expect("read mock RPC request body");
+        if should_fail {
+            MockRpcServer::write_http_response(stream, "503 Service Unavailable", "");
+            return;
+        }
+        let request: Value = serde_json::from_slice(&body).expect("parse mock RPC request body");
+        received_requests.lock().unwrap().push(request.clone());
+
+        let response = match &request {
+            // Batch entries are answered in reverse order, to make sure clients match responses up by ID rather than position
+            Value::Array(entries) => Value::Array(
+                entries.iter().rev().map(|entry| MockRpcServer::build_response(entry, handler)).collect()
+            ),
+            _ => MockRpcServer::build_response(&request, handler),
+        };
+        MockRpcServer::write_http_response(stream, "200 OK", &response.to_string());
+    }
+
+    fn build_response<F>(request: &Value, handler: &F) -> Value
+    where
+        F: Fn(&str, &Value) -> Result<Value, Value>,
+    {
+        let method = request["method"].as_str().unwrap_or_default();
+        return match handler(method, &request["params"]) {
+            Ok(result) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": result,
+            }),
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": error,
+            }),
+        };
+    }
+
+    fn write_http_response(mut stream: TcpStream, status: &str, body: &str) {
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body,
+        );
+        // The client may have given up on the request already, in which case there's nobody to report the error to
+        let _ = stream.write_all(response.as_bytes());
+    }
+}
@@ -1,5 +1,19 @@
+pub mod async_http_sender;
+pub mod async_rpc_client;
+pub mod async_rpc_sender;
+pub mod commitment_config;
+pub mod confirmation_tracker;
 pub mod http_sender;
+pub mod latency_histogram;
+#[cfg(test)]
+pub mod mock_rpc_server;
+pub mod rpc_client;
+pub mod pubsub_client;
+pub mod readiness;
+pub mod retry_policy;
+pub mod rpc_error;
 pub mod rpc_request;
+pub mod rpc_response;
 pub mod rpc_sender;
 pub mod validator_container_initializer;
 pub mod validator_service;
\ No newline at end of file
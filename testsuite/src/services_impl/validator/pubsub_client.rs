@@ -0,0 +1,363 @@
+use std::{pin::Pin, task::{Context as TaskContext, Poll}, time::Instant};
+
+use anyhow::{Context, Result};
+use futures::{SinkExt, Stream, StreamExt, channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded}, stream::{SplitSink, SplitStream}};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use tokio::{net::TcpStream, task::JoinHandle};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+
+use super::{commitment_config::CommitmentConfig, rpc_error::RpcError, rpc_response::{Response, RpcLogsResponse, RpcSignatureResult, SlotInfo, UiAccount}};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down, async port of https://github.com/solana-labs/solana/blob/master/client/src/pubsub_client.rs
+because solana-client provides Ledger support, which means it has a dependency
+on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana client here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+const SUBSCRIBE_REQUEST_ID: u64 = 1;
+const UNSUBSCRIBE_REQUEST_ID: u64 = 2;
+
+const ACCOUNT_ENCODING: &str = "base64";
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[allow(dead_code)]
+pub enum RpcTransactionLogsFilter {
+    // All transactions except simple vote transactions
+    All,
+    AllWithVotes,
+    // Only transactions that mention one of the given base58-encoded pubkeys
+    Mentions(Vec<String>),
+}
+
+// A notification, stamped with the instant it arrived so that tests can time exactly when the cluster did something
+pub struct PubsubNotification<T> {
+    pub received_at: Instant,
+    pub value: T,
+}
+
+// The three names that each kind of subscription goes by on the wire
+struct SubscriptionMethods {
+    subscribe: &'static str,
+    notification: &'static str,
+    unsubscribe: &'static str,
+}
+
+const ACCOUNT_METHODS: SubscriptionMethods = SubscriptionMethods{
+    subscribe: "accountSubscribe",
+    notification: "accountNotification",
+    unsubscribe: "accountUnsubscribe",
+};
+const LOGS_METHODS: SubscriptionMethods = SubscriptionMethods{
+    subscribe: "logsSubscribe",
+    notification: "logsNotification",
+    unsubscribe: "logsUnsubscribe",
+};
+const ROOT_METHODS: SubscriptionMethods = SubscriptionMethods{
+    subscribe: "rootSubscribe",
+    notification: "rootNotification",
+    unsubscribe: "rootUnsubscribe",
+};
+const SIGNATURE_METHODS: SubscriptionMethods = SubscriptionMethods{
+    subscribe: "signatureSubscribe",
+    notification: "signatureNotification",
+    unsubscribe: "signatureUnsubscribe",
+};
+const SLOT_METHODS: SubscriptionMethods = SubscriptionMethods{
+    subscribe: "slotSubscribe",
+    notification: "slotNotification",
+    unsubscribe: "slotUnsubscribe",
+};
+
+// Client for a validator's websocket PubSub API
+// Every subscription gets its own connection, so subscriptions can be created and dropped independently
+pub struct PubsubClient {
+    url: String,
+}
+
+impl PubsubClient {
+    pub fn new(url: String) -> PubsubClient {
+        return PubsubClient{
+            url,
+        };
+    }
+
+    #[allow(dead_code)]
+    pub async fn account_subscribe(&self, pubkey: &str, commitment: CommitmentConfig) -> Result<PubsubSubscription<Response<UiAccount>>> {
+        let params = json!([pubkey, { "encoding": ACCOUNT_ENCODING, "commitment": commitment.commitment }]);
+        return self.subscribe(&ACCOUNT_METHODS, params).await
+            .context(format!("An error occurred subscribing to account '{}'", pubkey));
+    }
+
+    #[allow(dead_code)]
+    pub async fn logs_subscribe(&self, filter: RpcTransactionLogsFilter, commitment: CommitmentConfig) -> Result<PubsubSubscription<Response<RpcLogsResponse>>> {
+        let filter_json = match filter {
+            RpcTransactionLogsFilter::All => json!("all"),
+            RpcTransactionLogsFilter::AllWithVotes => json!("allWithVotes"),
+            RpcTransactionLogsFilter::Mentions(pubkeys) => json!({ "mentions": pubkeys }),
+        };
+        let params = json!([filter_json, { "commitment": commitment.commitment }]);
+        return self.subscribe(&LOGS_METHODS, params).await
+            .context("An error occurred subscribing to transaction logs");
+    }
+
+    // Notifies with each new root slot
+    pub async fn root_subscribe(&self) -> Result<PubsubSubscription<u64>> {
+        return self.subscribe(&ROOT_METHODS, Value::Null).await
+            .context("An error occurred subscribing to roots");
+    }
+
+    // Notifies once, when the transaction with the given signature reaches the given commitment
+    #[allow(dead_code)]
+    pub async fn signature_subscribe(&self, signature: &str, commitment: CommitmentConfig) -> Result<PubsubSubscription<Response<RpcSignatureResult>>> {
+        let params = json!([signature, { "commitment": commitment.commitment }]);
+        return self.subscribe(&SIGNATURE_METHODS, params).await
+            .context(format!("An error occurred subscribing to signature '{}'", signature));
+    }
+
+    // Notifies with each slot that the node processes
+    #[allow(dead_code)]
+    pub async fn slot_subscribe(&self) -> Result<PubsubSubscription<SlotInfo>> {
+        return self.subscribe(&SLOT_METHODS, Value::Null).await
+            .context("An error occurred subscribing to slots");
+    }
+
+    async fn subscribe<T>(&self, methods: &'static SubscriptionMethods, params: Value) -> Result<PubsubSubscription<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let (mut socket, _) = connect_async(self.url.as_str()).await
+            .map_err(|err| RpcError::Transport(err.to_string()))
+            .context(format!("An error occurred connecting to PubSub endpoint '{}'", self.url))?;
+
+        let mut request = json!({
+            "jsonrpc": "2.0",
+            "id": SUBSCRIBE_REQUEST_ID,
+            "method": methods.subscribe,
+        });
+        if !params.is_null() {
+            request["params"] = params;
+        }
+        socket.send(Message::Text(request.to_string())).await
+            .map_err(|err| RpcError::Transport(err.to_string()))
+            .context(format!("An error occurred sending the '{}' request", methods.subscribe))?;
+        let subscription_id = PubsubClient::read_subscription_id(&mut socket).await
+            .context(format!("An error occurred reading the response to the '{}' request", methods.subscribe))?;
+
+        let (writer, reader) = socket.split();
+        let (sender, notifications) = unbounded();
+        let reader_task = tokio::spawn(PubsubClient::forward_notifications(reader, methods.notification, subscription_id, sender));
+        return Ok(PubsubSubscription{
+            subscription_id,
+            unsubscribe_method: methods.unsubscribe,
+            notifications,
+            reader_task,
+            writer,
+        });
+    }
+
+    async fn read_subscription_id(socket: &mut WebSocket) -> Result<u64> {
+        loop {
+            let message = match socket.next().await {
+                Some(message_or_err) => message_or_err.map_err(|err| RpcError::Transport(err.to_string()))?,
+                None => return Err(RpcError::Transport("The connection closed before the subscription was confirmed".to_owned()).into()),
+            };
+            let text = match message {
+                Message::Text(text) => text,
+                _ => continue,
+            };
+            let json: Value = serde_json::from_str(&text)
+                .map_err(|err| RpcError::Decode(format!("Message '{}' isn't valid JSON: {}", text, err)))?;
+            if json["id"].as_u64() != Some(SUBSCRIBE_REQUEST_ID) {
+                continue;
+            }
+            if json["error"].is_object() {
+                return Err(RpcError::from_error_object(&json["error"]).into());
+            }
+            let subscription_id = json["result"].as_u64()
+                .ok_or_else(|| RpcError::Decode(format!("Expected a numeric subscription ID in '{}'", text)))?;
+            return Ok(subscription_id);
+        }
+    }
+
+    // Runs in the background for the lifetime of the subscription, so that notifications are timestamped when they
+    // arrive rather than when somebody gets around to reading them
+    async fn forward_notifications<T>(
+        mut reader: SplitStream<WebSocket>,
+        notification_method: &'static str,
+        subscription_id: u64,
+        sender: UnboundedSender<PubsubNotification<T>>
+    )
+    where
+        T: DeserializeOwned,
+    {
+        while let Some(Ok(message)) = reader.next().await {
+            let received_at = Instant::now();
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let json: Value = match serde_json::from_str(&text) {
+                Ok(json) => json,
+                Err(err) => {
+                    debug!("Ignoring PubSub message '{}' that isn't valid JSON: {}", text, err);
+                    continue;
+                }
+            };
+            if json["method"] != notification_method || json["params"]["subscription"].as_u64() != Some(subscription_id) {
+                continue;
+            }
+            let value: T = match serde_json::from_value(json["params"]["result"].clone()) {
+                Ok(value) => value,
+                Err(err) => {
+                    debug!("Ignoring '{}' notification '{}' that couldn't be deserialized: {}", notification_method, text, err);
+                    continue;
+                }
+            };
+            if sender.unbounded_send(PubsubNotification{ received_at, value }).is_err() {
+                // Nobody's listening anymore
+                break;
+            }
+        }
+    }
+}
+
+// A live subscription, which can be consumed as a Stream of notifications
+// The stream ends if the node closes the connection; dropping the subscription closes the connection
+pub struct PubsubSubscription<T> {
+    subscription_id: u64,
+    unsubscribe_method: &'static str,
+    notifications: UnboundedReceiver<PubsubNotification<T>>,
+    reader_task: JoinHandle<()>,
+    writer: SplitSink<WebSocket, Message>,
+}
+
+impl<T> PubsubSubscription<T> {
+    // Takes all the notifications that have arrived so far, without waiting for more
+    pub fn drain(&mut self) -> Vec<PubsubNotification<T>> {
+        let mut result = Vec::new();
+        while let Ok(Some(notification)) = self.notifications.try_next() {
+            result.push(notification);
+        }
+        return result;
+    }
+
+    // Tells the node to stop sending notifications and closes the connection
+    #[allow(dead_code)]
+    pub async fn unsubscribe(mut self) -> Result<()> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": UNSUBSCRIBE_REQUEST_ID,
+            "method": self.unsubscribe_method,
+            "params": [self.subscription_id],
+        });
+        self.writer.send(Message::Text(request.to_string())).await
+            .map_err(|err| RpcError::Transport(err.to_string()))
+            .context(format!("An error occurred sending the '{}' request", self.unsubscribe_method))?;
+        self.writer.close().await
+            .map_err(|err| RpcError::Transport(err.to_string()))
+            .context("An error occurred closing the PubSub connection")?;
+        return Ok(());
+    }
+}
+
+impl<T> Stream for PubsubSubscription<T> {
+    type Item = PubsubNotification<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        return self.notifications.poll_next_unpin(cx);
+    }
+}
+
+impl<T> Drop for PubsubSubscription<T> {
+    fn drop(&mut self) {
+        self.reader_task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{SinkExt, StreamExt};
+    use serde_json::{Value, json};
+    use tokio::{net::TcpListener, runtime::Runtime};
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    use crate::services_impl::validator::rpc_error::RpcError;
+
+    use super::PubsubClient;
+
+    // Accepts a single connection, answers the subscribe request with the given response, then sends the given messages
+    async fn start_mock_pubsub_server(subscribe_response: Value, messages: Vec<Value>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            let request = socket.next().await.unwrap().unwrap();
+            let request: Value = serde_json::from_str(request.to_text().unwrap()).unwrap();
+            let mut response = subscribe_response;
+            response["id"] = request["id"].clone();
+            response["jsonrpc"] = json!("2.0");
+            socket.send(Message::Text(response.to_string())).await.unwrap();
+            for message in messages {
+                socket.send(Message::Text(message.to_string())).await.unwrap();
+            }
+            // Hold the connection open until the client goes away
+            while let Some(Ok(_)) = socket.next().await {}
+        });
+        return url;
+    }
+
+    fn slot_notification(subscription: u64, slot: u64) -> Value {
+        return json!({
+            "jsonrpc": "2.0",
+            "method": "slotNotification",
+            "params": {
+                "result": { "slot": slot, "parent": slot - 1, "root": slot - 32 },
+                "subscription": subscription,
+            },
+        });
+    }
+
+    #[test]
+    fn test_slot_subscribe_streams_notifications() {
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            let messages = vec![
+                slot_notification(7, 100),
+                // Notifications for other subscriptions get skipped
+                slot_notification(8, 500),
+                slot_notification(7, 101),
+                slot_notification(7, 102),
+            ];
+            let url = start_mock_pubsub_server(json!({ "result": 7 }), messages).await;
+            let client = PubsubClient::new(url);
+            let mut subscription = client.slot_subscribe().await.unwrap();
+            assert_eq!(7, subscription.subscription_id);
+
+            let notifications: Vec<_> = (&mut subscription).take(3).collect().await;
+            let slots: Vec<u64> = notifications.iter().map(|notification| notification.value.slot).collect();
+            assert_eq!(vec![100, 101, 102], slots);
+            assert!(notifications[0].received_at <= notifications[2].received_at);
+            assert!(subscription.drain().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_subscribe_error_is_returned() {
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            let error = json!({ "error": { "code": -32602, "message": "Invalid params" } });
+            let url = start_mock_pubsub_server(error, vec![]).await;
+            let client = PubsubClient::new(url);
+            let err = client.root_subscribe().await.err().unwrap();
+            assert_eq!(Some(-32602), RpcError::find(&err).unwrap().get_code());
+        });
+    }
+}
@@ -0,0 +1,293 @@
+use std::{fmt, time::{Duration, Instant}};
+
+use anyhow::{anyhow, Result};
+
+use super::{commitment_config::CommitmentConfig, rpc_client::RpcClient, rpc_error::RpcError};
+
+// The init-complete file only appears once the validator has loaded its ledger, which can take a while on a big ledger
+const DEFAULT_INIT_COMPLETE_TIMEOUT: Duration = Duration::from_secs(90);
+// A node that's catching up reports itself as unhealthy the whole time, so this only bounds how long it can go without
+// closing the gap; see ReadinessTracker::poll
+const DEFAULT_HEALTHY_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_SLOT_ADVANCING_TIMEOUT: Duration = Duration::from_secs(30);
+
+// The stages a node goes through on its way to being ready to use, in order
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReadinessStage {
+    // Waiting for the validator to write its init-complete file
+    InitComplete,
+    // Waiting for the RPC API to come up and for getHealth to report 'ok'
+    Healthy,
+    // Waiting for getSlot to report a slot past the first one it reported, i.e. for the node to be processing the chain
+    SlotAdvancing,
+    Ready,
+}
+
+impl fmt::Display for ReadinessStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            ReadinessStage::InitComplete => "init-complete file",
+            ReadinessStage::Healthy => "healthy RPC",
+            ReadinessStage::SlotAdvancing => "advancing slot",
+            ReadinessStage::Ready => "ready",
+        };
+        return write!(f, "{}", description);
+    }
+}
+
+// How long a node may spend in each stage before it's considered to have failed to start
+#[derive(Clone, Copy, Debug)]
+pub struct ReadinessTimeouts {
+    pub init_complete: Duration,
+    pub healthy: Duration,
+    pub slot_advancing: Duration,
+}
+
+pub const DEFAULT_READINESS_TIMEOUTS: ReadinessTimeouts = ReadinessTimeouts{
+    init_complete: DEFAULT_INIT_COMPLETE_TIMEOUT,
+    healthy: DEFAULT_HEALTHY_TIMEOUT,
+    slot_advancing: DEFAULT_SLOT_ADVANCING_TIMEOUT,
+};
+
+impl ReadinessTimeouts {
+    fn get_timeout(&self, stage: ReadinessStage) -> Option<Duration> {
+        return match stage {
+            ReadinessStage::InitComplete => Some(self.init_complete),
+            ReadinessStage::Healthy => Some(self.healthy),
+            ReadinessStage::SlotAdvancing => Some(self.slot_advancing),
+            ReadinessStage::Ready => None,
+        };
+    }
+}
+
+// Walks a node through the readiness stages across repeated polls, remembering why the node isn't ready yet so that a
+// startup failure can say which stage it got stuck at and why
+// Once a stage times out the node is failed for good, so that later polls don't mask the original reason
+pub struct ReadinessTracker {
+    timeouts: ReadinessTimeouts,
+    stage: ReadinessStage,
+    stage_start_time: Option<Instant>,
+    // The fewest slots behind the cluster that the node has reported during the healthy stage
+    min_slots_behind: Option<u64>,
+    // The first slot the node reported during the slot-advancing stage
+    initial_slot: Option<u64>,
+    // Why the node wasn't ready as of the last poll
+    last_reason: Option<String>,
+    failure: Option<String>,
+}
+
+impl ReadinessTracker {
+    pub fn new(timeouts: ReadinessTimeouts) -> ReadinessTracker {
+        return ReadinessTracker{
+            timeouts,
+            stage: ReadinessStage::InitComplete,
+            stage_start_time: None,
+            min_slots_behind: None,
+            initial_slot: None,
+            last_reason: None,
+            failure: None,
+        };
+    }
+
+    // Describes why the node isn't ready, or None if it is
+    pub fn get_unready_reason(&self) -> Option<String> {
+        if let Some(failure) = &self.failure {
+            return Some(failure.clone());
+        }
+        if self.stage == ReadinessStage::Ready {
+            return None;
+        }
+        let elapsed = self.stage_start_time.map(|start_time| start_time.elapsed()).unwrap_or_default();
+        return Some(format!(
+            "Still waiting for the {} stage after {:?}; last check: {}",
+            self.stage,
+            elapsed,
+            self.last_reason.as_deref().unwrap_or("not checked yet"),
+        ));
+    }
+
+    // Runs the checks for the current stage (moving on through as many stages as pass), returning whether the node is ready
+    // A node that's catching up (e.g. one that was restarted, upgraded, or joined late) fails getHealth for as long as
+    // it's behind, however far behind it started, so the healthy stage's clock restarts whenever the node reports being
+    // fewer slots behind than it was; only a node that stops closing the gap times out
+    pub fn poll<F: FnOnce() -> Result<()>>(&mut self, check_init_complete: F, client: &RpcClient) -> bool {
+        if self.failure.is_some() {
+            return false;
+        }
+        // The init-complete check is only ever run once per poll, because it's the first stage
+        let mut check_init_complete = Some(check_init_complete);
+        loop {
+            let stage_start_time = *self.stage_start_time.get_or_insert_with(Instant::now);
+            let check_result = match self.stage {
+                ReadinessStage::InitComplete => match check_init_complete.take() {
+                    Some(check) => check(),
+                    None => Err(anyhow!("The init-complete check was already run in this poll")),
+                },
+                ReadinessStage::Healthy => client.get_health(),
+                ReadinessStage::SlotAdvancing => self.check_slot_advancing(client),
+                ReadinessStage::Ready => return true,
+            };
+            match check_result {
+                Ok(()) => {
+                    debug!("Node passed the {} readiness stage after {:?}", self.stage, stage_start_time.elapsed());
+                    self.stage = ReadinessTracker::get_next_stage(self.stage);
+                    self.stage_start_time = None;
+                    self.last_reason = None;
+                },
+                Err(err) => {
+                    let reason = format!("{:#}", err);
+                    debug!("Node isn't past the {} readiness stage yet: {}", self.stage, reason);
+                    let mut stage_start_time = stage_start_time;
+                    if self.stage == ReadinessStage::Healthy && self.is_catching_up(&err) {
+                        stage_start_time = Instant::now();
+                        self.stage_start_time = Some(stage_start_time);
+                    }
+                    let timeout = self.timeouts.get_timeout(self.stage)
+                        .expect("Every stage but the ready one has a timeout");
+                    if stage_start_time.elapsed() >= timeout {
+                        let failure = format!(
+                            "Node didn't pass the {} readiness stage within {:?}; last check: {}",
+                            self.stage,
+                            timeout,
+                            reason,
+                        );
+                        error!("{}", failure);
+                        self.failure = Some(failure);
+                    }
+                    self.last_reason = Some(reason);
+                    return false;
+                },
+            }
+        }
+    }
+
+    // Returns: whether the error reports the node as fewer slots behind than it's been before
+    fn is_catching_up(&mut self, err: &anyhow::Error) -> bool {
+        let num_slots_behind = match RpcError::find(err).and_then(|rpc_err| rpc_err.get_num_slots_behind()) {
+            Some(num_slots_behind) => num_slots_behind,
+            None => return false,
+        };
+        let is_closer = self.min_slots_behind.map(|min| num_slots_behind < min).unwrap_or(false);
+        if is_closer || self.min_slots_behind.is_none() {
+            self.min_slots_behind = Some(num_slots_behind);
+        }
+        return is_closer;
+    }
+
+    fn check_slot_advancing(&mut self, client: &RpcClient) -> Result<()> {
+        let slot = client.get_slot(Some(CommitmentConfig::processed()))?;
+        let initial_slot = *self.initial_slot.get_or_insert(slot);
+        if slot <= initial_slot {
+            return Err(anyhow!(
+                "Slot is still {}",
+                slot,
+            ));
+        }
+        return Ok(());
+    }
+
+    fn get_next_stage(stage: ReadinessStage) -> ReadinessStage {
+        return match stage {
+            ReadinessStage::InitComplete => ReadinessStage::Healthy,
+            ReadinessStage::Healthy => ReadinessStage::SlotAdvancing,
+            ReadinessStage::SlotAdvancing | ReadinessStage::Ready => ReadinessStage::Ready,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::{AtomicU64, Ordering}, thread::sleep, time::Duration};
+
+    use anyhow::anyhow;
+    use serde_json::json;
+
+    use crate::services_impl::validator::{commitment_config::CommitmentConfig, mock_rpc_server::MockRpcServer, retry_policy::NO_RETRY_POLICY, rpc_client::RpcClient};
+
+    use super::{DEFAULT_READINESS_TIMEOUTS, ReadinessStage, ReadinessTimeouts, ReadinessTracker};
+
+    #[test]
+    fn test_poll_walks_through_stages() {
+        static SLOT: AtomicU64 = AtomicU64::new(10);
+        let server = MockRpcServer::start(|method, _| {
+            return match method {
+                "getHealth" => Ok(json!("ok")),
+                "getSlot" => Ok(json!(SLOT.load(Ordering::SeqCst))),
+                _ => panic!("Unexpected method '{}'", method),
+            };
+        });
+        let client = RpcClient::new(server.get_url(), CommitmentConfig::confirmed());
+        client.set_retry_policy(NO_RETRY_POLICY);
+        let mut tracker = ReadinessTracker::new(DEFAULT_READINESS_TIMEOUTS);
+
+        assert!(!tracker.poll(|| Err(anyhow!("No init-complete file")), &client));
+        assert_eq!(ReadinessStage::InitComplete, tracker.stage);
+        assert!(tracker.get_unready_reason().unwrap().contains("No init-complete file"));
+
+        // The first slot seen is only the baseline for the slot to advance past
+        assert!(!tracker.poll(|| Ok(()), &client));
+        assert_eq!(ReadinessStage::SlotAdvancing, tracker.stage);
+        assert!(tracker.get_unready_reason().unwrap().contains("Slot is still 10"));
+
+        SLOT.store(11, Ordering::SeqCst);
+        assert!(tracker.poll(|| Ok(()), &client));
+        assert_eq!(ReadinessStage::Ready, tracker.stage);
+        assert_eq!(None, tracker.get_unready_reason());
+    }
+
+    #[test]
+    fn test_poll_fails_stage_after_timeout() {
+        let server = MockRpcServer::start(|_, _| {
+            return Err(json!({ "code": -32005, "message": "Node is behind by 42 slots" }));
+        });
+        let client = RpcClient::new(server.get_url(), CommitmentConfig::confirmed());
+        client.set_retry_policy(NO_RETRY_POLICY);
+        let timeouts = ReadinessTimeouts{
+            init_complete: Duration::from_secs(60),
+            healthy: Duration::from_millis(0),
+            slot_advancing: Duration::from_secs(60),
+        };
+        let mut tracker = ReadinessTracker::new(timeouts);
+
+        assert!(!tracker.poll(|| Ok(()), &client));
+        let failure = tracker.failure.clone().unwrap();
+        assert!(failure.contains("healthy RPC"), "{}", failure);
+        assert!(failure.contains("Node is behind by 42 slots"), "{}", failure);
+
+        // A failed node stays failed, even if it would pass now
+        assert!(!tracker.poll(|| Ok(()), &client));
+        assert_eq!(Some(failure), tracker.get_unready_reason());
+    }
+
+    #[test]
+    fn test_poll_gives_catching_up_node_more_time() {
+        static NUM_SLOTS_BEHIND: AtomicU64 = AtomicU64::new(100);
+        let server = MockRpcServer::start(|_, _| {
+            return Err(json!({
+                "code": -32005,
+                "message": "Node is behind",
+                "data": { "numSlotsBehind": NUM_SLOTS_BEHIND.load(Ordering::SeqCst) },
+            }));
+        });
+        let client = RpcClient::new(server.get_url(), CommitmentConfig::confirmed());
+        client.set_retry_policy(NO_RETRY_POLICY);
+        let timeouts = ReadinessTimeouts{
+            init_complete: Duration::from_secs(60),
+            healthy: Duration::from_millis(200),
+            slot_advancing: Duration::from_secs(60),
+        };
+        let mut tracker = ReadinessTracker::new(timeouts);
+
+        assert!(!tracker.poll(|| Ok(()), &client));
+        sleep(Duration::from_millis(300));
+        // Past the timeout, but the node has closed the gap so it gets another full timeout
+        NUM_SLOTS_BEHIND.store(50, Ordering::SeqCst);
+        assert!(!tracker.poll(|| Ok(()), &client));
+        assert_eq!(None, tracker.failure);
+
+        sleep(Duration::from_millis(300));
+        assert!(!tracker.poll(|| Ok(()), &client));
+        let failure = tracker.failure.clone().unwrap();
+        assert!(failure.contains("healthy RPC"), "{}", failure);
+    }
+}
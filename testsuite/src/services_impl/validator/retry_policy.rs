@@ -0,0 +1,221 @@
+use std::{future::Future, thread::sleep, time::{Duration, Instant}};
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use reqwest::StatusCode;
+
+use super::rpc_error::RpcError;
+
+// The policy used by RPC clients that haven't been given one of their own
+// Generous enough to ride out a validator restarting (e.g. right after a partition heals)
+pub const DEFAULT_RETRY_POLICY: RetryPolicy = RetryPolicy{
+    initial_backoff: Duration::from_millis(250),
+    max_backoff: Duration::from_secs(4),
+    backoff_multiplier: 2,
+    jitter_fraction: 0.2,
+    max_elapsed_time: Duration::from_secs(30),
+    is_retryable: is_transient_error,
+};
+
+// Fails on the first error, for tests that want to observe failures exactly as the node reports them
+pub const NO_RETRY_POLICY: RetryPolicy = RetryPolicy{
+    initial_backoff: Duration::from_millis(0),
+    max_backoff: Duration::from_millis(0),
+    backoff_multiplier: 1,
+    jitter_fraction: 0.0,
+    max_elapsed_time: Duration::from_millis(0),
+    is_retryable: is_never_retryable,
+};
+
+// Decides whether, and after how long, a failed RPC call should be retried
+// The backoff before retry N is initial_backoff * backoff_multiplier^N, capped at max_backoff and then jittered; retrying
+// stops once the error isn't retryable or once the next attempt would start after max_elapsed_time
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub backoff_multiplier: u32,
+    // Each backoff is randomly lengthened or shortened by up to this fraction, so that clients which failed together
+    // don't all retry together
+    pub jitter_fraction: f64,
+    pub max_elapsed_time: Duration,
+    // Classifies the errors that are worth retrying; errors that aren't RpcErrors are never retried
+    pub is_retryable: fn(&RpcError) -> bool,
+}
+
+impl RetryPolicy {
+    // Returns how long to wait before retrying, or None if the call shouldn't be retried
+    pub fn get_retry_delay(&self, err: &anyhow::Error, num_retries_so_far: u32, elapsed: Duration) -> Option<Duration> {
+        let rpc_err = RpcError::find(err)?;
+        if !(self.is_retryable)(rpc_err) {
+            return None;
+        }
+        let backoff = self.get_backoff(num_retries_so_far);
+        if elapsed + backoff > self.max_elapsed_time {
+            return None;
+        }
+        return Some(backoff);
+    }
+
+    // Calls the function until it succeeds or the policy says to stop retrying
+    pub fn run<T, F>(&self, mut call: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let start_time = Instant::now();
+        let mut num_retries = 0;
+        loop {
+            let err = match call() {
+                Ok(result) => return Ok(result),
+                Err(err) => err,
+            };
+            let delay = self.get_delay_or_give_up(err, num_retries, start_time)?;
+            sleep(delay);
+            num_retries += 1;
+        }
+    }
+
+    // Async version of run, which sleeps without blocking the runtime
+    pub async fn run_async<T, F, Fut>(&self, mut call: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let start_time = Instant::now();
+        let mut num_retries = 0;
+        loop {
+            let err = match call().await {
+                Ok(result) => return Ok(result),
+                Err(err) => err,
+            };
+            let delay = self.get_delay_or_give_up(err, num_retries, start_time)?;
+            tokio::time::sleep(delay).await;
+            num_retries += 1;
+        }
+    }
+
+    fn get_delay_or_give_up(&self, err: anyhow::Error, num_retries: u32, start_time: Instant) -> Result<Duration> {
+        let elapsed = start_time.elapsed();
+        return match self.get_retry_delay(&err, num_retries, elapsed) {
+            Some(delay) => {
+                debug!("RPC call failed with error '{}'; retrying in {:?} ({} retries so far)", err, delay, num_retries);
+                Ok(delay)
+            },
+            None if num_retries == 0 => Err(err),
+            None => Err(err).context(format!("Giving up after {} retries over {:?}", num_retries, elapsed)),
+        };
+    }
+
+    fn get_backoff(&self, num_retries_so_far: u32) -> Duration {
+        let multiplier = self.backoff_multiplier.saturating_pow(num_retries_so_far);
+        let backoff = self.initial_backoff.checked_mul(multiplier)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter_fraction <= 0.0 {
+            return backoff;
+        }
+        let jitter_factor = rand::thread_rng().gen_range(1.0 - self.jitter_fraction, 1.0 + self.jitter_fraction);
+        return backoff.mul_f64(jitter_factor);
+    }
+}
+
+// The default classifier: retries failures that a node will likely recover from on its own (it being unreachable while
+// it restarts, being overloaded, or still catching up to the cluster), but not failures that retrying won't fix
+pub fn is_transient_error(err: &RpcError) -> bool {
+    return match err {
+        RpcError::Transport(_) | RpcError::Timeout(_) => true,
+        RpcError::HttpStatus(status) => {
+            *status == StatusCode::TOO_MANY_REQUESTS
+                || *status == StatusCode::BAD_GATEWAY
+                || *status == StatusCode::SERVICE_UNAVAILABLE
+                || *status == StatusCode::GATEWAY_TIMEOUT
+        },
+        RpcError::RpcResponseError{ .. } => err.is_node_unhealthy(),
+        RpcError::Decode(_) => false,
+    };
+}
+
+pub fn is_never_retryable(_: &RpcError) -> bool {
+    return false;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread, time::{Duration, Instant}};
+
+    use reqwest::StatusCode;
+    use serde_json::json;
+
+    use crate::services_impl::validator::{commitment_config::CommitmentConfig, mock_rpc_server::MockRpcServer, rpc_client::RpcClient, rpc_error::RpcError};
+
+    use super::{RetryPolicy, is_transient_error};
+
+    fn fast_policy(max_elapsed_time: Duration) -> RetryPolicy {
+        return RetryPolicy{
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            backoff_multiplier: 2,
+            jitter_fraction: 0.2,
+            max_elapsed_time,
+            is_retryable: is_transient_error,
+        };
+    }
+
+    fn start_flaky_client(num_failing_requests: usize, retry_policy: RetryPolicy) -> (MockRpcServer, RpcClient) {
+        let server = MockRpcServer::start_flaky("127.0.0.1:0".parse().unwrap(), num_failing_requests, |_, _| Ok(json!(42)));
+        let client = RpcClient::new(server.get_url(), CommitmentConfig::confirmed());
+        client.set_retry_policy(retry_policy);
+        return (server, client);
+    }
+
+    #[test]
+    fn test_retries_until_flaky_server_recovers() {
+        let (server, client) = start_flaky_client(3, fast_policy(Duration::from_secs(5)));
+        assert_eq!(42, client.get_slot(None).unwrap());
+        assert_eq!(1, server.get_received_requests().len());
+    }
+
+    #[test]
+    fn test_gives_up_after_max_elapsed_time() {
+        let (_server, client) = start_flaky_client(usize::MAX, fast_policy(Duration::from_millis(200)));
+        let start_time = Instant::now();
+        let err = client.get_slot(None).unwrap_err();
+        assert!(start_time.elapsed() < Duration::from_secs(5));
+        assert_eq!(Some(&RpcError::HttpStatus(StatusCode::SERVICE_UNAVAILABLE)), RpcError::find(&err));
+        assert!(err.chain().any(|cause| cause.to_string().starts_with("Giving up after")));
+    }
+
+    #[test]
+    fn test_retries_connection_refused_while_node_restarts() {
+        // Reserve a port, then leave it closed for a while so that the first attempts get their connections refused
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let client = RpcClient::new(format!("http://{}", addr), CommitmentConfig::confirmed());
+        client.set_retry_policy(fast_policy(Duration::from_secs(10)));
+        let restart = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            return MockRpcServer::start_flaky(addr, 0, |_, _| Ok(json!(42)));
+        });
+        assert_eq!(42, client.get_slot(None).unwrap());
+        restart.join().unwrap();
+    }
+
+    #[test]
+    fn test_non_retryable_errors_fail_immediately() {
+        let server = MockRpcServer::start(|_, _| Err(json!({ "code": -32602, "message": "Invalid params" })));
+        let client = RpcClient::new(server.get_url(), CommitmentConfig::confirmed());
+        client.set_retry_policy(fast_policy(Duration::from_secs(5)));
+        let err = client.get_slot(None).unwrap_err();
+        assert_eq!(Some(-32602), RpcError::find(&err).unwrap().get_code());
+        assert_eq!(1, server.get_received_requests().len());
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = fast_policy(Duration::from_secs(5));
+        for num_retries in 0..10 {
+            let unjittered = Duration::from_millis(10 * 2u64.pow(num_retries)).min(Duration::from_millis(50));
+            let backoff = policy.get_backoff(num_retries);
+            assert!(backoff >= unjittered.mul_f64(0.8) && backoff <= unjittered.mul_f64(1.2), "backoff {:?} for retry {}", backoff, num_retries);
+        }
+    }
+}
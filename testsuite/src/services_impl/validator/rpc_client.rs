@@ -0,0 +1,826 @@
+use std::cell::Cell;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{Value, json};
+
+use super::{commitment_config::CommitmentConfig, http_sender::HttpSender, retry_policy::{DEFAULT_RETRY_POLICY, RetryPolicy}, rpc_error::RpcError, rpc_request::{MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_MULTIPLE_ACCOUNTS, RpcRequest}, rpc_response::{EncodedConfirmedTransaction, EpochInfo, EpochSchedule, Response, RpcAccountBalance, RpcBlockhashFeeCalculator, RpcConfirmedTransactionStatusWithSignature, RpcContactInfo, RpcFeeCalculator, RpcFeeRateGovernor, RpcFees, RpcIdentity, RpcInflationGovernor, RpcInflationRate, RpcKeyedAccount, RpcLeaderSchedule, RpcSimulateTransactionResult, RpcStorageTurn, RpcSupply, RpcVersionInfo, RpcVoteAccountStatus, TransactionStatus, UiAccount, UiConfirmedBlock, UiTokenAmount}, rpc_sender::RpcSender};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This file is a stripped-down port of https://github.com/solana-labs/solana/blob/master/client/src/rpc_client.rs
+because solana-client provides Ledger support, which means it has a dependency
+on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana client here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// Transactions are always shipped to the node in base64, which (unlike the base58 default) has no size limit
+const TRANSACTION_ENCODING: &str = "base64";
+const ACCOUNT_ENCODING: &str = "base64";
+const TRANSACTION_DETAILS_ENCODING: &str = "json";
+
+pub enum RpcLargestAccountsFilter {
+    #[allow(dead_code)]
+    Circulating,
+    #[allow(dead_code)]
+    NonCirculating,
+}
+
+pub enum TokenAccountsFilter {
+    #[allow(dead_code)]
+    Mint(String),
+    #[allow(dead_code)]
+    ProgramId(String),
+}
+
+// Several views of a node's progress, all sampled in the same round trip
+pub struct ProgressSample {
+    pub slot: u64,
+    pub transaction_count: u64,
+    pub epoch_info: EpochInfo,
+}
+
+// Methods that take an optional commitment fall back to the client's default commitment when given None
+// Failed requests are retried according to the client's retry policy
+pub struct RpcClient {
+    sender: Box<dyn RpcSender>,
+    commitment_config: Cell<CommitmentConfig>,
+    retry_policy: Cell<RetryPolicy>,
+}
+
+impl RpcClient {
+    pub fn new(url: String, commitment_config: CommitmentConfig) -> RpcClient {
+        return RpcClient::new_sender(HttpSender::new(url), commitment_config);
+    }
+
+    pub fn new_sender<T: RpcSender + 'static>(sender: T, commitment_config: CommitmentConfig) -> RpcClient {
+        return RpcClient{
+            sender: Box::new(sender),
+            commitment_config: Cell::new(commitment_config),
+            retry_policy: Cell::new(DEFAULT_RETRY_POLICY),
+        };
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        return self.commitment_config.get();
+    }
+
+    pub fn set_commitment(&self, commitment_config: CommitmentConfig) {
+        self.commitment_config.set(commitment_config);
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        return self.retry_policy.get();
+    }
+
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        self.retry_policy.set(retry_policy);
+    }
+
+    #[allow(dead_code)]
+    pub fn deregister_node(&self, pubkey: &str, signature: &str, msg: &[u8]) -> Result<()> {
+        let params = json!([pubkey, signature, msg]);
+        self.send::<Value>(RpcRequest::DeregisterNode, params)
+            .context(format!("An error occurred deregistering node '{}'", pubkey))?;
+        return Ok(());
+    }
+
+    #[allow(dead_code)]
+    pub fn validator_exit(&self) -> Result<bool> {
+        return self.send(RpcRequest::ValidatorExit, Value::Null)
+            .context("An error occurred requesting that the validator exit");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_account_info(&self, pubkey: &str, commitment: Option<CommitmentConfig>) -> Result<Response<Option<UiAccount>>> {
+        let params = json!([
+            pubkey,
+            {
+                "encoding": ACCOUNT_ENCODING,
+                "commitment": self.get_commitment(commitment).commitment,
+            },
+        ]);
+        return self.send(RpcRequest::GetAccountInfo, params)
+            .context(format!("An error occurred getting the account info for '{}'", pubkey));
+    }
+
+    pub fn get_balance(&self, pubkey: &str, commitment: Option<CommitmentConfig>) -> Result<Response<u64>> {
+        let params = json!([pubkey, self.get_commitment(commitment)]);
+        return self.send(RpcRequest::GetBalance, params)
+            .context(format!("An error occurred getting the balance of '{}'", pubkey));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_block_time(&self, slot: u64) -> Result<Option<i64>> {
+        return self.send(RpcRequest::GetBlockTime, json!([slot]))
+            .context(format!("An error occurred getting the block time for slot {}", slot));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_cluster_nodes(&self) -> Result<Vec<RpcContactInfo>> {
+        return self.send(RpcRequest::GetClusterNodes, Value::Null)
+            .context("An error occurred getting the cluster nodes");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_confirmed_block(&self, slot: u64) -> Result<Option<UiConfirmedBlock>> {
+        let params = json!([slot, TRANSACTION_DETAILS_ENCODING]);
+        return self.send(RpcRequest::GetConfirmedBlock, params)
+            .context(format!("An error occurred getting confirmed block {}", slot));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_confirmed_blocks(&self, start_slot: u64, end_slot: Option<u64>) -> Result<Vec<u64>> {
+        let params = match end_slot {
+            Some(end_slot) => json!([start_slot, end_slot]),
+            None => json!([start_slot]),
+        };
+        return self.send(RpcRequest::GetConfirmedBlocks, params)
+            .context(format!("An error occurred getting the confirmed blocks starting at slot {}", start_slot));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_confirmed_blocks_with_limit(&self, start_slot: u64, limit: usize) -> Result<Vec<u64>> {
+        return self.send(RpcRequest::GetConfirmedBlocksWithLimit, json!([start_slot, limit]))
+            .context(format!("An error occurred getting {} confirmed blocks starting at slot {}", limit, start_slot));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_confirmed_signatures_for_address(&self, address: &str, start_slot: u64, end_slot: u64) -> Result<Vec<String>> {
+        let params = json!([address, start_slot, end_slot]);
+        return self.send(RpcRequest::GetConfirmedSignaturesForAddress, params)
+            .context(format!("An error occurred getting the confirmed signatures for address '{}'", address));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_confirmed_signatures_for_address2(
+        &self,
+        address: &str,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let params = json!([
+            address,
+            {
+                "before": before,
+                "until": until,
+                "limit": limit,
+            },
+        ]);
+        return self.send(RpcRequest::GetConfirmedSignaturesForAddress2, params)
+            .context(format!("An error occurred getting the confirmed signatures for address '{}'", address));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_confirmed_transaction(&self, signature: &str) -> Result<Option<EncodedConfirmedTransaction>> {
+        let params = json!([signature, TRANSACTION_DETAILS_ENCODING]);
+        return self.send(RpcRequest::GetConfirmedTransaction, params)
+            .context(format!("An error occurred getting confirmed transaction '{}'", signature));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_epoch_info(&self, commitment: Option<CommitmentConfig>) -> Result<EpochInfo> {
+        return self.send(RpcRequest::GetEpochInfo, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the epoch info");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_epoch_schedule(&self) -> Result<EpochSchedule> {
+        return self.send(RpcRequest::GetEpochSchedule, Value::Null)
+            .context("An error occurred getting the epoch schedule");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_fee_calculator_for_blockhash(&self, blockhash: &str, commitment: Option<CommitmentConfig>) -> Result<Response<Option<RpcFeeCalculator>>> {
+        let params = json!([blockhash, self.get_commitment(commitment)]);
+        return self.send(RpcRequest::GetFeeCalculatorForBlockhash, params)
+            .context(format!("An error occurred getting the fee calculator for blockhash '{}'", blockhash));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_fee_rate_governor(&self) -> Result<Response<RpcFeeRateGovernor>> {
+        return self.send(RpcRequest::GetFeeRateGovernor, Value::Null)
+            .context("An error occurred getting the fee rate governor");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_fees(&self, commitment: Option<CommitmentConfig>) -> Result<Response<RpcFees>> {
+        return self.send(RpcRequest::GetFees, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the fees");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_first_available_block(&self) -> Result<u64> {
+        return self.send(RpcRequest::GetFirstAvailableBlock, Value::Null)
+            .context("An error occurred getting the first available block");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_genesis_hash(&self) -> Result<String> {
+        return self.send(RpcRequest::GetGenesisHash, Value::Null)
+            .context("An error occurred getting the genesis hash");
+    }
+
+    // Returns Ok only if the node reports itself as healthy
+    pub fn get_health(&self) -> Result<()> {
+        let health: String = self.send(RpcRequest::GetHealth, Value::Null)
+            .context("An error occurred getting the node health")?;
+        if health != "ok" {
+            return Err(anyhow!(
+                "Node reported health '{}' rather than 'ok'",
+                health,
+            ));
+        }
+        return Ok(());
+    }
+
+    #[allow(dead_code)]
+    pub fn get_identity(&self) -> Result<RpcIdentity> {
+        return self.send(RpcRequest::GetIdentity, Value::Null)
+            .context("An error occurred getting the node identity");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_inflation_governor(&self, commitment: Option<CommitmentConfig>) -> Result<RpcInflationGovernor> {
+        return self.send(RpcRequest::GetInflationGovernor, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the inflation governor");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_inflation_rate(&self) -> Result<RpcInflationRate> {
+        return self.send(RpcRequest::GetInflationRate, Value::Null)
+            .context("An error occurred getting the inflation rate");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_largest_accounts(&self, filter: Option<RpcLargestAccountsFilter>, commitment: Option<CommitmentConfig>) -> Result<Response<Vec<RpcAccountBalance>>> {
+        let filter_str = match filter {
+            Some(RpcLargestAccountsFilter::Circulating) => Some("circulating"),
+            Some(RpcLargestAccountsFilter::NonCirculating) => Some("nonCirculating"),
+            None => None,
+        };
+        let params = json!([
+            {
+                "filter": filter_str,
+                "commitment": self.get_commitment(commitment).commitment,
+            },
+        ]);
+        return self.send(RpcRequest::GetLargestAccounts, params)
+            .context("An error occurred getting the largest accounts");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_leader_schedule(&self, slot: Option<u64>, commitment: Option<CommitmentConfig>) -> Result<Option<RpcLeaderSchedule>> {
+        let params = json!([slot, self.get_commitment(commitment)]);
+        return self.send(RpcRequest::GetLeaderSchedule, params)
+            .context("An error occurred getting the leader schedule");
+    }
+
+    pub fn get_minimum_balance_for_rent_exemption(&self, data_len: usize, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        let params = json!([data_len, self.get_commitment(commitment)]);
+        return self.send(RpcRequest::GetMinimumBalanceForRentExemption, params)
+            .context(format!("An error occurred getting the minimum rent-exempt balance for {} bytes of data", data_len));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_multiple_accounts(&self, pubkeys: &[&str], commitment: Option<CommitmentConfig>) -> Result<Response<Vec<Option<UiAccount>>>> {
+        if pubkeys.len() > MAX_MULTIPLE_ACCOUNTS {
+            return Err(anyhow!(
+                "Requested {} accounts, but at most {} accounts can be requested at once",
+                pubkeys.len(),
+                MAX_MULTIPLE_ACCOUNTS,
+            ));
+        }
+        let params = json!([
+            pubkeys,
+            {
+                "encoding": ACCOUNT_ENCODING,
+                "commitment": self.get_commitment(commitment).commitment,
+            },
+        ]);
+        return self.send(RpcRequest::GetMultipleAccounts, params)
+            .context("An error occurred getting multiple accounts");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_program_accounts(&self, program_id: &str, commitment: Option<CommitmentConfig>) -> Result<Vec<RpcKeyedAccount>> {
+        let params = json!([
+            program_id,
+            {
+                "encoding": ACCOUNT_ENCODING,
+                "commitment": self.get_commitment(commitment).commitment,
+            },
+        ]);
+        return self.send(RpcRequest::GetProgramAccounts, params)
+            .context(format!("An error occurred getting the accounts owned by program '{}'", program_id));
+    }
+
+    pub fn get_recent_blockhash(&self, commitment: Option<CommitmentConfig>) -> Result<Response<RpcBlockhashFeeCalculator>> {
+        return self.send(RpcRequest::GetRecentBlockhash, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting a recent blockhash");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_snapshot_slot(&self) -> Result<u64> {
+        return self.send(RpcRequest::GetSnapshotSlot, Value::Null)
+            .context("An error occurred getting the snapshot slot");
+    }
+
+    pub fn get_signature_statuses(&self, signatures: &[&str], search_transaction_history: bool) -> Result<Response<Vec<Option<TransactionStatus>>>> {
+        if signatures.len() > MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS {
+            return Err(anyhow!(
+                "Requested {} signature statuses, but at most {} can be requested at once",
+                signatures.len(),
+                MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
+            ));
+        }
+        let params = json!([signatures, { "searchTransactionHistory": search_transaction_history }]);
+        return self.send(RpcRequest::GetSignatureStatuses, params)
+            .context("An error occurred getting the signature statuses");
+    }
+
+    pub fn get_slot(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        return self.send(RpcRequest::GetSlot, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the slot");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_slot_leader(&self, commitment: Option<CommitmentConfig>) -> Result<String> {
+        return self.send(RpcRequest::GetSlotLeader, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the slot leader");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_storage_turn(&self) -> Result<RpcStorageTurn> {
+        return self.send(RpcRequest::GetStorageTurn, Value::Null)
+            .context("An error occurred getting the storage turn");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_storage_turn_rate(&self) -> Result<u64> {
+        return self.send(RpcRequest::GetStorageTurnRate, Value::Null)
+            .context("An error occurred getting the storage turn rate");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_slots_per_segment(&self) -> Result<u64> {
+        return self.send(RpcRequest::GetSlotsPerSegment, Value::Null)
+            .context("An error occurred getting the slots per segment");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_storage_pubkeys_for_slot(&self, slot: u64) -> Result<Vec<String>> {
+        return self.send(RpcRequest::GetStoragePubkeysForSlot, json!([slot]))
+            .context(format!("An error occurred getting the storage pubkeys for slot {}", slot));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_supply(&self, commitment: Option<CommitmentConfig>) -> Result<Response<RpcSupply>> {
+        return self.send(RpcRequest::GetSupply, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the supply");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_token_account_balance(&self, pubkey: &str, commitment: Option<CommitmentConfig>) -> Result<Response<UiTokenAmount>> {
+        let params = json!([pubkey, self.get_commitment(commitment)]);
+        return self.send(RpcRequest::GetTokenAccountBalance, params)
+            .context(format!("An error occurred getting the token account balance of '{}'", pubkey));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_token_accounts_by_delegate(&self, delegate: &str, filter: TokenAccountsFilter, commitment: Option<CommitmentConfig>) -> Result<Response<Vec<RpcKeyedAccount>>> {
+        let params = json!([
+            delegate,
+            RpcClient::get_token_accounts_filter_json(filter),
+            {
+                "encoding": ACCOUNT_ENCODING,
+                "commitment": self.get_commitment(commitment).commitment,
+            },
+        ]);
+        return self.send(RpcRequest::GetTokenAccountsByDelegate, params)
+            .context(format!("An error occurred getting the token accounts delegated to '{}'", delegate));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_token_accounts_by_owner(&self, owner: &str, filter: TokenAccountsFilter, commitment: Option<CommitmentConfig>) -> Result<Response<Vec<RpcKeyedAccount>>> {
+        let params = json!([
+            owner,
+            RpcClient::get_token_accounts_filter_json(filter),
+            {
+                "encoding": ACCOUNT_ENCODING,
+                "commitment": self.get_commitment(commitment).commitment,
+            },
+        ]);
+        return self.send(RpcRequest::GetTokenAccountsByOwner, params)
+            .context(format!("An error occurred getting the token accounts owned by '{}'", owner));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_token_supply(&self, mint: &str, commitment: Option<CommitmentConfig>) -> Result<Response<UiTokenAmount>> {
+        let params = json!([mint, self.get_commitment(commitment)]);
+        return self.send(RpcRequest::GetTokenSupply, params)
+            .context(format!("An error occurred getting the token supply of mint '{}'", mint));
+    }
+
+    #[allow(dead_code)]
+    pub fn get_total_supply(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        return self.send(RpcRequest::GetTotalSupply, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the total supply");
+    }
+
+    #[allow(dead_code)]
+    pub fn get_transaction_count(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        return self.send(RpcRequest::GetTransactionCount, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the transaction count");
+    }
+
+    pub fn get_version(&self) -> Result<RpcVersionInfo> {
+        return self.send(RpcRequest::GetVersion, Value::Null)
+            .context("An error occurred getting the node version");
+    }
+
+    pub fn get_vote_accounts(&self, commitment: Option<CommitmentConfig>) -> Result<RpcVoteAccountStatus> {
+        return self.send(RpcRequest::GetVoteAccounts, json!([self.get_commitment(commitment)]))
+            .context("An error occurred getting the vote accounts");
+    }
+
+    #[allow(dead_code)]
+    pub fn minimum_ledger_slot(&self) -> Result<u64> {
+        return self.send(RpcRequest::MinimumLedgerSlot, Value::Null)
+            .context("An error occurred getting the minimum ledger slot");
+    }
+
+    // Returns the pubkey the node was registered under
+    #[allow(dead_code)]
+    pub fn register_node(&self, pubkey: &str, signature: &str, msg: &[u8]) -> Result<String> {
+        let params = json!([pubkey, signature, msg]);
+        return self.send(RpcRequest::RegisterNode, params)
+            .context(format!("An error occurred registering node '{}'", pubkey));
+    }
+
+    // Returns the signature of the airdrop transaction
+    pub fn request_airdrop(&self, pubkey: &str, lamports: u64, commitment: Option<CommitmentConfig>) -> Result<String> {
+        let params = json!([pubkey, lamports, self.get_commitment(commitment)]);
+        return self.send(RpcRequest::RequestAirdrop, params)
+            .context(format!("An error occurred requesting an airdrop of {} lamports to '{}'", lamports, pubkey));
+    }
+
+    // Takes the wire-format bytes of a signed transaction, and returns the transaction's signature
+    pub fn send_transaction(&self, serialized_transaction: &[u8]) -> Result<String> {
+        let params = json!([
+            base64::encode(serialized_transaction),
+            {
+                "encoding": TRANSACTION_ENCODING,
+                "preflightCommitment": self.commitment().commitment,
+            },
+        ]);
+        return self.send(RpcRequest::SendTransaction, params)
+            .context("An error occurred sending the transaction");
+    }
+
+    #[allow(dead_code)]
+    pub fn simulate_transaction(&self, serialized_transaction: &[u8], commitment: Option<CommitmentConfig>) -> Result<Response<RpcSimulateTransactionResult>> {
+        let params = json!([
+            base64::encode(serialized_transaction),
+            {
+                "encoding": TRANSACTION_ENCODING,
+                "commitment": self.get_commitment(commitment).commitment,
+            },
+        ]);
+        return self.send(RpcRequest::SimulateTransaction, params)
+            .context("An error occurred simulating the transaction");
+    }
+
+    // Returns the signature of the vote
+    #[allow(dead_code)]
+    pub fn sign_vote(&self, pubkey: &str, signature: &str, msg: &[u8]) -> Result<String> {
+        let params = json!([pubkey, signature, msg]);
+        return self.send(RpcRequest::SignVote, params)
+            .context(format!("An error occurred getting node '{}' to sign a vote", pubkey));
+    }
+
+    pub fn get_progress_sample(&self, commitment: Option<CommitmentConfig>) -> Result<ProgressSample> {
+        let commitment = self.get_commitment(commitment);
+        let requests = vec![
+            (RpcRequest::GetSlot, json!([commitment])),
+            (RpcRequest::GetTransactionCount, json!([commitment])),
+            (RpcRequest::GetEpochInfo, json!([commitment])),
+        ];
+        let mut results = self.send_batch(requests)?.into_iter();
+        let mut next_result = || results.next().unwrap_or_else(|| Err(anyhow!("Missing batch response")));
+        let slot = RpcClient::deserialize(next_result())
+            .context("An error occurred getting the slot")?;
+        let transaction_count = RpcClient::deserialize(next_result())
+            .context("An error occurred getting the transaction count")?;
+        let epoch_info = RpcClient::deserialize(next_result())
+            .context("An error occurred getting the epoch info")?;
+        return Ok(ProgressSample{
+            slot,
+            transaction_count,
+            epoch_info,
+        });
+    }
+
+    // Sends all the requests in a single round trip; each request gets its own result, in the same order as the requests
+    pub fn send_batch(&self, requests: Vec<(RpcRequest, Value)>) -> Result<Vec<Result<Value>>> {
+        for (_, params) in &requests {
+            assert!(params.is_array() || params.is_null());
+        }
+        let num_requests = requests.len();
+        let results = self.retry_policy().run(|| self.sender.send_batch(requests.clone()))
+            .context("An error occurred sending the batch request")?;
+        if results.len() != num_requests {
+            return Err(anyhow!(
+                "Sent a batch of {} requests but got {} results back",
+                num_requests,
+                results.len(),
+            ));
+        }
+        return Ok(results);
+    }
+
+    pub fn send<T>(&self, request: RpcRequest, params: Value) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        assert!(params.is_array() || params.is_null());
+        let response = self.retry_policy().run(|| self.sender.send(request, params.clone()))
+            .context("An error occurred sending the request")?;
+        let deserialized = serde_json::from_value(response.clone())
+            .map_err(|err| RpcError::Decode(format!("Couldn't deserialize result '{}': {}", response, err)))
+            .context("An error occurred deserializing the response string to a JSON object")?;
+        return Ok(deserialized);
+    }
+
+    pub (super) fn deserialize<T>(response: Result<Value>) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let response = response?;
+        let deserialized = serde_json::from_value(response.clone())
+            .map_err(|err| RpcError::Decode(format!("Couldn't deserialize result '{}': {}", response, err)))
+            .context("An error occurred deserializing the response string to a JSON object")?;
+        return Ok(deserialized);
+    }
+
+    fn get_commitment(&self, commitment: Option<CommitmentConfig>) -> CommitmentConfig {
+        return commitment.unwrap_or_else(|| self.commitment());
+    }
+
+    fn get_token_accounts_filter_json(filter: TokenAccountsFilter) -> Value {
+        return match filter {
+            TokenAccountsFilter::Mint(mint) => json!({ "mint": mint }),
+            TokenAccountsFilter::ProgramId(program_id) => json!({ "programId": program_id }),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use crate::services_impl::validator::{commitment_config::CommitmentConfig, mock_rpc_server::MockRpcServer, retry_policy::NO_RETRY_POLICY};
+
+    use super::{RpcClient, RpcError, RpcRequest};
+
+    fn start_client(handler: fn(&str, &Value) -> Result<Value, Value>) -> (MockRpcServer, RpcClient) {
+        let server = MockRpcServer::start(handler);
+        let client = RpcClient::new(server.get_url(), CommitmentConfig::confirmed());
+        return (server, client);
+    }
+
+    #[test]
+    fn test_get_epoch_info() {
+        let (_server, client) = start_client(|method, _| {
+            assert_eq!("getEpochInfo", method);
+            return Ok(json!({
+                "absoluteSlot": 166598,
+                "blockHeight": 166500,
+                "epoch": 27,
+                "slotIndex": 2790,
+                "slotsInEpoch": 8192,
+                "transactionCount": 22661093,
+            }));
+        });
+        let epoch_info = client.get_epoch_info(None).unwrap();
+        assert_eq!(27, epoch_info.epoch);
+        assert_eq!(166598, epoch_info.absolute_slot);
+        assert_eq!(Some(22661093), epoch_info.transaction_count);
+    }
+
+    #[test]
+    fn test_get_vote_accounts() {
+        let (_server, client) = start_client(|_, _| {
+            return Ok(json!({
+                "current": [
+                    {
+                        "commission": 0,
+                        "epochVoteAccount": true,
+                        "epochCredits": [[1, 64, 0], [2, 192, 64]],
+                        "nodePubkey": "B97CCUW3AEZFGy6uUg6zUdnNYvnVq5VG8PUtb2HayTDD",
+                        "lastVote": 147,
+                        "activatedStake": 42,
+                        "votePubkey": "3ZT31jkAGhUaw8jsy4bTknwBMP8i4Eueh52By4zXcsVw",
+                        "rootSlot": 100,
+                    },
+                ],
+                "delinquent": [],
+            }));
+        });
+        let vote_accounts = client.get_vote_accounts(None).unwrap();
+        assert_eq!(1, vote_accounts.current.len());
+        assert_eq!(0, vote_accounts.delinquent.len());
+        let vote_account = &vote_accounts.current[0];
+        assert_eq!(42, vote_account.activated_stake);
+        assert_eq!(vec![(1, 64, 0), (2, 192, 64)], vote_account.epoch_credits);
+    }
+
+    #[test]
+    fn test_get_version_and_cluster_nodes() {
+        let (_server, client) = start_client(|method, _| {
+            return match method {
+                "getVersion" => Ok(json!({ "solana-core": "1.5.10", "feature-set": 1234 })),
+                "getClusterNodes" => Ok(json!([
+                    {
+                        "gossip": "10.2.0.3:8001",
+                        "pubkey": "9QzsJf7LPLj8GkXbYT3LFDKqsj2hHG7TA3xinJHu8epQ",
+                        "rpc": "10.2.0.3:8899",
+                        "tpu": null,
+                        "version": "1.5.10",
+                    },
+                ])),
+                _ => panic!("Unexpected method '{}'", method),
+            };
+        });
+        let version = client.get_version().unwrap();
+        assert_eq!("1.5.10", version.solana_core);
+        assert_eq!(Some(1234), version.feature_set);
+
+        let nodes = client.get_cluster_nodes().unwrap();
+        assert_eq!(1, nodes.len());
+        assert_eq!(Some("10.2.0.3:8899".parse().unwrap()), nodes[0].rpc);
+        assert_eq!(None, nodes[0].tpu);
+    }
+
+    #[test]
+    fn test_get_supply_unwraps_context() {
+        let (_server, client) = start_client(|_, _| {
+            return Ok(json!({
+                "context": { "slot": 1114 },
+                "value": {
+                    "circulating": 16000,
+                    "nonCirculating": 1000000,
+                    "nonCirculatingAccounts": ["FEy8pTbP5fEoqMV1GdTz83byuA8EKByqYat1PKDgVAq5"],
+                    "total": 1016000,
+                },
+            }));
+        });
+        let supply = client.get_supply(None).unwrap();
+        assert_eq!(1114, supply.context.slot);
+        assert_eq!(1016000, supply.value.total);
+        assert_eq!(1, supply.value.non_circulating_accounts.len());
+    }
+
+    #[test]
+    fn test_commitment_defaults_to_client_commitment() {
+        let (server, client) = start_client(|_, _| Ok(json!(42)));
+        client.get_slot(None).unwrap();
+        client.get_slot(Some(CommitmentConfig::finalized())).unwrap();
+        client.set_commitment(CommitmentConfig::processed());
+        client.get_slot(None).unwrap();
+
+        let requests = server.get_received_requests();
+        assert_eq!(json!([{ "commitment": "confirmed" }]), requests[0]["params"]);
+        assert_eq!(json!([{ "commitment": "finalized" }]), requests[1]["params"]);
+        assert_eq!(json!([{ "commitment": "processed" }]), requests[2]["params"]);
+    }
+
+    #[test]
+    fn test_get_progress_sample_uses_one_round_trip() {
+        let (server, client) = start_client(|method, _| {
+            return match method {
+                "getSlot" => Ok(json!(100)),
+                "getTransactionCount" => Ok(json!(2000)),
+                "getEpochInfo" => Ok(json!({
+                    "absoluteSlot": 100,
+                    "blockHeight": 98,
+                    "epoch": 0,
+                    "slotIndex": 100,
+                    "slotsInEpoch": 8192,
+                })),
+                _ => panic!("Unexpected method '{}'", method),
+            };
+        });
+        let sample = client.get_progress_sample(None).unwrap();
+        assert_eq!(100, sample.slot);
+        assert_eq!(2000, sample.transaction_count);
+        assert_eq!(98, sample.epoch_info.block_height);
+
+        let requests = server.get_received_requests();
+        assert_eq!(1, requests.len());
+        let ids: Vec<&Value> = requests[0].as_array().unwrap().iter().map(|entry| &entry["id"]).collect();
+        assert_eq!(3, ids.len());
+        assert!(ids[0] != ids[1] && ids[1] != ids[2] && ids[0] != ids[2]);
+    }
+
+    #[test]
+    fn test_send_batch_reports_per_entry_errors() {
+        let (_server, client) = start_client(|_, params| {
+            return match params[0]["commitment"].as_str() {
+                Some("finalized") => Err(json!({ "code": -32005, "message": "Node is unhealthy" })),
+                _ => Ok(json!(7)),
+            };
+        });
+        let requests = vec![
+            (RpcRequest::GetSlot, json!([CommitmentConfig::processed()])),
+            (RpcRequest::GetSlot, json!([CommitmentConfig::finalized()])),
+            (RpcRequest::GetSlot, json!([CommitmentConfig::confirmed()])),
+        ];
+        let results = client.send_batch(requests).unwrap();
+        assert_eq!(json!(7), *results[0].as_ref().unwrap());
+        assert!(results[1].is_err());
+        assert_eq!(json!(7), *results[2].as_ref().unwrap());
+    }
+
+    #[test]
+    fn test_request_airdrop() {
+        let (server, client) = start_client(|method, params| {
+            assert_eq!("requestAirdrop", method);
+            assert_eq!("9QzsJf7LPLj8GkXbYT3LFDKqsj2hHG7TA3xinJHu8epQ", params[0]);
+            assert_eq!(1_000_000, params[1]);
+            return Ok(json!("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW"));
+        });
+        let signature = client.request_airdrop("9QzsJf7LPLj8GkXbYT3LFDKqsj2hHG7TA3xinJHu8epQ", 1_000_000, Some(CommitmentConfig::finalized())).unwrap();
+        assert_eq!("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW", signature);
+        assert_eq!("finalized", server.get_received_requests()[0]["params"][2]["commitment"]);
+    }
+
+    #[test]
+    fn test_send_transaction_encodes_base64() {
+        let (server, client) = start_client(|_, params| {
+            assert_eq!("AQID", params[0]);
+            assert_eq!("base64", params[1]["encoding"]);
+            return Ok(json!("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW"));
+        });
+        let signature = client.send_transaction(&[1, 2, 3]).unwrap();
+        assert_eq!("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW", signature);
+        assert_eq!("sendTransaction", server.get_received_requests()[0]["method"]);
+    }
+
+    #[test]
+    fn test_rpc_error_is_returned() {
+        let (_server, client) = start_client(|_, _| {
+            return Err(json!({ "code": -32005, "message": "Node is behind by 42 slots", "data": { "numSlotsBehind": 42 } }));
+        });
+        client.set_retry_policy(NO_RETRY_POLICY);
+        let err = client.get_health().unwrap_err();
+        let rpc_err = RpcError::find(&err).expect("RPC error in chain");
+        assert!(rpc_err.is_node_unhealthy());
+        assert_eq!(Some(42), rpc_err.get_num_slots_behind());
+        assert!(!rpc_err.is_transport_failure());
+    }
+
+    #[test]
+    fn test_preflight_failure_is_classified() {
+        let (_server, client) = start_client(|_, _| {
+            return Err(json!({
+                "code": -32002,
+                "message": "Transaction simulation failed: Blockhash not found",
+                "data": { "err": "BlockhashNotFound", "logs": [] },
+            }));
+        });
+        let err = client.send_transaction(&[1, 2, 3]).unwrap_err();
+        let rpc_err = RpcError::find(&err).expect("RPC error in chain");
+        assert!(rpc_err.is_preflight_failure());
+        assert!(rpc_err.is_blockhash_not_found());
+        assert!(!rpc_err.is_node_unhealthy());
+    }
+
+    #[test]
+    fn test_transport_and_decode_errors_are_classified() {
+        // Nothing listens on the port once the listener is dropped, so the connection gets refused
+        let unused_addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let client = RpcClient::new(format!("http://{}", unused_addr), CommitmentConfig::confirmed());
+        client.set_retry_policy(NO_RETRY_POLICY);
+        let err = client.get_slot(None).unwrap_err();
+        assert!(RpcError::find(&err).expect("RPC error in chain").is_transport_failure());
+
+        let (_server, client) = start_client(|_, _| Ok(json!("not a slot")));
+        let err = client.get_slot(None).unwrap_err();
+        match RpcError::find(&err) {
+            Some(RpcError::Decode(_)) => {},
+            other => panic!("Expected a decode error but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_signature_statuses_rejects_too_many_signatures() {
+        let (server, client) = start_client(|_, _| Ok(Value::Null));
+        let signature = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+        let signatures = vec![signature; super::MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS + 1];
+        assert!(client.get_signature_statuses(&signatures, false).is_err());
+        assert!(server.get_received_requests().is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use reqwest::StatusCode;
+use serde_json::Value;
+use thiserror::Error;
+
+use super::rpc_response::RpcSimulateTransactionResult;
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+The error codes in this file are copied from https://github.com/solana-labs/solana/blob/master/client/src/rpc_custom_error.rs
+because solana-client provides Ledger support, which means it has a dependency
+on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana client here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+// Solana-specific error codes
+pub const JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE: i64 = -32002;
+pub const JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY: i64 = -32005;
+
+// The transaction error that a node reports when a transaction references a blockhash it doesn't know about
+const BLOCKHASH_NOT_FOUND_TRANSACTION_ERROR: &str = "BlockhashNotFound";
+const BLOCKHASH_NOT_FOUND_MESSAGE_FRAGMENT: &str = "Blockhash not found";
+
+// The ways that an RPC call can fail
+// These get returned wrapped in anyhow errors, so callers that need to tell failures apart should use RpcError::find
+// to dig the RpcError back out of the error chain
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RpcError {
+    // The node received the request and answered with a JSON-RPC error object
+    #[error("The node returned JSON-RPC error {code}: {message}")]
+    RpcResponseError {
+        code: i64,
+        message: String,
+        data: Option<Value>,
+    },
+
+    // The node answered with a non-success HTTP status
+    #[error("The node responded with HTTP status {0}")]
+    HttpStatus(StatusCode),
+
+    // The request didn't complete within the client's timeout
+    #[error("The request to the node timed out: {0}")]
+    Timeout(String),
+
+    // The request couldn't be delivered, or the response couldn't be read (e.g. connection refused)
+    #[error("An error occurred communicating with the node: {0}")]
+    Transport(String),
+
+    // The node answered, but with something that isn't the JSON we expected
+    #[error("An error occurred decoding the node's response: {0}")]
+    Decode(String),
+}
+
+impl RpcError {
+    // Returns the first RpcError in the given error's chain of causes, if any
+    pub fn find(err: &anyhow::Error) -> Option<&RpcError> {
+        return err.chain().find_map(|cause| cause.downcast_ref::<RpcError>());
+    }
+
+    // Builds an RpcError from a JSON-RPC 'error' object
+    pub fn from_error_object(error: &Value) -> RpcError {
+        let code = error["code"].as_i64().unwrap_or_default();
+        let message = error["message"].as_str().unwrap_or_default().to_owned();
+        let data = match &error["data"] {
+            Value::Null => None,
+            other => Some(other.clone()),
+        };
+        return RpcError::RpcResponseError{
+            code,
+            message,
+            data,
+        };
+    }
+
+    pub fn from_reqwest_error(err: reqwest::Error) -> RpcError {
+        if err.is_timeout() {
+            return RpcError::Timeout(err.to_string());
+        }
+        if let Some(status) = err.status() {
+            return RpcError::HttpStatus(status);
+        }
+        if err.is_decode() {
+            return RpcError::Decode(err.to_string());
+        }
+        return RpcError::Transport(err.to_string());
+    }
+
+    // Returns the JSON-RPC error code, if the node answered with a JSON-RPC error
+    pub fn get_code(&self) -> Option<i64> {
+        return match self {
+            RpcError::RpcResponseError{ code, .. } => Some(*code),
+            _ => None,
+        };
+    }
+
+    // True if the node refused the request because it's too far behind the cluster
+    pub fn is_node_unhealthy(&self) -> bool {
+        return self.get_code() == Some(JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY);
+    }
+
+    // How far behind the cluster an unhealthy node says it is, if it said
+    pub fn get_num_slots_behind(&self) -> Option<u64> {
+        if !self.is_node_unhealthy() {
+            return None;
+        }
+        return match self {
+            RpcError::RpcResponseError{ data: Some(data), .. } => data["numSlotsBehind"].as_u64(),
+            _ => None,
+        };
+    }
+
+    // True if the node rejected a sent transaction because simulating it failed
+    #[allow(dead_code)]
+    pub fn is_preflight_failure(&self) -> bool {
+        return self.get_code() == Some(JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE);
+    }
+
+    // The result of the failed simulation, if this is a preflight failure
+    #[allow(dead_code)]
+    pub fn get_simulation_result(&self) -> Option<RpcSimulateTransactionResult> {
+        if !self.is_preflight_failure() {
+            return None;
+        }
+        return match self {
+            RpcError::RpcResponseError{ data: Some(data), .. } => serde_json::from_value(data.clone()).ok(),
+            _ => None,
+        };
+    }
+
+    // True if the node rejected a transaction because it doesn't know the transaction's recent blockhash
+    #[allow(dead_code)]
+    pub fn is_blockhash_not_found(&self) -> bool {
+        if let Some(simulation_result) = self.get_simulation_result() {
+            if simulation_result.err == Some(Value::String(BLOCKHASH_NOT_FOUND_TRANSACTION_ERROR.to_owned())) {
+                return true;
+            }
+        }
+        return match self {
+            RpcError::RpcResponseError{ message, .. } => message.contains(BLOCKHASH_NOT_FOUND_MESSAGE_FRAGMENT),
+            _ => false,
+        };
+    }
+
+    // True if the request never got a JSON-RPC answer from the node (e.g. it timed out or couldn't connect)
+    #[allow(dead_code)]
+    pub fn is_transport_failure(&self) -> bool {
+        return matches!(self, RpcError::Timeout(_) | RpcError::Transport(_) | RpcError::HttpStatus(_));
+    }
+}
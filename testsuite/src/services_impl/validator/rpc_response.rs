@@ -0,0 +1,337 @@
+use std::{collections::HashMap, net::SocketAddr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::commitment_config::{CommitmentConfig, CommitmentLevel};
+
+/*
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+This entire file is copied from https://github.com/solana-labs/solana/blob/master/client/src/rpc_response.rs
+(plus the handful of SDK types that the responses embed) because solana-client provides Ledger support,
+which means it has a dependency on the 'hidapi' kernel module. This module won't compile under Docker-for-Mac
+due to https://github.com/docker/for-mac/issues/5295
+Therefore, we reimplement parts of the Solana client here as a hackaround
+NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
+*/
+
+pub type RpcLeaderSchedule = HashMap<String, Vec<usize>>;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcResponseContext {
+    pub slot: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response<T> {
+    pub context: RpcResponseContext,
+    pub value: T,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochInfo {
+    pub epoch: u64,
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+    pub absolute_slot: u64,
+    pub block_height: u64,
+    pub transaction_count: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochSchedule {
+    pub slots_per_epoch: u64,
+    pub leader_schedule_slot_offset: u64,
+    pub warmup: bool,
+    pub first_normal_epoch: u64,
+    pub first_normal_slot: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeCalculator {
+    pub lamports_per_signature: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRateGovernor {
+    pub lamports_per_signature: u64,
+    pub target_lamports_per_signature: u64,
+    pub target_signatures_per_slot: u64,
+    pub min_lamports_per_signature: u64,
+    pub max_lamports_per_signature: u64,
+    pub burn_percent: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockhashFeeCalculator {
+    pub blockhash: String,
+    pub fee_calculator: FeeCalculator,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFees {
+    pub blockhash: String,
+    pub fee_calculator: FeeCalculator,
+    pub last_valid_slot: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeeCalculator {
+    pub fee_calculator: FeeCalculator,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeeRateGovernor {
+    pub fee_rate_governor: FeeRateGovernor,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInflationGovernor {
+    pub initial: f64,
+    pub terminal: f64,
+    pub taper: f64,
+    pub foundation: f64,
+    pub foundation_term: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInflationRate {
+    pub total: f64,
+    pub validator: f64,
+    pub foundation: f64,
+    pub epoch: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcKeyedAccount {
+    pub pubkey: String,
+    pub account: UiAccount,
+}
+
+// The 'data' field is left as a raw JSON value because its shape depends on the encoding that was requested
+// (e.g. ["<base64 string>", "base64"] vs a JSON-parsed object)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAccount {
+    pub lamports: u64,
+    pub data: Value,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTokenAmount {
+    pub ui_amount: Option<f64>,
+    pub decimals: u8,
+    pub amount: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContactInfo {
+    pub pubkey: String,
+    pub gossip: Option<SocketAddr>,
+    pub tpu: Option<SocketAddr>,
+    pub rpc: Option<SocketAddr>,
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RpcVersionInfo {
+    pub solana_core: String,
+    pub feature_set: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcIdentity {
+    pub identity: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVoteAccountStatus {
+    pub current: Vec<RpcVoteAccountInfo>,
+    pub delinquent: Vec<RpcVoteAccountInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVoteAccountInfo {
+    pub vote_pubkey: String,
+    pub node_pubkey: String,
+    pub activated_stake: u64,
+    pub commission: u8,
+    pub epoch_vote_account: bool,
+    pub epoch_credits: Vec<(u64, u64, u64)>,
+    pub last_vote: u64,
+    pub root_slot: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSupply {
+    pub total: u64,
+    pub circulating: u64,
+    pub non_circulating: u64,
+    pub non_circulating_accounts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountBalance {
+    pub address: String,
+    pub lamports: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcStorageTurn {
+    pub blockhash: String,
+    pub slot: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateTransactionResult {
+    pub err: Option<Value>,
+    pub logs: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConfirmedTransactionStatusWithSignature {
+    pub signature: String,
+    pub slot: u64,
+    pub err: Option<Value>,
+    pub memo: Option<String>,
+    pub block_time: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionConfirmationStatus {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatus {
+    pub slot: u64,
+    pub confirmations: Option<usize>,  // None = rooted
+    pub err: Option<Value>,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+}
+
+impl TransactionStatus {
+    // True if the transaction has reached (at least) the given commitment level
+    pub fn satisfies_commitment(&self, commitment_config: CommitmentConfig) -> bool {
+        return match commitment_config.commitment {
+            CommitmentLevel::Finalized => self.confirmations.is_none(),
+            CommitmentLevel::Confirmed => match &self.confirmation_status {
+                Some(status) => *status != TransactionConfirmationStatus::Processed,
+                // Nodes that don't report a confirmation status count confirmations instead
+                None => !matches!(self.confirmations, Some(confirmations) if confirmations <= 1),
+            },
+            CommitmentLevel::Processed => true,
+        };
+    }
+}
+
+// Transactions and rewards are left as raw JSON values; the tests only ever look at the block metadata
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiConfirmedBlock {
+    pub previous_blockhash: String,
+    pub blockhash: String,
+    pub parent_slot: u64,
+    pub transactions: Vec<Value>,
+    pub rewards: Vec<Value>,
+    pub block_time: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedConfirmedTransaction {
+    pub slot: u64,
+    pub transaction: Value,
+    pub meta: Option<Value>,
+    pub block_time: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotInfo {
+    pub slot: u64,
+    pub parent: u64,
+    pub root: u64,
+}
+
+// A 'None' err means the transaction succeeded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSignatureResult {
+    pub err: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLogsResponse {
+    pub signature: String,
+    pub err: Option<Value>,
+    pub logs: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services_impl::validator::commitment_config::CommitmentConfig;
+
+    use super::{TransactionConfirmationStatus, TransactionStatus};
+
+    fn status(confirmations: Option<usize>, confirmation_status: Option<TransactionConfirmationStatus>) -> TransactionStatus {
+        return TransactionStatus{
+            slot: 1,
+            confirmations,
+            err: None,
+            confirmation_status,
+        };
+    }
+
+    #[test]
+    fn test_satisfies_commitment() {
+        let processed = status(Some(0), Some(TransactionConfirmationStatus::Processed));
+        assert!(processed.satisfies_commitment(CommitmentConfig::processed()));
+        assert!(!processed.satisfies_commitment(CommitmentConfig::confirmed()));
+        assert!(!processed.satisfies_commitment(CommitmentConfig::finalized()));
+
+        let confirmed = status(Some(5), Some(TransactionConfirmationStatus::Confirmed));
+        assert!(confirmed.satisfies_commitment(CommitmentConfig::confirmed()));
+        assert!(!confirmed.satisfies_commitment(CommitmentConfig::finalized()));
+
+        let finalized = status(None, Some(TransactionConfirmationStatus::Finalized));
+        assert!(finalized.satisfies_commitment(CommitmentConfig::confirmed()));
+        assert!(finalized.satisfies_commitment(CommitmentConfig::finalized()));
+
+        // Older nodes only report the confirmation count
+        assert!(!status(Some(1), None).satisfies_commitment(CommitmentConfig::confirmed()));
+        assert!(status(Some(2), None).satisfies_commitment(CommitmentConfig::confirmed()));
+    }
+}
@@ -14,4 +14,8 @@ NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE NOTE
 
 pub trait RpcSender {
     fn send(&self, request: RpcRequest, params: serde_json::Value) -> Result<serde_json::Value>;
+
+    // Sends all the requests in a single JSON-RPC batch, returning one result per request in the same order as the
+    // requests; the outer error is only for failures that affect the entire batch (e.g. the node being unreachable)
+    fn send_batch(&self, requests: Vec<(RpcRequest, serde_json::Value)>) -> Result<Vec<Result<serde_json::Value>>>;
 }
\ No newline at end of file
@@ -3,9 +3,9 @@ use std::{borrow::BorrowMut, collections::{HashMap, HashSet}, fs::File, io::Writ
 
 use kurtosis_rust_lib::services::{docker_container_initializer::DockerContainerInitializer, service_context::ServiceContext};
 
-use crate::services_impl::faucet::faucet_service::FaucetService;
+use crate::{sdk_impl::keypair::Keypair, services_impl::faucet::faucet_service::FaucetService};
 
-use super::validator_service::{INIT_COMPLETE_FILEPATH, GOSSIP_PORT, RPC_PORT, ValidatorService};
+use super::validator_service::{INIT_COMPLETE_FILEPATH, GOSSIP_PORT, PUBSUB_PORT, RPC_PORT, ValidatorService};
 
 const VALIDATOR_BIN_FILEPATH: &str = "/usr/bin/solana-validator";
 
@@ -19,8 +19,15 @@ pub (super) const TEST_VOLUME_MOUNTPOINT: &str = "/test-volume";
 
 const SKIP_CORRUPTED_RECORD_RECOVERY_MODE: &str = "skip_any_corrupted_record";
 
-// Where to mount the ledger directory on the validator container
-const LEDGER_DIR_MOUNTPOINT: &str = "/ledger";
+// Where to mount the genesis ledger directory on the validator container; bootstrappers copy it into their own ledger
+// directory the first time they start
+const GENESIS_LEDGER_DIR_MOUNTPOINT: &str = "/ledger";
+
+// Each node keeps its ledger in a directory on the test volume named after its identity, so that the ledger outlives the
+// node's container and a node that gets restarted picks up where it left off
+// Joiners don't start from the genesis ledger artifact; instead they fetch genesis and a snapshot from their entrypoint
+// into their (initially nonexistent) ledger directory
+const LEDGERS_DIRNAME: &str = "ledgers";
 
 // Every validator can potentially run the wallet sanity check, which means they need the faucet keypair
 // Thus, we write the faucet keypair to every validator's filesystem in preparation
@@ -29,68 +36,122 @@ pub (super) const FAUCET_KEYPAIR_FILEPATH: &str = "/faucet-keypair.json";
 enum ValidatorType {
     FirstBootstrapper,
     ExtraBootstrapper,
+    // A validator that isn't in genesis, and so joins the cluster after it's already running
+    Joiner,
+    // A non-voting node that joins like a joiner and serves the full RPC API, the way clients see the cluster
+    RpcNode,
+}
+
+// The genesis ledger that a bootstrapper starts from, and what the bootstrapper should expect the cluster to look like
+pub struct BootstrapperLedgerOptions {
+    pub expected_bank_hash: String,
+    pub expected_genesis_hash: String,
+    pub expected_shred_version: u64,
+    pub ledger_dir_artifact_key: String,
 }
 
 pub struct ValidatorContainerInitializer {
 	docker_image: String,
-    expected_bank_hash: String,
+    expected_bank_hash: Option<String>,  // Only filled in for bootstrappers, which wait for supermajority at genesis
     expected_genesis_hash: String,
     expected_shred_version: u64,
-    ledger_dir_artifact_key: String,
+    ledger_dir_artifact_key: Option<String>,  // Only filled in for bootstrappers, which start from the genesis ledger
     validator_type: ValidatorType,
-    identity_keypair_json: String,
-    vote_account_keypair_json: String,
+    identity_keypair: Keypair,
+    vote_account_keypair: Option<Keypair>,  // Not filled in for RPC nodes, which don't vote
     faucet: Rc<FaucetService>,
-    first_bootstrapper: Option<Rc<ValidatorService>>,  // Only filled in for extra bootstrappers
+    entrypoint: Option<Rc<ValidatorService>>,  // Filled in for everything except the first bootstrapper, unless it's being restarted
 }
 
 impl<'obj> ValidatorContainerInitializer {
     pub fn for_first_bootstrapper(
         docker_image: String,
-        expected_bank_hash: String,
+        ledger_options: BootstrapperLedgerOptions,
+        identity_keypair: Keypair,
+        vote_account_keypair: Keypair,
+        faucet: Rc<FaucetService>,
+        // Only needed when the first bootstrapper is restarted, to find the rest of the cluster again
+        entrypoint: Option<Rc<ValidatorService>>,
+    ) -> ValidatorContainerInitializer {
+        return ValidatorContainerInitializer{
+            docker_image,
+            expected_bank_hash: Some(ledger_options.expected_bank_hash),
+            expected_genesis_hash: ledger_options.expected_genesis_hash,
+            expected_shred_version: ledger_options.expected_shred_version,
+            ledger_dir_artifact_key: Some(ledger_options.ledger_dir_artifact_key),
+            validator_type: ValidatorType::FirstBootstrapper,
+            identity_keypair,
+            vote_account_keypair: Some(vote_account_keypair),
+            entrypoint,
+            faucet: faucet,
+        }
+    }
+
+    pub fn for_extra_bootstrapper(
+        docker_image: String,
+        ledger_options: BootstrapperLedgerOptions,
+        identity_keypair: Keypair,
+        vote_account_keypair: Keypair,
+        faucet: Rc<FaucetService>,
+        bootstrapper: Rc<ValidatorService>,
+    ) -> ValidatorContainerInitializer {
+        return ValidatorContainerInitializer{
+            docker_image,
+            expected_bank_hash: Some(ledger_options.expected_bank_hash),
+            expected_genesis_hash: ledger_options.expected_genesis_hash,
+            expected_shred_version: ledger_options.expected_shred_version,
+            ledger_dir_artifact_key: Some(ledger_options.ledger_dir_artifact_key),
+            validator_type: ValidatorType::ExtraBootstrapper,
+            identity_keypair,
+            vote_account_keypair: Some(vote_account_keypair),
+            entrypoint: Some(bootstrapper),
+            faucet: faucet,
+        }
+    }
+
+    // The joiner's vote account must already exist on the cluster, funded and authorized to the identity
+    pub fn for_joiner(
+        docker_image: String,
         expected_genesis_hash: String,
         expected_shred_version: u64,
-        ledger_dir_artifact_key: String,
-        identity_keypair_json: String,
-        vote_account_keypair_json: String,
+        identity_keypair: Keypair,
+        vote_account_keypair: Keypair,
         faucet: Rc<FaucetService>,
+        entrypoint: Rc<ValidatorService>,
     ) -> ValidatorContainerInitializer {
         return ValidatorContainerInitializer{
             docker_image,
-            expected_bank_hash,
+            expected_bank_hash: None,
             expected_genesis_hash,
             expected_shred_version,
-            ledger_dir_artifact_key,
-            validator_type: ValidatorType::FirstBootstrapper,
-            identity_keypair_json,
-            vote_account_keypair_json,
-            first_bootstrapper: None,
-            faucet: faucet,
+            ledger_dir_artifact_key: None,
+            validator_type: ValidatorType::Joiner,
+            identity_keypair,
+            vote_account_keypair: Some(vote_account_keypair),
+            entrypoint: Some(entrypoint),
+            faucet,
         }
     }
 
-    pub fn for_extra_bootstrapper(
+    pub fn for_rpc_node(
         docker_image: String,
-        expected_bank_hash: String,
         expected_genesis_hash: String,
         expected_shred_version: u64,
-        ledger_dir_artifact_key: String,
-        identity_keypair_json: String,
-        vote_account_keypair_json: String,
+        identity_keypair: Keypair,
         faucet: Rc<FaucetService>,
-        bootstrapper: Rc<ValidatorService>,
+        entrypoint: Rc<ValidatorService>,
     ) -> ValidatorContainerInitializer {
         return ValidatorContainerInitializer{
             docker_image,
-            expected_bank_hash,
+            expected_bank_hash: None,
             expected_genesis_hash,
             expected_shred_version,
-            ledger_dir_artifact_key,
-            validator_type: ValidatorType::ExtraBootstrapper,
-            identity_keypair_json,
-            vote_account_keypair_json,
-            first_bootstrapper: Some(bootstrapper),
-            faucet: faucet,
+            ledger_dir_artifact_key: None,
+            validator_type: ValidatorType::RpcNode,
+            identity_keypair,
+            vote_account_keypair: None,
+            entrypoint: Some(entrypoint),
+            faucet,
         }
     }
 }
@@ -103,6 +164,7 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
     fn get_used_ports(&self) -> std::collections::HashSet<String> {
         let mut result = HashSet::new();
         result.insert(format!("{}/tcp", RPC_PORT));
+        result.insert(format!("{}/tcp", PUBSUB_PORT));
         result.insert(format!("{}/udp", GOSSIP_PORT));
         for port in PORT_RANGE_FOR_GOSSIP_START..PORT_RANGE_FOR_GOSSIP_END {
             result.insert(format!("{}/udp", port));
@@ -118,7 +180,9 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
     fn get_files_to_generate(&self) -> std::collections::HashSet<String> {
         let mut result = HashSet::new();
         result.insert(String::from(IDENTITY_FILE_KEY));
-        result.insert(String::from(VOTE_ACCOUNT_FILE_KEY));
+        if self.vote_account_keypair.is_some() {
+            result.insert(String::from(VOTE_ACCOUNT_FILE_KEY));
+        }
         return result;
     }
 
@@ -126,9 +190,11 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
         for (file_key, mut fp) in generated_files {
             let file_contents;
             if file_key == IDENTITY_FILE_KEY {
-                file_contents = self.identity_keypair_json.clone();
+                file_contents = self.identity_keypair.to_json();
             } else if file_key == VOTE_ACCOUNT_FILE_KEY {
-                file_contents = self.vote_account_keypair_json.clone();
+                let vote_account_keypair = self.vote_account_keypair.as_ref()
+                    .context("Got a vote account file to initialize, but this validator has no vote account keypair")?;
+                file_contents = vote_account_keypair.to_json();
             } else {
                 return Err(anyhow!(
                     "Unrecognized file key '{}'",
@@ -143,10 +209,12 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
 
     fn get_files_artifact_mountpoints(&self) -> HashMap<String, String> {
         let mut result: HashMap<String, String> = HashMap::new();
-        result.insert(
-            self.ledger_dir_artifact_key.clone(),
-            LEDGER_DIR_MOUNTPOINT.to_owned(),
-        );
+        if let Some(ledger_dir_artifact_key) = &self.ledger_dir_artifact_key {
+            result.insert(
+                ledger_dir_artifact_key.clone(),
+                GENESIS_LEDGER_DIR_MOUNTPOINT.to_owned(),
+            );
+        }
         return result;
     }
 
@@ -163,10 +231,6 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
             .context(format!("Could not find file key '{}' in the generated filepaths map, even though we expected it", IDENTITY_FILE_KEY))?
             .to_str()
             .context(format!("Could not get path string representation of {}", IDENTITY_FILE_KEY))?;
-        let vote_account_filepath = generated_file_filepaths.get(VOTE_ACCOUNT_FILE_KEY)
-            .context(format!("Could not find file key '{}' in the generated filepaths map, even though we expected it", VOTE_ACCOUNT_FILE_KEY))?
-            .to_str()
-            .context(format!("Could not get path string representation of {}", VOTE_ACCOUNT_FILE_KEY))?;
 
         // We need to override the ENTRYPOINT because the Solana image has an ENTRYPOINT we don't want
         let entrypoint_args = vec![
@@ -177,11 +241,38 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
         let mut cmd_fragments: Vec<String> = vec![
             // Write the faucet keypair to every validator's filesystem
             String::from("echo"),
-            self.faucet.get_keypair_json(),
+            self.faucet.get_keypair().to_json(),
             String::from(">"),
             FAUCET_KEYPAIR_FILEPATH.to_owned(),
             String::from("&&"),
-
+        ];
+        let ledger_dirpath = format!("{}/{}/{}", TEST_VOLUME_MOUNTPOINT, LEDGERS_DIRNAME, self.identity_keypair.pubkey());
+        match self.validator_type {
+            ValidatorType::FirstBootstrapper | ValidatorType::ExtraBootstrapper => {
+                // Only copied the first time, so that a restarted bootstrapper keeps the slots it had already processed
+                cmd_fragments.append(vec![
+                    String::from("mkdir"),
+                    String::from("-p"),
+                    format!("{}/{}", TEST_VOLUME_MOUNTPOINT, LEDGERS_DIRNAME),
+                    String::from("&&"),
+                    String::from("{"),
+                    String::from("["),
+                    String::from("-d"),
+                    ledger_dirpath.clone(),
+                    String::from("]"),
+                    String::from("||"),
+                    String::from("cp"),
+                    String::from("-r"),
+                    GENESIS_LEDGER_DIR_MOUNTPOINT.to_owned(),
+                    ledger_dirpath.clone(),
+                    String::from(";"),
+                    String::from("}"),
+                    String::from("&&"),
+                ].borrow_mut());
+            },
+            ValidatorType::Joiner | ValidatorType::RpcNode => {},
+        }
+        cmd_fragments.append(vec![
             String::from(VALIDATOR_BIN_FILEPATH),
             String::from("--rpc-port"),
             RPC_PORT.to_string(),
@@ -193,20 +284,10 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
             ip_addr.to_owned(),
             String::from("--identity"),
             identity_filepath.to_owned(),
-            String::from("--vote-account"),
-            vote_account_filepath.to_owned(),
             String::from("--gossip-port"),
             GOSSIP_PORT.to_string(),
             String::from("--wal-recovery-mode"),
             SKIP_CORRUPTED_RECORD_RECOVERY_MODE.to_owned(),
-            // This tells the nodes to wait until both are visible in gossip before they start producing blocks
-            // With the stake evenly distributed between the two nodes, neither node will be able to successfully 
-            // build any blocks because we'd normally need 66.6% of the network to vote on a block
-            String::from("--wait-for-supermajority"),
-            String::from("0"),
-            // Whenever wait-for-supermajority is specified, expected-bank-hash is required
-            String::from("--expected-bank-hash"),
-            self.expected_bank_hash.clone(),
             String::from("--expected-genesis-hash"),
             self.expected_genesis_hash.clone(),
             String::from("--expected-shred-version"),
@@ -218,11 +299,41 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
             String::from("--no-poh-speed-test"),
             String::from("--init-complete-file"),
             String::from(INIT_COMPLETE_FILEPATH),
-            String::from("--ledger"), 
-            LEDGER_DIR_MOUNTPOINT.to_owned(),
             String::from("--log"), 
             String::from("-"),
-        ];
+            String::from("--ledger"),
+            ledger_dirpath,
+        ].borrow_mut());
+        if self.vote_account_keypair.is_some() {
+            let vote_account_filepath = generated_file_filepaths.get(VOTE_ACCOUNT_FILE_KEY)
+                .context(format!("Could not find file key '{}' in the generated filepaths map, even though we expected it", VOTE_ACCOUNT_FILE_KEY))?
+                .to_str()
+                .context(format!("Could not get path string representation of {}", VOTE_ACCOUNT_FILE_KEY))?;
+            cmd_fragments.append(vec![
+                String::from("--vote-account"),
+                vote_account_filepath.to_owned(),
+            ].borrow_mut());
+        } else {
+            cmd_fragments.push(String::from("--no-voting"));
+        }
+        match self.validator_type {
+            ValidatorType::FirstBootstrapper | ValidatorType::ExtraBootstrapper => {
+                let expected_bank_hash = self.expected_bank_hash.as_ref()
+                    .context("Bootstrappers require an expected bank hash, but none was found")?;
+                cmd_fragments.append(vec![
+                    // This tells the nodes to wait until both are visible in gossip before they start producing blocks
+                    // With the stake evenly distributed between the two nodes, neither node will be able to successfully 
+                    // build any blocks because we'd normally need 66.6% of the network to vote on a block
+                    // A restarted bootstrapper's ledger is already past slot 0, so it doesn't wait again
+                    String::from("--wait-for-supermajority"),
+                    String::from("0"),
+                    // Whenever wait-for-supermajority is specified, expected-bank-hash is required
+                    String::from("--expected-bank-hash"),
+                    expected_bank_hash.clone(),
+                ].borrow_mut());
+            },
+            ValidatorType::Joiner | ValidatorType::RpcNode => {},
+        }
         match self.validator_type {
             ValidatorType::FirstBootstrapper => {
                 let faucet_url = format!("{}:{}", self.faucet.get_ip_address(), self.faucet.get_port());
@@ -230,9 +341,18 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
                     String::from("--rpc-faucet-address"), 
                     faucet_url,
                 ].borrow_mut());
+                if let Some(entrypoint) = &self.entrypoint {
+                    let entrypoint_gossip_url = format!("{}:{}", entrypoint.get_ip_address(), GOSSIP_PORT);
+                    cmd_fragments.append(vec![
+                        String::from("--entrypoint"), 
+                        entrypoint_gossip_url,
+                        String::from("--no-snapshot-fetch"), // Picks up from its own ledger instead
+                        String::from("--no-genesis-fetch"),
+                    ].borrow_mut());
+                }
             },
             ValidatorType::ExtraBootstrapper => {
-                let bootstrapper = self.first_bootstrapper.as_ref()
+                let bootstrapper = self.entrypoint.as_ref()
                     .context("Extra bootstrapper requires a first bootstrapper, but no bootstrapper was found")?;
                 let bootstrap_gossip_url = format!("{}:{}", bootstrapper.get_ip_address(), GOSSIP_PORT);
                 cmd_fragments.append(vec![
@@ -242,6 +362,31 @@ impl DockerContainerInitializer<ValidatorService> for ValidatorContainerInitiali
                     String::from("--no-genesis-fetch"), // Doesn't need to fetch genesis because it already has it
                 ].borrow_mut());
             },
+            ValidatorType::Joiner => {
+                let entrypoint = self.entrypoint.as_ref()
+                    .context("Joiner requires an entrypoint, but no entrypoint was found")?;
+                let entrypoint_gossip_url = format!("{}:{}", entrypoint.get_ip_address(), GOSSIP_PORT);
+                // Genesis and a snapshot get fetched from the entrypoint, and then the joiner catches up from there
+                cmd_fragments.append(vec![
+                    String::from("--entrypoint"), 
+                    entrypoint_gossip_url,
+                ].borrow_mut());
+            },
+            ValidatorType::RpcNode => {
+                let entrypoint = self.entrypoint.as_ref()
+                    .context("RPC node requires an entrypoint, but no entrypoint was found")?;
+                let entrypoint_gossip_url = format!("{}:{}", entrypoint.get_ip_address(), GOSSIP_PORT);
+                let faucet_url = format!("{}:{}", self.faucet.get_ip_address(), self.faucet.get_port());
+                cmd_fragments.append(vec![
+                    String::from("--entrypoint"), 
+                    entrypoint_gossip_url,
+                    // Clients go through RPC nodes for everything, so they need the full history & airdrops too
+                    String::from("--enable-rpc-transaction-history"),
+                    String::from("--enable-cpi-and-log-storage"),
+                    String::from("--rpc-faucet-address"), 
+                    faucet_url,
+                ].borrow_mut());
+            },
         }
 
         cmd_fragments.append(vec![
@@ -1,13 +1,16 @@
-use std::{borrow::BorrowMut, time::{SystemTime, UNIX_EPOCH}};
+use std::{borrow::BorrowMut, cell::RefCell, collections::HashMap, thread::sleep, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
 
 use anyhow::{anyhow, Context, Result};
 use kurtosis_rust_lib::services::{service::Service, service_context::ServiceContext};
-use serde_json::{Value, json};
+
+use crate::sdk_impl::{hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction};
 
 use super::validator_container_initializer::FAUCET_KEYPAIR_FILEPATH;
-use super::{http_sender::HttpSender, rpc_request::RpcRequest, rpc_sender::RpcSender};
+use super::{async_rpc_client::AsyncRpcClient, pubsub_client::PubsubClient, commitment_config::{CommitmentConfig, CommitmentLevel}, readiness::{DEFAULT_READINESS_TIMEOUTS, ReadinessTracker}, retry_policy::{NO_RETRY_POLICY, RetryPolicy}, rpc_client::{ProgressSample, RpcClient}, rpc_request::MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, rpc_response::{RpcVersionInfo, TransactionStatus}};
 
 pub (super) const RPC_PORT: u32 = 8899;
+// The validator always serves PubSub websockets on the port after the RPC port
+pub (super) const PUBSUB_PORT: u32 = RPC_PORT + 1;
 pub (super) const GOSSIP_PORT: u32 = 8001;
 const GOSSIP_CLI_GOSSIP_PORT: u32 = 9000;
 
@@ -17,23 +20,54 @@ const SOLANA_BINARIES_DIRPATH: &str = "/usr/bin";
 const SOLANA_CLI_BIN_FILENAME: &str = "solana";
 const SOLANA_KEYGEN_BIN_FILENAME: &str = "solana-keygen";
 const SOLANA_GOSSIP_BIN_FILENAME: &str = "solana-gossip";
+const SOLANA_VALIDATOR_BIN_FILENAME: &str = "solana-validator";
 
-const COMMITMENT_LEVEL_PARAM: &str = "commitment";
-const CONFIRMED_COMMMITMENT_LEVEL: &str = "confirmed";
+// The commitment used by queries that don't specify one, until overridden with set_default_commitment
+const DEFAULT_COMMITMENT: CommitmentConfig = CommitmentConfig{
+    commitment: CommitmentLevel::Confirmed,
+};
 
 const SUCCESSFUL_EXIT_CODE: i32 = 0;
 
+const TIME_BETWEEN_SIGNATURE_STATUS_POLLS: Duration = Duration::from_millis(500);
+
+// An airdrop that reached the commitment level it was waited on for
+#[derive(Clone, Debug)]
+pub struct AirdropConfirmation {
+    pub signature: Signature,
+    // The slot the airdrop transaction landed in
+    pub slot: u64,
+    // From requesting the airdrop to seeing it reach the commitment level
+    pub elapsed: Duration,
+}
+
 pub struct ValidatorService {
     service_context: ServiceContext,
-    sender: Box<dyn RpcSender>,
+    client: RpcClient,
+    async_client: AsyncRpcClient,
+    pubsub_client: PubsubClient,
+    // Used only by the readiness checks, which need to see each failure as it happens rather than have it retried away
+    readiness_client: RpcClient,
+    readiness: RefCell<ReadinessTracker>,
 }
 
 impl ValidatorService {
     pub fn new(service_context: ServiceContext) -> ValidatorService {
         let url = format!("http://{}:{}", service_context.get_ip_address(), RPC_PORT);
+        let pubsub_url = format!("ws://{}:{}", service_context.get_ip_address(), PUBSUB_PORT);
+        return ValidatorService::new_with_urls(service_context, url, pubsub_url);
+    }
+
+    fn new_with_urls(service_context: ServiceContext, url: String, pubsub_url: String) -> ValidatorService {
+        let readiness_client = RpcClient::new(url.clone(), DEFAULT_COMMITMENT);
+        readiness_client.set_retry_policy(NO_RETRY_POLICY);
         return ValidatorService{
             service_context,
-            sender: Box::new(HttpSender::new(url)),
+            client: RpcClient::new(url.clone(), DEFAULT_COMMITMENT),
+            async_client: AsyncRpcClient::new(url, DEFAULT_COMMITMENT),
+            pubsub_client: PubsubClient::new(pubsub_url),
+            readiness_client,
+            readiness: RefCell::new(ReadinessTracker::new(DEFAULT_READINESS_TIMEOUTS)),
         };
     }
 
@@ -41,6 +75,36 @@ impl ValidatorService {
         return self.service_context.get_ip_address();
     }
 
+    // Describes which readiness stage the node is stuck at and why, or None if it's ready
+    pub fn get_unready_reason(&self) -> Option<String> {
+        return self.readiness.borrow().get_unready_reason();
+    }
+
+    pub fn get_pubsub_client(&self) -> &PubsubClient {
+        return &self.pubsub_client;
+    }
+
+    #[allow(dead_code)]
+    pub fn get_default_commitment(&self) -> CommitmentConfig {
+        return self.client.commitment();
+    }
+
+    #[allow(dead_code)]
+    pub fn set_default_commitment(&self, commitment: CommitmentConfig) {
+        self.client.set_commitment(commitment);
+        self.async_client.set_commitment(commitment);
+    }
+
+    pub fn get_retry_policy(&self) -> RetryPolicy {
+        return self.client.retry_policy();
+    }
+
+    // Sets how RPC calls to this node get retried when they fail, e.g. to tolerate the node restarting
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        self.client.set_retry_policy(retry_policy);
+        self.async_client.set_retry_policy(retry_policy);
+    }
+
     // TODO All of the methods below this point can be replaced by the official Solana RpcClient:
     // https://github.com/solana-labs/solana/blob/master/client/src/rpc_client.rs
     // Unfortunately, that library (solana-client) provides Ledger support, and so depends on the 'hidapi'
@@ -50,17 +114,6 @@ impl ValidatorService {
     //   a) solana-client makes Ledger dependencies optional or
     //   b) Docker-for-Mac supports Linux headers
     // we have to reimplement the client methods
-    pub fn get_confirmed_transaction_count(&self) -> Result<u64> {
-        let params = json!([
-            {
-                COMMITMENT_LEVEL_PARAM: CONFIRMED_COMMMITMENT_LEVEL,
-            },
-        ]);
-        let result = self.send(RpcRequest::GetTransactionCount, params)
-            .context("An error occurred getting the confirmed transaction count")?;
-        return Ok(result);
-    }
-
     pub fn assert_number_of_nodes(&self, expected_num_nodes: usize) -> Result<()> {
         let now = SystemTime::now();
         let time_since_epoch = now.duration_since(UNIX_EPOCH)
@@ -114,14 +167,179 @@ impl ValidatorService {
         return Ok(());
     }
 
-    pub fn get_confirmed_slot(&self) -> Result<u64> {
-        let params = json!([
-            {
-                COMMITMENT_LEVEL_PARAM: CONFIRMED_COMMMITMENT_LEVEL,
-            },
-        ]);
-        let result = self.send(RpcRequest::GetSlot, params)
-            .context("An error occurred getting the confirmed slot")?;
+    pub fn get_slot(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        let result = self.client.get_slot(commitment)
+            .context("An error occurred getting the slot")?;
+        return Ok(result);
+    }
+
+    // Async versions of the queries above, so that the same query can be fired at many nodes at (nearly) the same instant
+    pub async fn get_slot_async(&self, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        let result = self.async_client.get_slot(commitment).await
+            .context("An error occurred getting the slot")?;
+        return Ok(result);
+    }
+
+    pub async fn get_slots_async(&self, commitments: &[CommitmentConfig]) -> Result<Vec<u64>> {
+        let result = self.async_client.get_slots(commitments).await
+            .context(format!("An error occurred getting the slots for commitments {:?}", commitments))?;
+        return Ok(result);
+    }
+
+    pub fn get_recent_blockhash(&self) -> Result<Hash> {
+        let response = self.client.get_recent_blockhash(None)
+            .context("An error occurred getting a recent blockhash")?;
+        let blockhash = Hash::from_base58(&response.value.blockhash)
+            .context(format!("An error occurred parsing recent blockhash '{}'", response.value.blockhash))?;
+        return Ok(blockhash);
+    }
+
+    // Submits a transaction built and signed in-process, returning its signature once the node has accepted it
+    pub fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        let signature = transaction.get_signature();
+        let node_signature = self.client.send_transaction(&transaction.serialize())
+            .context(format!("An error occurred sending transaction '{}'", signature))?;
+        if node_signature != signature.to_string() {
+            return Err(anyhow!(
+                "Sent transaction '{}' but the node reported its signature as '{}'",
+                signature,
+                node_signature,
+            ));
+        }
+        return Ok(signature);
+    }
+
+    // Polls the transaction's status until it reaches the given commitment level, failing if the transaction failed or if
+    // it doesn't get there within the timeout
+    pub fn wait_for_signature_confirmation(&self, signature: &Signature, commitment: CommitmentConfig, timeout: Duration) -> Result<TransactionStatus> {
+        let signature_str = signature.to_string();
+        let start_time = Instant::now();
+        loop {
+            let statuses = self.client.get_signature_statuses(&[&signature_str], false)
+                .context(format!("An error occurred getting the status of transaction '{}'", signature))?;
+            if let Some(Some(status)) = statuses.value.into_iter().next() {
+                if let Some(err) = &status.err {
+                    return Err(anyhow!(
+                        "Transaction '{}' failed in slot {} with error: {}",
+                        signature,
+                        status.slot,
+                        err,
+                    ));
+                }
+                if status.satisfies_commitment(commitment) {
+                    return Ok(status);
+                }
+            }
+            if start_time.elapsed() >= timeout {
+                return Err(anyhow!(
+                    "Transaction '{}' didn't reach commitment '{}' within {:?}",
+                    signature,
+                    commitment,
+                    timeout,
+                ));
+            }
+            sleep(TIME_BETWEEN_SIGNATURE_STATUS_POLLS);
+        }
+    }
+
+    // Gets the statuses of any number of transactions, in batches of as many as the node allows per request
+    // Returns: the status of each transaction in the same order as the signatures, or None for those the node hasn't seen
+    pub fn get_signature_statuses(&self, signatures: &[Signature]) -> Result<Vec<Option<TransactionStatus>>> {
+        let mut result = Vec::with_capacity(signatures.len());
+        for batch in signatures.chunks(MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS) {
+            let batch_strs: Vec<String> = batch.iter().map(|signature| signature.to_string()).collect();
+            let batch_str_refs: Vec<&str> = batch_strs.iter().map(|signature_str| signature_str.as_str()).collect();
+            let statuses = self.client.get_signature_statuses(&batch_str_refs, false)
+                .context(format!("An error occurred getting the statuses of a batch of {} transactions", batch.len()))?;
+            result.extend(statuses.value);
+        }
+        return Ok(result);
+    }
+
+    pub fn send_and_confirm_transaction(&self, transaction: &Transaction, commitment: CommitmentConfig, timeout: Duration) -> Result<TransactionStatus> {
+        let signature = self.send_transaction(transaction)?;
+        let status = self.wait_for_signature_confirmation(&signature, commitment, timeout)
+            .context(format!("An error occurred waiting for transaction '{}' to be confirmed", signature))?;
+        return Ok(status);
+    }
+
+    // Asks the node to get the faucet to fund the pubkey, then waits for the airdrop transaction to reach the commitment
+    // level; only nodes started with a faucet address (the first bootstrapper and the RPC nodes) can do this
+    pub fn airdrop_and_confirm(&self, pubkey: &Pubkey, lamports: u64, commitment: CommitmentConfig, timeout: Duration) -> Result<AirdropConfirmation> {
+        let start_time = Instant::now();
+        let signature_str = self.client.request_airdrop(&pubkey.to_string(), lamports, None)
+            .context(format!("An error occurred requesting an airdrop of {} lamports to '{}'", lamports, pubkey))?;
+        let signature = Signature::from_base58(&signature_str)
+            .context(format!("An error occurred parsing airdrop signature '{}'", signature_str))?;
+        let remaining_timeout = timeout.checked_sub(start_time.elapsed()).unwrap_or_default();
+        let status = self.wait_for_signature_confirmation(&signature, commitment, remaining_timeout)
+            .context(format!("An error occurred waiting for the airdrop of {} lamports to '{}' to be confirmed", lamports, pubkey))?;
+        return Ok(AirdropConfirmation{
+            signature,
+            slot: status.slot,
+            elapsed: start_time.elapsed(),
+        });
+    }
+
+    pub fn get_balance(&self, pubkey: &Pubkey, commitment: Option<CommitmentConfig>) -> Result<u64> {
+        let response = self.client.get_balance(&pubkey.to_string(), commitment)
+            .context(format!("An error occurred getting the balance of '{}'", pubkey))?;
+        return Ok(response.value);
+    }
+
+    pub fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64> {
+        let result = self.client.get_minimum_balance_for_rent_exemption(data_len, None)
+            .context(format!("An error occurred getting the minimum rent-exempt balance for {} bytes", data_len))?;
+        return Ok(result);
+    }
+
+    // The pubkey that the node identifies itself to the cluster with
+    pub fn get_identity(&self) -> Result<Pubkey> {
+        let response = self.client.get_identity()
+            .context("An error occurred getting the node's identity")?;
+        let identity = Pubkey::from_base58(&response.identity)
+            .context(format!("An error occurred parsing node identity '{}'", response.identity))?;
+        return Ok(identity);
+    }
+
+    // The version of the Solana software the node is running, along with the feature set it was built with
+    pub fn get_version(&self) -> Result<RpcVersionInfo> {
+        let result = self.client.get_version()
+            .context("An error occurred getting the node's version")?;
+        return Ok(result);
+    }
+
+    // Gets the activated stake of every vote account the node knows of, whether delinquent or not
+    pub fn get_vote_account_stakes(&self, commitment: Option<CommitmentConfig>) -> Result<HashMap<Pubkey, u64>> {
+        let vote_accounts = self.client.get_vote_accounts(commitment)
+            .context("An error occurred getting the vote accounts")?;
+        let mut result = HashMap::new();
+        for vote_account in vote_accounts.current.iter().chain(vote_accounts.delinquent.iter()) {
+            let vote_pubkey = Pubkey::from_base58(&vote_account.vote_pubkey)
+                .context(format!("An error occurred parsing vote account pubkey '{}'", vote_account.vote_pubkey))?;
+            result.insert(vote_pubkey, vote_account.activated_stake);
+        }
+        return Ok(result);
+    }
+
+    // Gets the vote accounts that the node sees as having fallen behind on voting, e.g. because their node is down or on
+    // a different fork
+    pub fn get_delinquent_vote_pubkeys(&self, commitment: Option<CommitmentConfig>) -> Result<Vec<Pubkey>> {
+        let vote_accounts = self.client.get_vote_accounts(commitment)
+            .context("An error occurred getting the vote accounts")?;
+        let mut result = Vec::new();
+        for vote_account in vote_accounts.delinquent.iter() {
+            let vote_pubkey = Pubkey::from_base58(&vote_account.vote_pubkey)
+                .context(format!("An error occurred parsing vote account pubkey '{}'", vote_account.vote_pubkey))?;
+            result.push(vote_pubkey);
+        }
+        return Ok(result);
+    }
+
+    // Gets the slot, transaction count, and epoch info all in a single round trip
+    pub fn get_progress_sample(&self, commitment: Option<CommitmentConfig>) -> Result<ProgressSample> {
+        let result = self.client.get_progress_sample(commitment)
+            .context("An error occurred getting the progress sample")?;
         return Ok(result);
     }
 
@@ -180,50 +398,136 @@ impl ValidatorService {
         return Ok(());
     }
 
-    fn send<T>(&self, request: RpcRequest, params: Value) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        assert!(params.is_array() || params.is_null());
-        let response = self
-            .sender
-            .send(request, params)
-            .context("An error occurred sending the request")?;
-        let deserialized = serde_json::from_value(response)
-            .context("An error occurred deserializing the response string to a JSON object")?;
-        return Ok(deserialized);
-    }
-
-    fn get_solana_bin_filepath(bin_filename: &str) -> String {
-        return format!("{}/{}", SOLANA_BINARIES_DIRPATH, bin_filename);
+    // Sends the validator process SIGTERM and waits for it to exit, so that the container can then be removed without
+    // killing the validator mid-write
+    // The validator has to be signalled directly because it isn't the container's main process - that's the shell that
+    // pipes its output to the log file, and which ignores SIGTERM
+    pub fn shut_down(&self, timeout: Duration) -> Result<()> {
+        let cmd_args: Vec<String> = vec![
+            String::from("kill"),
+            String::from("-TERM"),
+            format!("$(pidof {})", SOLANA_VALIDATOR_BIN_FILENAME),
+            String::from("&&"),
+            String::from("for"),
+            String::from("i"),
+            String::from("in"),
+            format!("$(seq 1 {})", timeout.as_secs().max(1)),
+            String::from(";"),
+            String::from("do"),
+            String::from("pidof"),
+            SOLANA_VALIDATOR_BIN_FILENAME.to_owned(),
+            String::from(">"),
+            String::from("/dev/null"),
+            String::from("||"),
+            String::from("exit"),
+            String::from("0"),
+            String::from(";"),
+            String::from("sleep"),
+            String::from("1"),
+            String::from(";"),
+            String::from("done"),
+            String::from(";"),
+            String::from("exit"),
+            String::from("1"),
+        ];
+        let command: Vec<String> = vec![
+            String::from("sh"),
+            String::from("-c"),
+            cmd_args.join(" "),
+        ];
+        debug!("Command to exec: {:?}", command);
+        let (exit_code, _) = self.service_context.exec_command(command.clone())
+            .context(format!("An error occurred executing command to shut down the validator '{:?}'", command))?;
+        if exit_code != SUCCESSFUL_EXIT_CODE {
+            return Err(anyhow!(
+                "Expected the validator to have exited within {:?} of being sent SIGTERM, but command '{:?}' exited with code '{}'",
+                timeout,
+                command,
+                exit_code,
+            ));
+        }
+        return Ok(());
     }
-}
 
-impl Service for ValidatorService {
-    fn is_available(&self) -> bool {
+    fn check_init_complete(&self) -> Result<()> {
         let command: Vec<String> = vec![
             String::from("["),
             String::from("-r"),
             String::from(INIT_COMPLETE_FILEPATH),
             String::from("]"),
         ];
-        let exec_resp_or_err = self.service_context.exec_command(command);
-        let exit_code: i32;
-        match exec_resp_or_err {
-            Ok((inner_exit_code, _)) => exit_code = inner_exit_code,
-            Err(err) => {
-                debug!("An error occurred executing the command to test if the init file exists: {}", err);
-                return false;
-            }
-        }
+        let (exit_code, _) = self.service_context.exec_command(command)
+            .context("An error occurred executing the command to test if the init file exists")?;
         if exit_code != SUCCESSFUL_EXIT_CODE {
-            debug!(
-                "Expected successful exit code '{}' when checking if init file exists, but got '{}'",
+            return Err(anyhow!(
+                "Expected successful exit code '{}' when checking if init file '{}' exists, but got '{}'",
                 SUCCESSFUL_EXIT_CODE,
+                INIT_COMPLETE_FILEPATH,
                 exit_code,
-            );
-            return false;
+            ));
         }
-        return true;
+        return Ok(());
+    }
+
+    fn get_solana_bin_filepath(bin_filename: &str) -> String {
+        return format!("{}/{}", SOLANA_BINARIES_DIRPATH, bin_filename);
+    }
+}
+
+impl Service for ValidatorService {
+    // A node is available once it's written its init-complete file, its RPC API reports it healthy, and it's processing slots
+    fn is_available(&self) -> bool {
+        return self.readiness.borrow_mut().poll(|| self.check_init_complete(), &self.readiness_client);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use kurtosis_rust_lib::{core_api_bindings::api_container_api::test_execution_service_client::TestExecutionServiceClient, services::service_context::ServiceContext};
+    use serde_json::json;
+    use tokio::runtime::Runtime;
+    use tonic::transport::Endpoint;
+
+    use crate::services_impl::validator::{commitment_config::CommitmentConfig, mock_rpc_server::MockRpcServer};
+
+    use super::ValidatorService;
+
+    // The service context is never used by the RPC queries, so it points at an API container that doesn't exist
+    fn new_service(runtime: &Rc<Runtime>, server: &MockRpcServer) -> ValidatorService {
+        let channel = {
+            let _guard = runtime.enter();
+            Endpoint::from_static("http://127.0.0.1:1").connect_lazy().unwrap()
+        };
+        let service_context = ServiceContext::new(
+            runtime.clone(),
+            TestExecutionServiceClient::new(channel),
+            "validator-0".to_owned(),
+            "127.0.0.1".to_owned(),
+        );
+        return ValidatorService::new_with_urls(service_context, server.get_url(), "ws://127.0.0.1:1".to_owned());
+    }
+
+    #[test]
+    fn test_set_default_commitment() {
+        let server = MockRpcServer::start(|method, params| {
+            assert_eq!("getSlot", method);
+            return match params[0]["commitment"].as_str() {
+                Some("finalized") => Ok(json!(5)),
+                _ => Ok(json!(10)),
+            };
+        });
+        let runtime = Rc::new(Runtime::new().unwrap());
+        let service = new_service(&runtime, &server);
+        assert_eq!(CommitmentConfig::confirmed(), service.get_default_commitment());
+        assert_eq!(10, service.get_slot(None).unwrap());
+
+        service.set_default_commitment(CommitmentConfig::finalized());
+        assert_eq!(CommitmentConfig::finalized(), service.get_default_commitment());
+        assert_eq!(5, service.get_slot(None).unwrap());
+        assert_eq!(5, runtime.block_on(service.get_slot_async(None)).unwrap());
+        // An explicit commitment still wins over the default
+        assert_eq!(10, service.get_slot(Some(CommitmentConfig::processed())).unwrap());
+    }
+}
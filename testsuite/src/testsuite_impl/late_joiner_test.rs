@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Context, Result};
+use std::{rc::Rc, thread::sleep, time::Duration};
+
+use kurtosis_rust_lib::{networks::network_context::NetworkContext, testsuite::{test::Test, test_configuration::TestConfiguration, test_context::TestContext}};
+
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, solana_network::SolanaNetwork, solana_network_config::SolanaNetworkConfig}, services_impl::validator::commitment_config::CommitmentConfig};
+
+// Long enough for a few slots to get confirmed
+const TIME_BETWEEN_SLOT_CHECKS: Duration = Duration::from_secs(5);
+
+// Checks that a validator that isn't in genesis can join the running cluster, catch up, and keep up
+pub struct LateJoinerTest {
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
+}
+
+impl LateJoinerTest {
+    pub fn new(genesis_config: Rc<GenesisConfig>, ledger_artifact: Rc<LedgerArtifact>, network_config: SolanaNetworkConfig) -> LateJoinerTest {
+        return LateJoinerTest{
+            genesis_config,
+            ledger_artifact,
+            network_config,
+        };
+    }
+}
+
+impl Test for LateJoinerTest {
+    type N = SolanaNetwork;
+
+    fn get_test_configuration(&self) -> TestConfiguration {
+        return TestConfiguration{
+            is_partitioning_enabled: false,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
+        };
+    }
+
+    fn setup(&mut self, network_ctx: NetworkContext) -> Result<Box<SolanaNetwork>> {
+        let mut network = SolanaNetwork::new(
+            network_ctx,
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
+
+        network.start_faucet_and_bootstrappers()
+            .context("An error occurred starting the faucet and bootstrappers")?;
+
+        return Ok(Box::new(network));
+    }
+
+    fn run(&self, mut network: Box<SolanaNetwork>, _: TestContext) -> Result<()> {
+        info!("Adding a validator that isn't in genesis...");
+        let validator_index = network.add_validator()
+            .context("An error occurred adding a late-joining validator")?;
+        info!("Validator joined and caught up");
+
+        let expected_num_nodes = network.get_num_bootstrappers() + network.get_num_validators();
+        info!("Asserting that the network has the correct number of nodes, {}...", expected_num_nodes);
+        network.get_bootstrapper(0)?.assert_number_of_nodes(expected_num_nodes)
+            .context(format!("An error occurred asserting that we have the expected number of nodes, '{}'", expected_num_nodes))?;
+        info!("Successfully asserted that the network has the correct number of nodes");
+
+        // Having caught up once isn't enough; the joiner has to keep up with the cluster too
+        let validator = network.get_validator(validator_index)?;
+        let slot_before = validator.get_slot(Some(CommitmentConfig::confirmed()))
+            .context("An error occurred getting the joiner's slot")?;
+        sleep(TIME_BETWEEN_SLOT_CHECKS);
+        let slot_after = validator.get_slot(Some(CommitmentConfig::confirmed()))
+            .context("An error occurred getting the joiner's slot")?;
+        if slot_after <= slot_before {
+            return Err(anyhow!(
+                "The joiner's confirmed slot didn't advance past {} in {:?}",
+                slot_before,
+                TIME_BETWEEN_SLOT_CHECKS,
+            ));
+        }
+        info!("Joiner's confirmed slot advanced from {} to {}", slot_before, slot_after);
+        return Ok(());
+    }
+
+    fn get_setup_timeout(&self) -> Duration {
+        return Duration::from_secs(300);
+    }
+
+    fn get_execution_timeout(&self) -> Duration {
+        return Duration::from_secs(480);
+    }
+}
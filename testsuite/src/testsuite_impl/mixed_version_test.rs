@@ -0,0 +1,145 @@
+use anyhow::{anyhow, Context, Result};
+use std::{collections::{HashMap, HashSet}, rc::Rc, thread::sleep, time::Duration};
+
+use kurtosis_rust_lib::{networks::network_context::NetworkContext, services::service::ServiceId, testsuite::{test::Test, test_configuration::TestConfiguration, test_context::TestContext}};
+
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, solana_network::SolanaNetwork, solana_network_config::SolanaNetworkConfig}, sdk_impl::pubkey::Pubkey, services_impl::validator::commitment_config::CommitmentConfig};
+
+// Long enough for every node to finalize several more slots if the cluster is still in consensus
+const TIME_BETWEEN_ROOT_CHECKS: Duration = Duration::from_secs(15);
+
+// Checks that a cluster whose bootstrappers run different images still reaches consensus: every node keeps rooting, and
+// no node sees any of the others' votes as delinquent (which is what a node voting on a different fork looks like)
+pub struct MixedVersionTest {
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
+}
+
+impl MixedVersionTest {
+    pub fn new(genesis_config: Rc<GenesisConfig>, ledger_artifact: Rc<LedgerArtifact>, network_config: SolanaNetworkConfig) -> MixedVersionTest {
+        return MixedVersionTest{
+            genesis_config,
+            ledger_artifact,
+            network_config,
+        };
+    }
+
+    // Logs which image & version each node is running, so that a consensus failure can be traced back to the versions involved
+    fn report_versions(network: &SolanaNetwork, service_ids: &[ServiceId]) -> Result<()> {
+        let versions = network.get_node_versions(service_ids)
+            .context("An error occurred getting the nodes' versions")?;
+        for (service_id, version) in service_ids.iter().zip(versions.iter()) {
+            info!(
+                "Node '{}' is running image '{}', which reports version {} with feature set {:?}",
+                service_id,
+                network.get_node_image(service_id),
+                version.solana_core,
+                version.feature_set,
+            );
+        }
+        let distinct_versions: HashSet<&str> = versions.iter().map(|version| version.solana_core.as_str()).collect();
+        info!("The cluster is running {} distinct versions: {:?}", distinct_versions.len(), distinct_versions);
+        return Ok(());
+    }
+
+    fn verify_all_rooting(network: &SolanaNetwork, service_ids: &[ServiceId]) -> Result<()> {
+        let slots_before = network.get_node_slots(service_ids, CommitmentConfig::finalized())
+            .context("An error occurred getting the nodes' finalized slots")?;
+        sleep(TIME_BETWEEN_ROOT_CHECKS);
+        let slots_after = network.get_node_slots(service_ids, CommitmentConfig::finalized())
+            .context("An error occurred getting the nodes' finalized slots")?;
+        for ((service_id, slot_before), slot_after) in service_ids.iter().zip(slots_before).zip(slots_after) {
+            if slot_after <= slot_before {
+                return Err(anyhow!(
+                    "Node '{}' running image '{}' didn't finalize any slots past {} in {:?}",
+                    service_id,
+                    network.get_node_image(service_id),
+                    slot_before,
+                    TIME_BETWEEN_ROOT_CHECKS,
+                ));
+            }
+            debug!("Node '{}' finalized slot went from {} to {}", service_id, slot_before, slot_after);
+        }
+        return Ok(());
+    }
+
+    fn verify_no_delinquent_votes(&self, network: &SolanaNetwork, service_ids: &[ServiceId]) -> Result<()> {
+        // Only the bootstrappers that got started vote; the rest of genesis's bootstrappers are always delinquent
+        let vote_pubkeys: HashMap<Pubkey, &ServiceId> = self.genesis_config.get_bootstrappers().iter()
+            .map(|bootstrapper_keypairs| *bootstrapper_keypairs.vote_account.get_pubkey())
+            .zip(service_ids.iter())
+            .collect();
+        for service_id in service_ids {
+            let delinquent_vote_pubkeys = network.get_node(service_id)?.get_delinquent_vote_pubkeys(Some(CommitmentConfig::finalized()))
+                .context(format!("An error occurred getting the delinquent vote accounts from node '{}'", service_id))?;
+            let delinquent_service_ids: Vec<&ServiceId> = delinquent_vote_pubkeys.iter()
+                .filter_map(|vote_pubkey| vote_pubkeys.get(vote_pubkey).copied())
+                .collect();
+            if !delinquent_service_ids.is_empty() {
+                return Err(anyhow!(
+                    "Node '{}' running image '{}' sees the votes of nodes {:?} as delinquent",
+                    service_id,
+                    network.get_node_image(service_id),
+                    delinquent_service_ids,
+                ));
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl Test for MixedVersionTest {
+    type N = SolanaNetwork;
+
+    fn get_test_configuration(&self) -> TestConfiguration {
+        return TestConfiguration{
+            is_partitioning_enabled: false,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
+        };
+    }
+
+    fn setup(&mut self, network_ctx: NetworkContext) -> Result<Box<SolanaNetwork>> {
+        let mut network = SolanaNetwork::new(
+            network_ctx,
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
+
+        network.start_faucet_and_bootstrappers()
+            .context("An error occurred starting the faucet and bootstrappers")?;
+
+        return Ok(Box::new(network));
+    }
+
+    fn run(&self, network: Box<SolanaNetwork>, _: TestContext) -> Result<()> {
+        let service_ids: Vec<ServiceId> = (0..network.get_num_bootstrappers()).map(SolanaNetwork::get_bootstrapper_service_id).collect();
+        MixedVersionTest::report_versions(&network, &service_ids)?;
+
+        info!("Verifying that every node is rooting...");
+        MixedVersionTest::verify_all_rooting(&network, &service_ids)
+            .context("The mixed-version cluster didn't keep rooting")?;
+        info!("Every node is rooting");
+
+        info!("Verifying that every node sees every other node's votes...");
+        self.verify_no_delinquent_votes(&network, &service_ids)
+            .context("The mixed-version cluster isn't voting on a single fork")?;
+        info!("Every node sees every other node's votes");
+
+        let expected_num_nodes = network.get_num_bootstrappers();
+        info!("Asserting that the network has the correct number of nodes, {}...", expected_num_nodes);
+        network.get_bootstrapper(0)?.assert_number_of_nodes(expected_num_nodes)
+            .context(format!("An error occurred asserting that we have the expected number of nodes, '{}'", expected_num_nodes))?;
+        info!("Successfully asserted that the network has the correct number of nodes");
+        return Ok(());
+    }
+
+    fn get_setup_timeout(&self) -> Duration {
+        return Duration::from_secs(300);
+    }
+
+    fn get_execution_timeout(&self) -> Duration {
+        return Duration::from_secs(300);
+    }
+}
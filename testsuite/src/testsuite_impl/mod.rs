@@ -1,3 +1,9 @@
+pub mod late_joiner_test;
+pub mod mixed_version_test;
 pub mod network_partition_test;
+pub mod node_restart_test;
+pub mod rolling_upgrade_test;
 pub mod simple_network_test;
-pub mod solana_testsuite;
\ No newline at end of file
+pub mod solana_testsuite;
+pub mod stake_partition_test;
+pub mod throughput_test;
\ No newline at end of file
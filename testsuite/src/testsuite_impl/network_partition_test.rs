@@ -1,11 +1,9 @@
-use std::{collections::{HashMap}, convert::TryInto, thread::sleep, time::{Duration, Instant}};
+use std::{convert::TryInto, rc::Rc, thread::sleep, time::{Duration, Instant}};
 
 use anyhow::{anyhow, Context, Result};
 use kurtosis_rust_lib::testsuite::{test::Test, test_configuration::TestConfiguration};
 
-use crate::{networks_impl::solana_network::SolanaNetwork, services_impl::validator::validator_service::ValidatorService};
-
-use super::solana_testsuite::{LEDGER_DIR_ARTIFACT_KEY, LEDGER_DIR_ARTIFACT_URL};
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, solana_network::SolanaNetwork, solana_network_config::SolanaNetworkConfig}, services_impl::validator::{commitment_config::CommitmentConfig, pubsub_client::PubsubSubscription}};
 
 // This is the maximum amount of time that a cluster might take to settle into its given state after a partition (e.g.
 // for blocks to stop being produced after a partition, or for blocks to start being produced after a partition heals)
@@ -28,35 +26,82 @@ const NUM_NETWORK_PARTITION_ROUNDS: u32 = 5;
 const AVG_TIME_TO_ADVANCING_THRESHOLD: Duration = Duration::from_secs(30);
 
 pub struct NetworkPartitionTest {
-    docker_image: String,
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
 }
 
 impl NetworkPartitionTest {
-    pub fn new(docker_image: String) -> NetworkPartitionTest {
+    pub fn new(genesis_config: Rc<GenesisConfig>, ledger_artifact: Rc<LedgerArtifact>, network_config: SolanaNetworkConfig) -> NetworkPartitionTest {
         return NetworkPartitionTest{
-            docker_image,
+            genesis_config,
+            ledger_artifact,
+            network_config,
         };
     }
 
-    fn get_current_confirmed_slots(bootstrappers: &HashMap<usize, &ValidatorService>) -> Result<HashMap<usize, u64>> {
-        let mut result = HashMap::new();
-        for i in 0..bootstrappers.len() {
-            let bootstrapper = bootstrappers[&i];
-            debug!("Getting current slot for bootstrapper {}...", i);
-            let current_slot = bootstrapper.get_confirmed_slot()
-                .context(format!("An error occurred getting the current confirmed slot for bootstrapper {}", i))?;
-            debug!("Bootstrapper {}'s current slot is {}", i, current_slot);
-            result.insert(i, current_slot);
+    // Gets the largest gap, across all bootstrappers, between a bootstrapper's confirmed slot and its finalized slot
+    fn get_max_finalized_lag(network: &SolanaNetwork) -> Result<u64> {
+        // Sampling all the commitment levels in the same round trip keeps them consistent with each other
+        let commitments = [CommitmentConfig::processed(), CommitmentConfig::confirmed(), CommitmentConfig::finalized()];
+        let all_slots = network.get_bootstrapper_slots_by_commitment(&commitments)
+            .context("An error occurred getting the slots at each commitment level for the bootstrappers")?;
+        let mut max_lag = 0;
+        for (i, slots_opt) in all_slots.iter().enumerate() {
+            let slots = match slots_opt {
+                Some(slots) => slots,
+                None => continue,
+            };
+            let (processed_slot, confirmed_slot, finalized_slot) = (slots[0], slots[1], slots[2]);
+            let lag = confirmed_slot.saturating_sub(finalized_slot);
+            debug!(
+                "Bootstrapper {} slots: processed '{}', confirmed '{}', finalized '{}' (finalized lags confirmed by {} slots)",
+                i,
+                processed_slot,
+                confirmed_slot,
+                finalized_slot,
+                lag,
+            );
+            max_lag = max_lag.max(lag);
         }
-        return Ok(result);
+        return Ok(max_lag);
+    }
+
+    // Finds, from each bootstrapper's root subscription, the first root it made after the partition was healed
+    // Stopped bootstrappers don't have subscriptions, and so don't get checked
+    // Returns: how long after the heal the last bootstrapper to resume rooting did so, or None if some haven't resumed yet
+    fn get_time_to_first_root(root_subscriptions: &mut [Option<PubsubSubscription<u64>>], heal_time: Instant) -> Option<Duration> {
+        let mut max_time_to_root = Duration::from_secs(0);
+        for (i, subscription_opt) in root_subscriptions.iter_mut().enumerate() {
+            let subscription = match subscription_opt {
+                Some(subscription) => subscription,
+                None => continue,
+            };
+            let first_root_after_heal = subscription.drain().into_iter()
+                .find(|notification| notification.received_at >= heal_time);
+            let notification = match first_root_after_heal {
+                Some(notification) => notification,
+                None => {
+                    debug!("Bootstrapper {} hasn't rooted a slot since the partition was healed", i);
+                    return None;
+                }
+            };
+            let time_to_root = notification.received_at - heal_time;
+            debug!("Bootstrapper {} rooted slot {} {:?} after the partition was healed", i, notification.value, time_to_root);
+            max_time_to_root = max_time_to_root.max(time_to_root);
+        }
+        return Some(max_time_to_root);
     }
 
     // Checks if the current slot for each bootstrapper matches the predicate when compared to the last slot for the bootstrapper
-    fn check_if_predicate_matches(is_advancing: bool, last_slots: &HashMap<usize, u64>, current_slots: &HashMap<usize, u64>) -> bool {
+    // Stopped bootstrappers don't have slots, and so don't get checked
+    fn check_if_predicate_matches(is_advancing: bool, last_slots: &[Option<u64>], current_slots: &[Option<u64>]) -> bool {
         let mut all_predicates_match = true;
         for i in 0..current_slots.len() {
-            let current_slot = current_slots[&i];
-            let last_slot = last_slots[&i];
+            let (current_slot, last_slot) = match (current_slots[i], last_slots[i]) {
+                (Some(current_slot), Some(last_slot)) => (current_slot, last_slot),
+                _ => continue,
+            };
             let predicate;
             let predicate_description;
             if is_advancing {
@@ -92,17 +137,10 @@ impl NetworkPartitionTest {
     // multiple checks
     // Returns: the time taken for the cluster to arrive at the expected state, or an error if a fatal error occurred
     fn wait_until_cluster_matches_state(slots_are_advancing_state: bool, network: &SolanaNetwork) -> Result<Duration> {
-        let mut bootstrappers = HashMap::new();
-        for i in 0..network.get_num_bootstrappers() {
-            let bootstrapper = network.get_bootstrapper(i)
-                .context(format!("An error occurred getting the interface for bootstrapper {}", i))?;
-            bootstrappers.insert(i, bootstrapper);
-        }
-
         let start_time = Instant::now();
         let error_threshold = start_time + MAX_CLUSTER_SETTLE_TIME;
         let mut successive_check_rounds_passed = 0;
-        let mut last_slots_opt: Option<HashMap<usize, u64>> = None;
+        let mut last_slots_opt: Option<Vec<Option<u64>>> = None;
         loop {
             if Instant::now() >= error_threshold {
                 let expected_state_desc;
@@ -118,7 +156,8 @@ impl NetworkPartitionTest {
                 ));
             }
 
-            let current_slots = NetworkPartitionTest::get_current_confirmed_slots(&bootstrappers)
+            // The bootstrappers are all queried at once so that a slow node doesn't skew when the others get sampled
+            let current_slots = network.get_bootstrapper_slots(CommitmentConfig::confirmed())
                 .context("An error occurred getting the current confirmed slots for the bootstrappers")?;
             match last_slots_opt {
                 Some(last_slots) => {
@@ -146,25 +185,21 @@ impl Test for NetworkPartitionTest {
     type N = SolanaNetwork;
 
     fn get_test_configuration(&self) -> kurtosis_rust_lib::testsuite::test_configuration::TestConfiguration {
-        let mut files_artifact_urls: HashMap<String, String> = HashMap::new();
-        files_artifact_urls.insert(
-            LEDGER_DIR_ARTIFACT_KEY.to_owned(), 
-            LEDGER_DIR_ARTIFACT_URL.to_owned(),
-        );
-
         return TestConfiguration{
             is_partitioning_enabled: true,
-            files_artifact_urls: files_artifact_urls,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
         }
     }
 
     fn setup(&mut self, network_ctx: kurtosis_rust_lib::networks::network_context::NetworkContext) -> anyhow::Result<Box<Self::N>> {
-        let mut network = SolanaNetwork::new(network_ctx, LEDGER_DIR_ARTIFACT_KEY.to_owned());
-        network.start_faucet_and_bootstrappers(&self.docker_image, &self.docker_image)
+        let mut network = SolanaNetwork::new(
+            network_ctx,
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
+        network.start_faucet_and_bootstrappers()
             .context("An error occurred starting the faucet and bootstrappers")?;
-        // TODO figure out why we need this, since the RPC APIs don't come up in time without it - maybe
-        // add an RPC check in hte is_available method of the validator service?
-        sleep(Duration::from_secs(10));
         return Ok(Box::new(network));
     }
 
@@ -175,6 +210,8 @@ impl Test for NetworkPartitionTest {
         info!("Slots are advancing");
 
         let mut times_to_advancing: Vec<Duration> = Vec::new();
+        let mut times_to_rooting: Vec<Option<Duration>> = Vec::new();
+        let mut finalized_lags: Vec<(u64, u64)> = Vec::new();
         for i in 0..NUM_NETWORK_PARTITION_ROUNDS {
             info!("--------------------- Partition/Heal Round {} ----------------------", i);
             info!("Partitioning network...");
@@ -187,24 +224,54 @@ impl Test for NetworkPartitionTest {
                 .context("An error occurred while waiting for the cluster slots to stop advancing")?;
             info!("Slots stopped advancing in {:?}", time_to_stop_advancing);
 
+            let partitioned_finalized_lag = NetworkPartitionTest::get_max_finalized_lag(&network)
+                .context("An error occurred measuring how far finalized slots lag confirmed slots while partitioned")?;
+            info!("While partitioned, finalized slots lag confirmed slots by up to {} slots", partitioned_finalized_lag);
+
+            // Subscribing while the roots are stalled means the first root notification on each subscription marks
+            // exactly when that bootstrapper resumed rooting
+            let mut root_subscriptions = network.subscribe_to_bootstrapper_roots()
+                .context("An error occurred subscribing to the bootstrappers' roots")?;
+
             info!("Healing partition...");
             network.partition_in_half_with_connection(false)
                 .context("An error occurred healing the network partition")?;
+            let heal_time = Instant::now();
             info!("Partition healed");
 
             info!("Verifying slots are advancing once again...");
             let time_to_advancing_again = NetworkPartitionTest::wait_until_cluster_matches_state(true, &network)
                 .context("An error occurred while waiting for the cluster slots to start advancing again")?;
             info!("Slots started advancing once again in {:?}", time_to_advancing_again);
+
+            let time_to_rooting_again = NetworkPartitionTest::get_time_to_first_root(&mut root_subscriptions, heal_time);
+            match time_to_rooting_again {
+                Some(time) => info!("All bootstrappers started rooting slots once again in {:?}", time),
+                None => info!("Not every bootstrapper has rooted a slot since the partition was healed"),
+            }
+
+            let healed_finalized_lag = NetworkPartitionTest::get_max_finalized_lag(&network)
+                .context("An error occurred measuring how far finalized slots lag confirmed slots after healing")?;
+            info!("After healing, finalized slots lag confirmed slots by up to {} slots", healed_finalized_lag);
             info!("");
 
             times_to_advancing.push(time_to_advancing_again);
+            times_to_rooting.push(time_to_rooting_again);
+            finalized_lags.push((partitioned_finalized_lag, healed_finalized_lag));
+        }
+
+        info!("Max finalized lag per round (partitioned / healed):");
+        for (i, (partitioned_lag, healed_lag)) in finalized_lags.iter().enumerate() {
+            info!(" - {}: {} slots / {} slots", i, partitioned_lag, healed_lag);
         }
 
-        info!("Heal time per round:");
+        info!("Heal time per round (slots advancing / all bootstrappers rooting):");
         let mut sum_heal_time_millis: u128 = 0;
-        for (i, time) in times_to_advancing.iter().enumerate() {
-            info!(" - {}: {:?}", i, time);
+        for (i, (time, time_to_rooting)) in times_to_advancing.iter().zip(times_to_rooting.iter()).enumerate() {
+            match time_to_rooting {
+                Some(time_to_rooting) => info!(" - {}: {:?} / {:?}", i, time, time_to_rooting),
+                None => info!(" - {}: {:?} / not rooting", i, time),
+            }
             sum_heal_time_millis += time.as_millis();
         }
         let sum_heal_time_millis_u64: u64 = sum_heal_time_millis.try_into()
@@ -0,0 +1,127 @@
+use anyhow::{anyhow, Context, Result};
+use std::{rc::Rc, thread::sleep, time::{Duration, Instant}};
+
+use kurtosis_rust_lib::{networks::network_context::NetworkContext, testsuite::{test::Test, test_configuration::TestConfiguration, test_context::TestContext}};
+
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, solana_network::SolanaNetwork, solana_network_config::SolanaNetworkConfig}, services_impl::validator::{commitment_config::CommitmentConfig, validator_service::ValidatorService}};
+
+// If the restarted node was the one holding the cluster's supermajority together, rooting only resumes once it has
+// replayed its ledger and started voting again
+const MAX_TIME_FOR_ROOTING_TO_RESUME: Duration = Duration::from_secs(120);
+const TIME_BETWEEN_ROOT_CHECKS: Duration = Duration::from_secs(5);
+
+// Checks that nodes come back with their ledgers intact after being stopped cleanly and after being killed mid-write,
+// the latter relying on the validators' '--wal-recovery-mode skip_any_corrupted_record' to recover
+pub struct NodeRestartTest {
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
+}
+
+impl NodeRestartTest {
+    pub fn new(genesis_config: Rc<GenesisConfig>, ledger_artifact: Rc<LedgerArtifact>, network_config: SolanaNetworkConfig) -> NodeRestartTest {
+        return NodeRestartTest{
+            genesis_config,
+            ledger_artifact,
+            network_config,
+        };
+    }
+
+    // Waits until the node has rooted a slot past the one it had rooted before it went down, which it can only do once
+    // it's recovered its ledger and rejoined the cluster
+    fn wait_for_rooting_past(node: &ValidatorService, slot_before_restart: u64) -> Result<()> {
+        let start_time = Instant::now();
+        loop {
+            let slot = node.get_slot(Some(CommitmentConfig::finalized()))
+                .context("An error occurred getting the restarted node's finalized slot")?;
+            if slot > slot_before_restart {
+                info!("Restarted node rooted slot {}, past slot {} from before the restart", slot, slot_before_restart);
+                return Ok(());
+            }
+            if start_time.elapsed() >= MAX_TIME_FOR_ROOTING_TO_RESUME {
+                return Err(anyhow!(
+                    "The restarted node's finalized slot was still {} after {:?}, but it was already {} before the restart",
+                    slot,
+                    MAX_TIME_FOR_ROOTING_TO_RESUME,
+                    slot_before_restart,
+                ));
+            }
+            sleep(TIME_BETWEEN_ROOT_CHECKS);
+        }
+    }
+}
+
+impl Test for NodeRestartTest {
+    type N = SolanaNetwork;
+
+    fn get_test_configuration(&self) -> TestConfiguration {
+        return TestConfiguration{
+            is_partitioning_enabled: false,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
+        };
+    }
+
+    fn setup(&mut self, network_ctx: NetworkContext) -> Result<Box<SolanaNetwork>> {
+        let mut network = SolanaNetwork::new(
+            network_ctx,
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
+
+        network.start_faucet_and_bootstrappers()
+            .context("An error occurred starting the faucet and bootstrappers")?;
+
+        return Ok(Box::new(network));
+    }
+
+    fn run(&self, mut network: Box<SolanaNetwork>, _: TestContext) -> Result<()> {
+        let bootstrapper_index = network.get_num_bootstrappers() - 1;
+        for is_killed in &[false, true] {
+            info!("--------------------- {} Bootstrapper ----------------------", if *is_killed { "Killed" } else { "Stopped" });
+            let slot_before_restart = network.get_bootstrapper(bootstrapper_index)?.get_slot(Some(CommitmentConfig::finalized()))
+                .context(format!("An error occurred getting the finalized slot of bootstrapper #{}", bootstrapper_index))?;
+            if *is_killed {
+                network.kill_bootstrapper(bootstrapper_index)?;
+            } else {
+                network.stop_bootstrapper(bootstrapper_index)?;
+            }
+            network.restart_bootstrapper(bootstrapper_index)?;
+            NodeRestartTest::wait_for_rooting_past(network.get_bootstrapper(bootstrapper_index)?, slot_before_restart)
+                .context(format!("Bootstrapper #{} didn't recover from being {}", bootstrapper_index, if *is_killed { "killed" } else { "stopped" }))?;
+        }
+
+        info!("--------------------- Killed Validator ----------------------");
+        let validator_index = network.add_validator()
+            .context("An error occurred adding a validator to kill")?;
+        let slot_before_kill = network.get_validator(validator_index)?.get_slot(Some(CommitmentConfig::finalized()))
+            .context(format!("An error occurred getting the finalized slot of validator #{}", validator_index))?;
+        network.kill_validator(validator_index)?;
+        network.restart_validator(validator_index)?;
+        NodeRestartTest::wait_for_rooting_past(network.get_validator(validator_index)?, slot_before_kill)
+            .context(format!("Validator #{} didn't recover from being killed", validator_index))?;
+
+        // Restarting a running node stops it cleanly first
+        info!("--------------------- Restarted Validator ----------------------");
+        let slot_before_restart = network.get_validator(validator_index)?.get_slot(Some(CommitmentConfig::finalized()))
+            .context(format!("An error occurred getting the finalized slot of validator #{}", validator_index))?;
+        network.restart_validator(validator_index)?;
+        NodeRestartTest::wait_for_rooting_past(network.get_validator(validator_index)?, slot_before_restart)
+            .context(format!("Validator #{} didn't recover from being restarted", validator_index))?;
+
+        let expected_num_nodes = network.get_num_bootstrappers() + network.get_num_validators();
+        info!("Asserting that the network has the correct number of nodes, {}...", expected_num_nodes);
+        network.get_bootstrapper(0)?.assert_number_of_nodes(expected_num_nodes)
+            .context(format!("An error occurred asserting that we have the expected number of nodes, '{}'", expected_num_nodes))?;
+        info!("Successfully asserted that the network has the correct number of nodes");
+        return Ok(());
+    }
+
+    fn get_setup_timeout(&self) -> Duration {
+        return Duration::from_secs(300);
+    }
+
+    fn get_execution_timeout(&self) -> Duration {
+        return Duration::from_secs(900);
+    }
+}
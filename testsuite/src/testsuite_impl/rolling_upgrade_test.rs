@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Context, Result};
+use std::{rc::Rc, thread::sleep, time::{Duration, Instant}};
+
+use kurtosis_rust_lib::{networks::network_context::NetworkContext, services::service::ServiceId, testsuite::{test::Test, test_configuration::TestConfiguration, test_context::TestContext}};
+
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, solana_network::{SolanaNetwork, StakeSource}, solana_network_config::SolanaNetworkConfig}, services_impl::validator::{commitment_config::CommitmentConfig, rpc_response::RpcVersionInfo}};
+
+// Once a bootstrapper is back up on the new image, the cluster has this long to finalize a slot past where it was before
+// the bootstrapper went down
+const MAX_TIME_TO_FINALIZE_PAST_UPGRADE: Duration = Duration::from_secs(120);
+const TIME_BETWEEN_FINALIZED_SLOT_CHECKS: Duration = Duration::from_secs(5);
+
+// Restarts the bootstrappers onto the upgrade image one at a time, checking that the cluster keeps finalizing the whole
+// way through and that the nodes report the expected mix of old & new versions after every step
+pub struct RollingUpgradeTest {
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
+    upgrade_image: String,
+}
+
+impl RollingUpgradeTest {
+    pub fn new(genesis_config: Rc<GenesisConfig>, ledger_artifact: Rc<LedgerArtifact>, network_config: SolanaNetworkConfig, upgrade_image: String) -> RollingUpgradeTest {
+        return RollingUpgradeTest{
+            genesis_config,
+            ledger_artifact,
+            network_config,
+            upgrade_image,
+        };
+    }
+
+    // The cluster can only keep finalizing while a bootstrapper is down if the rest still hold a supermajority
+    fn verify_cluster_survives_any_one_bootstrapper_down(&self, network: &SolanaNetwork) -> Result<()> {
+        let node_stakes = network.get_node_stakes(StakeSource::Genesis)
+            .context("An error occurred getting the bootstrappers' genesis stakes")?;
+        let running_stake: u64 = node_stakes.iter().map(|(_, stake)| stake).sum();
+        let total_stake = self.genesis_config.get_total_stake();
+        for (service_id, stake) in &node_stakes {
+            let remaining_stake = running_stake - stake;
+            if remaining_stake as u128 * 3 <= total_stake as u128 * 2 {
+                return Err(anyhow!(
+                    "While '{}' is down for its upgrade the other nodes would only hold {} of the {} stake, which isn't a supermajority, so the cluster couldn't keep finalizing",
+                    service_id,
+                    remaining_stake,
+                    total_stake,
+                ));
+            }
+        }
+        return Ok(());
+    }
+
+    // Waits until every one of the given nodes has finalized a slot past the given one
+    fn wait_for_finalized_past(network: &SolanaNetwork, service_ids: &[ServiceId], slot: u64) -> Result<()> {
+        let start_time = Instant::now();
+        loop {
+            let finalized_slots = network.get_node_slots(service_ids, CommitmentConfig::finalized())
+                .context("An error occurred getting the nodes' finalized slots")?;
+            if finalized_slots.iter().all(|finalized_slot| *finalized_slot > slot) {
+                debug!("Nodes {:?} finalized slots {:?}, all past slot {}", service_ids, finalized_slots, slot);
+                return Ok(());
+            }
+            if start_time.elapsed() >= MAX_TIME_TO_FINALIZE_PAST_UPGRADE {
+                return Err(anyhow!(
+                    "Nodes {:?} were at finalized slots {:?} after {:?}, but expected all of them to be past slot {}",
+                    service_ids,
+                    finalized_slots,
+                    MAX_TIME_TO_FINALIZE_PAST_UPGRADE,
+                    slot,
+                ));
+            }
+            sleep(TIME_BETWEEN_FINALIZED_SLOT_CHECKS);
+        }
+    }
+
+    // Checks that the first num_upgraded bootstrappers report the new version and the rest still report the old one
+    fn verify_version_mix(
+        network: &SolanaNetwork,
+        service_ids: &[ServiceId],
+        num_upgraded: usize,
+        old_version: &RpcVersionInfo,
+        new_version: &RpcVersionInfo,
+    ) -> Result<()> {
+        let versions = network.get_node_versions(service_ids)
+            .context("An error occurred getting the bootstrappers' versions")?;
+        for (i, (service_id, version)) in service_ids.iter().zip(versions.iter()).enumerate() {
+            let expected_version = if i < num_upgraded { new_version } else { old_version };
+            if version != expected_version {
+                return Err(anyhow!(
+                    "Expected node '{}' to be running version {:?} with {} of {} nodes upgraded, but it reported {:?}",
+                    service_id,
+                    expected_version,
+                    num_upgraded,
+                    service_ids.len(),
+                    version,
+                ));
+            }
+        }
+        info!("{} of {} nodes report version {:?}; the rest report version {:?}", num_upgraded, service_ids.len(), new_version, old_version);
+        return Ok(());
+    }
+}
+
+impl Test for RollingUpgradeTest {
+    type N = SolanaNetwork;
+
+    fn get_test_configuration(&self) -> TestConfiguration {
+        return TestConfiguration{
+            is_partitioning_enabled: false,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
+        };
+    }
+
+    fn setup(&mut self, network_ctx: NetworkContext) -> Result<Box<SolanaNetwork>> {
+        let mut network = SolanaNetwork::new(
+            network_ctx,
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
+
+        network.start_faucet_and_bootstrappers()
+            .context("An error occurred starting the faucet and bootstrappers")?;
+
+        return Ok(Box::new(network));
+    }
+
+    fn run(&self, mut network: Box<SolanaNetwork>, _: TestContext) -> Result<()> {
+        self.verify_cluster_survives_any_one_bootstrapper_down(&network)?;
+
+        let num_bootstrappers = network.get_num_bootstrappers();
+        let service_ids: Vec<ServiceId> = (0..num_bootstrappers).map(SolanaNetwork::get_bootstrapper_service_id).collect();
+        let old_versions = network.get_node_versions(&service_ids)
+            .context("An error occurred getting the bootstrappers' versions before the upgrade")?;
+        let old_version = old_versions[0].clone();
+        if old_versions.iter().any(|version| *version != old_version) {
+            return Err(anyhow!(
+                "Expected every bootstrapper to be running the same version before the upgrade, but they reported {:?}",
+                old_versions,
+            ));
+        }
+        info!("All {} bootstrappers are running version {:?}", num_bootstrappers, old_version);
+
+        let mut new_version: Option<RpcVersionInfo> = None;
+        for i in 0..num_bootstrappers {
+            info!("--------------------- Upgrading Bootstrapper #{} ----------------------", i);
+            let other_service_ids: Vec<ServiceId> = service_ids.iter()
+                .filter(|service_id| **service_id != service_ids[i])
+                .cloned()
+                .collect();
+            let slot_before_upgrade = *network.get_node_slots(&other_service_ids, CommitmentConfig::finalized())
+                .context(format!("An error occurred getting the finalized slots before upgrading bootstrapper #{}", i))?
+                .iter()
+                .max()
+                .context("Expected at least one other bootstrapper to keep the cluster finalizing")?;
+
+            network.restart_bootstrapper_with_image(i, &self.upgrade_image)
+                .context(format!("An error occurred upgrading bootstrapper #{} to image '{}'", i, self.upgrade_image))?;
+            RollingUpgradeTest::wait_for_finalized_past(&network, &service_ids, slot_before_upgrade)
+                .context(format!("The cluster didn't keep finalizing through the upgrade of bootstrapper #{}", i))?;
+
+            let upgraded_version = match &new_version {
+                Some(version) => version.clone(),
+                None => {
+                    let version = network.get_bootstrapper(i)?.get_version()
+                        .context(format!("An error occurred getting the version of upgraded bootstrapper #{}", i))?;
+                    if version == old_version {
+                        return Err(anyhow!(
+                            "Image '{}' runs the same version as before the upgrade, {:?}",
+                            self.upgrade_image,
+                            version,
+                        ));
+                    }
+                    new_version = Some(version.clone());
+                    version
+                },
+            };
+            RollingUpgradeTest::verify_version_mix(&network, &service_ids, i + 1, &old_version, &upgraded_version)
+                .context(format!("The nodes didn't report the expected versions after upgrading bootstrapper #{}", i))?;
+        }
+        return Ok(());
+    }
+
+    fn get_setup_timeout(&self) -> Duration {
+        return Duration::from_secs(300);
+    }
+
+    fn get_execution_timeout(&self) -> Duration {
+        // Each bootstrapper gets stopped, restarted, caught up, and then has to finalize past where the cluster was
+        return Duration::from_secs(300 * self.network_config.get_num_bootstrappers() as u64);
+    }
+}
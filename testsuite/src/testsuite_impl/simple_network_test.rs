@@ -1,25 +1,42 @@
 use anyhow::{anyhow, Context, Result};
-use std::{collections::HashMap, thread::sleep, time::Duration};
+use std::{rc::Rc, thread::sleep, time::{Duration, Instant}};
 
 use kurtosis_rust_lib::{networks::network_context::NetworkContext, testsuite::{test::Test, test_configuration::TestConfiguration, test_context::TestContext}};
 
-use crate::{networks_impl::{solana_network::SolanaNetwork}, };
-
-use super::solana_testsuite::{LEDGER_DIR_ARTIFACT_KEY, LEDGER_DIR_ARTIFACT_URL};
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, solana_network::SolanaNetwork, solana_network_config::SolanaNetworkConfig}, sdk_impl::{keypair::Keypair, system_instruction, transaction::Transaction}, services_impl::validator::{commitment_config::CommitmentConfig, confirmation_tracker::{ConfirmationStage, ConfirmationTracker}}};
 
 // We don't always get new transactions produced every second, so we add a little pause to guarantee that we do
 const TIME_BETWEEN_TRANSACTION_COUNT_CHECKS: Duration = Duration::from_secs(2);
 
 const NUM_CHECK_ITERATIONS: u32 = 3;
 
+// Non-voting RPC nodes see confirmations a little after the voting nodes do, so they get a few seconds to catch up to the
+// bootstrapper's transaction count
+const MAX_RPC_NODE_SETTLE_TIME: Duration = Duration::from_secs(5);
+const TIME_BETWEEN_RPC_NODE_SETTLE_CHECKS: Duration = Duration::from_millis(500);
+
+const AIRDROP_LAMPORTS: u64 = 1_000_000;
+const AIRDROP_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+// The airdropped account sends this many transfers, to measure how long transactions take to get through each commitment level
+const NUM_TRACKED_TRANSFERS: u64 = 20;
+const TRACKED_TRANSFER_LAMPORTS: u64 = 1_000;
+const TRACKED_TRANSFERS_TIMEOUT: Duration = Duration::from_secs(90);
+// Finalization takes 32 confirmed blocks on top of the transaction's own, so anything much slower means the cluster is struggling
+const MAX_P99_FINALIZATION_LATENCY: Duration = Duration::from_secs(45);
+
 pub struct SimpleNetworkTest {
-    docker_image: String,
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
 }
 
 impl SimpleNetworkTest {
-    pub fn new(docker_image: String) -> SimpleNetworkTest {
+    pub fn new(genesis_config: Rc<GenesisConfig>, ledger_artifact: Rc<LedgerArtifact>, network_config: SolanaNetworkConfig) -> SimpleNetworkTest {
         return SimpleNetworkTest{
-            docker_image,
+            genesis_config,
+            ledger_artifact,
+            network_config,
         };
     }
 }
@@ -28,26 +45,24 @@ impl Test for SimpleNetworkTest {
     type N = SolanaNetwork;
 
     fn get_test_configuration(&self) -> kurtosis_rust_lib::testsuite::test_configuration::TestConfiguration {
-        let mut files_artifact_urls: HashMap<String, String> = HashMap::new();
-        files_artifact_urls.insert(
-            LEDGER_DIR_ARTIFACT_KEY.to_owned(), 
-            LEDGER_DIR_ARTIFACT_URL.to_owned(),
-        );
-    
         return TestConfiguration{ 
             is_partitioning_enabled: false, 
-            files_artifact_urls,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
         };
     }
 
     fn setup(&mut self, network_ctx: NetworkContext) -> Result<Box<SolanaNetwork>> {
         let mut network = SolanaNetwork::new(
             network_ctx, 
-            LEDGER_DIR_ARTIFACT_KEY.to_owned(), 
-        );
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
 
-        network.start_faucet_and_bootstrappers(&self.docker_image, &self.docker_image)
+        network.start_faucet_and_bootstrappers()
             .context("An error occurred starting the faucet and bootstrappers")?;
+        network.attach_rpc_nodes(self.network_config.get_num_rpc_nodes())
+            .context("An error occurred attaching the RPC nodes")?;
 
         return Ok(Box::new(network));
     }
@@ -65,9 +80,15 @@ impl Test for SimpleNetworkTest {
                 .context(format!("An error occurred asserting that we have the expected number of nodes, '{}'", expected_num_nodes))?;
             info!("Successfully asserted that the network has the correct number of nodes");
 
-            info!("RPC API: bootstrap-validator getTransactionCount ({})", i);
-            let bootstrapper_transaction_count = first_bootstrapper.get_confirmed_transaction_count()
-                .context("An error occurred getting the bootstrapper transaction count")?;
+            info!("RPC API: bootstrap-validator getSlot + getTransactionCount + getEpochInfo ({})", i);
+            let progress_sample = first_bootstrapper.get_progress_sample(Some(CommitmentConfig::confirmed()))
+                .context("An error occurred getting the bootstrapper progress sample")?;
+            info!(
+                "Bootstrapper is at slot {} in epoch {}",
+                progress_sample.slot,
+                progress_sample.epoch_info.epoch,
+            );
+            let bootstrapper_transaction_count = progress_sample.transaction_count;
 
             match last_bootstrapper_transaction_count_opt.as_ref() {
                 Some(last_transaction_count) => {
@@ -84,6 +105,30 @@ impl Test for SimpleNetworkTest {
             }
             last_bootstrapper_transaction_count_opt = Some(bootstrapper_transaction_count);
 
+            // Clients see the cluster through RPC nodes, so the RPC nodes must see the same progress the bootstrapper does
+            for j in 0..network.get_num_rpc_nodes() {
+                let rpc_node = network.get_rpc_node(j)?;
+                let settle_start_time = Instant::now();
+                loop {
+                    let rpc_node_progress_sample = rpc_node.get_progress_sample(Some(CommitmentConfig::confirmed()))
+                        .context(format!("An error occurred getting the progress sample of RPC node #{}", j))?;
+                    info!("RPC node #{} is at slot {} with transaction count {}", j, rpc_node_progress_sample.slot, rpc_node_progress_sample.transaction_count);
+                    if rpc_node_progress_sample.transaction_count >= bootstrapper_transaction_count {
+                        break;
+                    }
+                    if settle_start_time.elapsed() >= MAX_RPC_NODE_SETTLE_TIME {
+                        return Err(anyhow!(
+                            "RPC node #{} still has transaction count '{}' after {:?}, which is behind the bootstrapper's '{}' that was read before it",
+                            j,
+                            rpc_node_progress_sample.transaction_count,
+                            MAX_RPC_NODE_SETTLE_TIME,
+                            bootstrapper_transaction_count,
+                        ));
+                    }
+                    sleep(TIME_BETWEEN_RPC_NODE_SETTLE_CHECKS);
+                }
+            }
+
             // Wallet sanity check
             info!("Running wallet sanity check...");
             first_bootstrapper.run_wallet_sanity_check()
@@ -93,6 +138,55 @@ impl Test for SimpleNetworkTest {
             sleep(TIME_BETWEEN_TRANSACTION_COUNT_CHECKS);
         }
 
+        info!("Airdropping {} lamports to a fresh account...", AIRDROP_LAMPORTS);
+        let recipient_keypair = Keypair::new();
+        let recipient = recipient_keypair.pubkey();
+        let airdrop = first_bootstrapper.airdrop_and_confirm(&recipient, AIRDROP_LAMPORTS, CommitmentConfig::finalized(), AIRDROP_CONFIRMATION_TIMEOUT)
+            .context(format!("An error occurred airdropping {} lamports to '{}'", AIRDROP_LAMPORTS, recipient))?;
+        info!("Airdrop '{}' landed in slot {} and was finalized after {:?}", airdrop.signature, airdrop.slot, airdrop.elapsed);
+        let balance = first_bootstrapper.get_balance(&recipient, Some(CommitmentConfig::finalized()))
+            .context(format!("An error occurred getting the balance of airdrop recipient '{}'", recipient))?;
+        if balance != AIRDROP_LAMPORTS {
+            return Err(anyhow!(
+                "Expected airdrop recipient '{}' to have a balance of {} lamports, but it has {}",
+                recipient,
+                AIRDROP_LAMPORTS,
+                balance,
+            ));
+        }
+
+        info!("Sending {} transfers and tracking them through to finalization...", NUM_TRACKED_TRANSFERS);
+        let recent_blockhash = first_bootstrapper.get_recent_blockhash()
+            .context("An error occurred getting a recent blockhash for the transfers")?;
+        let mut tracker = ConfirmationTracker::new();
+        for _ in 0..NUM_TRACKED_TRANSFERS {
+            let instruction = system_instruction::transfer(&recipient, &Keypair::new().pubkey(), TRACKED_TRANSFER_LAMPORTS);
+            let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&recipient_keypair), &[], recent_blockhash)?;
+            tracker.send(first_bootstrapper, &transaction)
+                .context("An error occurred sending a tracked transfer")?;
+        }
+        tracker.wait_for_all(first_bootstrapper, TRACKED_TRANSFERS_TIMEOUT);
+        info!("Tracked transfers: {}", tracker);
+        if tracker.get_num_failed() > 0 || tracker.get_num_expired() > 0 {
+            return Err(anyhow!(
+                "Expected all {} tracked transfers to be finalized, but only {} were: {} failed and {} weren't finalized within {:?}",
+                tracker.get_num_sent(),
+                tracker.get_num_finalized(),
+                tracker.get_num_failed(),
+                tracker.get_num_expired(),
+                TRACKED_TRANSFERS_TIMEOUT,
+            ));
+        }
+        let p99_finalization_latency = tracker.get_latencies(ConfirmationStage::Finalized).get_p99()
+            .context("Expected the finalized transfers to have latencies")?;
+        if p99_finalization_latency > MAX_P99_FINALIZATION_LATENCY {
+            return Err(anyhow!(
+                "Expected the p99 finalization latency to be at most {:?}, but it was {:?}",
+                MAX_P99_FINALIZATION_LATENCY,
+                p99_finalization_latency,
+            ));
+        }
+
         return Ok(());
     }
 
@@ -101,6 +195,6 @@ impl Test for SimpleNetworkTest {
     }
 
     fn get_execution_timeout(&self) -> std::time::Duration {
-        return Duration::from_secs(240);
+        return Duration::from_secs(420);
     }
 }
\ No newline at end of file
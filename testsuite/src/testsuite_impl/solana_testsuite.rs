@@ -1,21 +1,38 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, rc::Rc};
 
 use kurtosis_rust_lib::testsuite::{dyn_test::DynTest, dyn_test_container::DynTestContainer, testsuite::TestSuite};
 use simple_network_test::SimpleNetworkTest;
 
-use super::{network_partition_test::NetworkPartitionTest, simple_network_test};
+use crate::networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, load_generator::LoadGeneratorConfig, solana_network_config::SolanaNetworkConfig};
 
-pub (super) const LEDGER_DIR_ARTIFACT_KEY: &str = "ledger-dir";
-pub (super) const LEDGER_DIR_ARTIFACT_URL: &str = "https://kurtosis-public-access.s3.us-east-1.amazonaws.com/client-artifacts/solana/test-ledger.tgz";
+use super::{late_joiner_test::LateJoinerTest, mixed_version_test::MixedVersionTest, network_partition_test::NetworkPartitionTest, node_restart_test::NodeRestartTest, rolling_upgrade_test::RollingUpgradeTest, simple_network_test, stake_partition_test::StakePartitionTest, throughput_test::ThroughputTest};
+
+// Leaves room for plenty of RPC nodes alongside the bootstrappers
+pub const NETWORK_WIDTH_BITS: u32 = 8;
 
 pub struct SolanaTestsuite {
-    normal_image: String,
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
+    // The load that the throughput test sends, and the confirmed TPS below which it fails
+    throughput_load_config: LoadGeneratorConfig,
+    throughput_min_tps: u64,
 }
 
 impl SolanaTestsuite {
-    pub fn new(normal_image: String) -> SolanaTestsuite {
+    pub fn new(
+        genesis_config: Rc<GenesisConfig>,
+        ledger_artifact: Rc<LedgerArtifact>,
+        network_config: SolanaNetworkConfig,
+        throughput_load_config: LoadGeneratorConfig,
+        throughput_min_tps: u64,
+    ) -> SolanaTestsuite {
         return SolanaTestsuite {
-            normal_image,
+            genesis_config,
+            ledger_artifact,
+            network_config,
+            throughput_load_config,
+            throughput_min_tps,
         }
     }
 }
@@ -25,7 +42,9 @@ impl TestSuite for SolanaTestsuite {
         let mut result: HashMap<String, Box<dyn DynTest>> = HashMap::new();
 
         let simple_network_test = SimpleNetworkTest::new(
-            self.normal_image.clone(), 
+            self.genesis_config.clone(),
+            self.ledger_artifact.clone(),
+            self.network_config.clone(),
         );
         let simple_network_test_container = DynTestContainer::new(simple_network_test);
         result.insert(
@@ -34,7 +53,9 @@ impl TestSuite for SolanaTestsuite {
         );
 
         let network_partition_test = NetworkPartitionTest::new(
-            self.normal_image.clone(), 
+            self.genesis_config.clone(),
+            self.ledger_artifact.clone(),
+            self.network_config.clone(),
         );
         let network_partition_test_container = DynTestContainer::new(network_partition_test);
         result.insert(
@@ -42,10 +63,85 @@ impl TestSuite for SolanaTestsuite {
             Box::new(network_partition_test_container)
         );
 
+        let late_joiner_test = LateJoinerTest::new(
+            self.genesis_config.clone(),
+            self.ledger_artifact.clone(),
+            self.network_config.clone(),
+        );
+        let late_joiner_test_container = DynTestContainer::new(late_joiner_test);
+        result.insert(
+            String::from("lateJoinerTest"),
+            Box::new(late_joiner_test_container)
+        );
+
+        let stake_partition_test = StakePartitionTest::new(
+            self.genesis_config.clone(),
+            self.ledger_artifact.clone(),
+            self.network_config.clone(),
+        );
+        let stake_partition_test_container = DynTestContainer::new(stake_partition_test);
+        result.insert(
+            String::from("stakePartitionTest"),
+            Box::new(stake_partition_test_container)
+        );
+
+        let node_restart_test = NodeRestartTest::new(
+            self.genesis_config.clone(),
+            self.ledger_artifact.clone(),
+            self.network_config.clone(),
+        );
+        let node_restart_test_container = DynTestContainer::new(node_restart_test);
+        result.insert(
+            String::from("nodeRestartTest"),
+            Box::new(node_restart_test_container)
+        );
+
+        let throughput_test = ThroughputTest::new(
+            self.genesis_config.clone(),
+            self.ledger_artifact.clone(),
+            self.network_config.clone(),
+            self.throughput_load_config.clone(),
+            self.throughput_min_tps,
+        );
+        let throughput_test_container = DynTestContainer::new(throughput_test);
+        result.insert(
+            String::from("throughputTest"),
+            Box::new(throughput_test_container)
+        );
+
+        // The bootstrappers all run the same version unless they were given their own images
+        if self.network_config.has_mixed_bootstrapper_images() {
+            let mixed_version_test = MixedVersionTest::new(
+                self.genesis_config.clone(),
+                self.ledger_artifact.clone(),
+                self.network_config.clone(),
+            );
+            let mixed_version_test_container = DynTestContainer::new(mixed_version_test);
+            result.insert(
+                String::from("mixedVersionTest"),
+                Box::new(mixed_version_test_container)
+            );
+        }
+
+        // There's nothing to upgrade to unless an upgrade image was given
+        if let Some(upgrade_image) = self.network_config.get_upgrade_image() {
+            let rolling_upgrade_test = RollingUpgradeTest::new(
+                self.genesis_config.clone(),
+                self.ledger_artifact.clone(),
+                self.network_config.clone(),
+                upgrade_image.to_owned(),
+            );
+            let rolling_upgrade_test_container = DynTestContainer::new(rolling_upgrade_test);
+            result.insert(
+                String::from("rollingUpgradeTest"),
+                Box::new(rolling_upgrade_test_container)
+            );
+        }
+
         return result;
     }
 
     fn get_network_width_bits(&self) -> u32 {
-        return 8;
+        return NETWORK_WIDTH_BITS;
     }
 }
\ No newline at end of file
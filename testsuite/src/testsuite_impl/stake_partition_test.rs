@@ -0,0 +1,140 @@
+use anyhow::{anyhow, Context, Result};
+use std::{rc::Rc, thread::sleep, time::Duration};
+
+use kurtosis_rust_lib::{networks::network_context::NetworkContext, testsuite::{test::Test, test_configuration::TestConfiguration, test_context::TestContext}};
+
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, solana_network::{SolanaNetwork, StakeSource}, solana_network_config::SolanaNetworkConfig, stake_partition::{STAKE_SIDE_A_PARTITION_ID_STR, STAKE_SIDE_B_PARTITION_ID_STR, StakePartition}}, services_impl::validator::commitment_config::CommitmentConfig};
+
+// Side A gets less than 1/3 of the stake, so side B keeps a supermajority (as long as enough bootstrappers are running)
+const SIDE_A_STAKE_FRACTION: f64 = 0.3;
+
+// Long enough for the slots that were in flight when the partition went up to get finalized (or not)
+const PARTITION_SETTLE_TIME: Duration = Duration::from_secs(30);
+// Long enough for a side that still has a supermajority to root several more slots
+const TIME_BETWEEN_ROOT_CHECKS: Duration = Duration::from_secs(15);
+
+// Checks that when the cluster is split by stake, only a side with a 2/3 supermajority keeps rooting slots
+pub struct StakePartitionTest {
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
+}
+
+impl StakePartitionTest {
+    pub fn new(genesis_config: Rc<GenesisConfig>, ledger_artifact: Rc<LedgerArtifact>, network_config: SolanaNetworkConfig) -> StakePartitionTest {
+        return StakePartitionTest{
+            genesis_config,
+            ledger_artifact,
+            network_config,
+        };
+    }
+
+    // Applies the partition, checks that each side is or isn't rooting according to its stake, then heals the partition
+    fn run_partition_round(network: &mut SolanaNetwork, plan: &StakePartition) -> Result<()> {
+        info!(
+            "Partitioning network with {} stake on side A and {} stake on side B, out of {} total; supermajority side: {:?}",
+            plan.get_side_a_stake(),
+            plan.get_side_b_stake(),
+            plan.get_total_stake(),
+            plan.get_supermajority_side(),
+        );
+        network.repartition(plan.get_partition())
+            .context("An error occurred applying the stake partition")?;
+        info!("Network partitioned; waiting {:?} for it to settle...", PARTITION_SETTLE_TIME);
+        sleep(PARTITION_SETTLE_TIME);
+
+        for side_partition_id in &[STAKE_SIDE_A_PARTITION_ID_STR, STAKE_SIDE_B_PARTITION_ID_STR] {
+            info!("Verifying rooting on side '{}'...", side_partition_id);
+            StakePartitionTest::verify_side_rooting(network, plan, side_partition_id)
+                .context(format!("An error occurred verifying rooting on side '{}'", side_partition_id))?;
+            info!("Side '{}' is rooting as expected", side_partition_id);
+        }
+
+        info!("Healing partition...");
+        network.heal_partition()
+            .context("An error occurred healing the stake partition")?;
+        info!("Partition healed");
+        return Ok(());
+    }
+
+    // Checks that every node on the side is rooting slots if the side has a supermajority, and that none of them are if not
+    fn verify_side_rooting(network: &SolanaNetwork, plan: &StakePartition, side_partition_id: &str) -> Result<()> {
+        let should_be_rooting = plan.get_supermajority_side() == Some(side_partition_id);
+        let service_ids = plan.get_side_service_ids(side_partition_id)?;
+        let slots_before = network.get_node_slots(&service_ids, CommitmentConfig::finalized())
+            .context(format!("An error occurred getting the finalized slots of side '{}'", side_partition_id))?;
+        sleep(TIME_BETWEEN_ROOT_CHECKS);
+        let slots_after = network.get_node_slots(&service_ids, CommitmentConfig::finalized())
+            .context(format!("An error occurred getting the finalized slots of side '{}'", side_partition_id))?;
+        for ((service_id, slot_before), slot_after) in service_ids.iter().zip(slots_before).zip(slots_after) {
+            let is_rooting = slot_after > slot_before;
+            debug!("Node '{}' finalized slot went from {} to {}", service_id, slot_before, slot_after);
+            if is_rooting != should_be_rooting {
+                return Err(anyhow!(
+                    "Expected node '{}' on side '{}' to be {} but its finalized slot went from {} to {} in {:?}",
+                    service_id,
+                    side_partition_id,
+                    if should_be_rooting { "rooting" } else { "stalled" },
+                    slot_before,
+                    slot_after,
+                    TIME_BETWEEN_ROOT_CHECKS,
+                ));
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl Test for StakePartitionTest {
+    type N = SolanaNetwork;
+
+    fn get_test_configuration(&self) -> TestConfiguration {
+        return TestConfiguration{
+            is_partitioning_enabled: true,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
+        };
+    }
+
+    fn setup(&mut self, network_ctx: NetworkContext) -> Result<Box<SolanaNetwork>> {
+        let mut network = SolanaNetwork::new(
+            network_ctx,
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
+
+        network.start_faucet_and_bootstrappers()
+            .context("An error occurred starting the faucet and bootstrappers")?;
+
+        return Ok(Box::new(network));
+    }
+
+    fn run(&self, mut network: Box<SolanaNetwork>, _: TestContext) -> Result<()> {
+        let live_stake_source = StakeSource::Live(CommitmentConfig::finalized());
+        let node_stakes = network.get_node_stakes(live_stake_source)
+            .context("An error occurred getting the nodes' stakes")?;
+        info!("Node stakes: {:?}", node_stakes);
+
+        info!("--------------------- Minority/Majority Partition ----------------------");
+        let plan = network.plan_stake_partition(SIDE_A_STAKE_FRACTION, live_stake_source)
+            .context("An error occurred planning the minority/majority stake partition")?;
+        StakePartitionTest::run_partition_round(&mut network, &plan)
+            .context("An error occurred running the minority/majority stake partition round")?;
+
+        // Every side falls short of a supermajority, so the whole cluster should stall
+        info!("--------------------- Below-Supermajority Partition ----------------------");
+        let plan = network.plan_stake_partition_below_supermajority(StakeSource::Genesis)
+            .context("An error occurred planning the below-supermajority stake partition")?;
+        StakePartitionTest::run_partition_round(&mut network, &plan)
+            .context("An error occurred running the below-supermajority stake partition round")?;
+        return Ok(());
+    }
+
+    fn get_setup_timeout(&self) -> Duration {
+        return Duration::from_secs(300);
+    }
+
+    fn get_execution_timeout(&self) -> Duration {
+        return Duration::from_secs(480);
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Context, Result};
+use std::{rc::Rc, time::Duration};
+
+use kurtosis_rust_lib::{networks::network_context::NetworkContext, testsuite::{test::Test, test_configuration::TestConfiguration, test_context::TestContext}};
+
+use crate::{networks_impl::{genesis_config::GenesisConfig, ledger_artifact::LedgerArtifact, load_generator::{LoadGenerator, LoadGeneratorConfig}, solana_network::SolanaNetwork, solana_network_config::SolanaNetworkConfig}, services_impl::validator::validator_service::ValidatorService};
+
+// Funding the accounts, plus draining the transactions still in flight after the load stops
+const NON_LOAD_EXECUTION_TIME: Duration = Duration::from_secs(240);
+
+// Sends transfers at a target rate across every bootstrapper's RPC endpoint, and fails if the rate at which they get
+// confirmed falls below a floor
+pub struct ThroughputTest {
+    genesis_config: Rc<GenesisConfig>,
+    ledger_artifact: Rc<LedgerArtifact>,
+    network_config: SolanaNetworkConfig,
+    load_config: LoadGeneratorConfig,
+    min_confirmed_tps: u64,
+}
+
+impl ThroughputTest {
+    pub fn new(
+        genesis_config: Rc<GenesisConfig>,
+        ledger_artifact: Rc<LedgerArtifact>,
+        network_config: SolanaNetworkConfig,
+        load_config: LoadGeneratorConfig,
+        min_confirmed_tps: u64,
+    ) -> ThroughputTest {
+        return ThroughputTest{
+            genesis_config,
+            ledger_artifact,
+            network_config,
+            load_config,
+            min_confirmed_tps,
+        };
+    }
+}
+
+impl Test for ThroughputTest {
+    type N = SolanaNetwork;
+
+    fn get_test_configuration(&self) -> TestConfiguration {
+        return TestConfiguration{
+            is_partitioning_enabled: false,
+            files_artifact_urls: self.ledger_artifact.get_files_artifact_urls(),
+        };
+    }
+
+    fn setup(&mut self, network_ctx: NetworkContext) -> Result<Box<SolanaNetwork>> {
+        let mut network = SolanaNetwork::new(
+            network_ctx,
+            self.ledger_artifact.clone(),
+            self.genesis_config.clone(),
+            self.network_config.clone(),
+        ).context("An error occurred creating the Solana network")?;
+
+        network.start_faucet_and_bootstrappers()
+            .context("An error occurred starting the faucet and bootstrappers")?;
+
+        return Ok(Box::new(network));
+    }
+
+    fn run(&self, network: Box<SolanaNetwork>, _: TestContext) -> Result<()> {
+        let faucet = network.get_faucet()?;
+        let mut bootstrappers: Vec<&ValidatorService> = Vec::new();
+        for i in 0..network.get_num_bootstrappers() {
+            bootstrappers.push(network.get_bootstrapper(i)?);
+        }
+
+        let load_generator = LoadGenerator::new(self.load_config.clone());
+        info!("Funding {} load-generation accounts from the faucet...", self.load_config.num_accounts);
+        load_generator.fund_accounts(faucet, bootstrappers[0])
+            .context("An error occurred funding the load-generation accounts")?;
+
+        let report = load_generator.run(&bootstrappers)
+            .context("An error occurred generating load")?;
+        info!("Load report: {}", report);
+
+        let confirmed_tps = report.get_confirmed_tps();
+        if confirmed_tps < self.min_confirmed_tps as f64 {
+            return Err(anyhow!(
+                "Expected at least {} confirmed TPS at a target of {} TPS, but only got {:.1}: {}",
+                self.min_confirmed_tps,
+                self.load_config.target_tps,
+                confirmed_tps,
+                report,
+            ));
+        }
+        return Ok(());
+    }
+
+    fn get_setup_timeout(&self) -> Duration {
+        return Duration::from_secs(300);
+    }
+
+    fn get_execution_timeout(&self) -> Duration {
+        return NON_LOAD_EXECUTION_TIME + self.load_config.duration;
+    }
+}